@@ -162,27 +162,87 @@ pub async fn create_chunks(
     let mut chunks = Vec::new();
 
     for (index, region) in regions.iter().enumerate() {
-        let chunk_path = output_dir.join(format!("chunk_{:04}.wav", index));
-
-        debug!(
-            "Creating chunk {}: {:?} to {:?}",
-            index, region.start, region.end
-        );
-
-        let _metadata =
-            extract_audio_segment(source_audio, &chunk_path, region.start, region.end).await?;
-
-        chunks.push(AudioChunk {
-            region: region.clone(),
-            path: chunk_path,
-            index,
-        });
+        chunks.push(create_chunk(source_audio, region, index, output_dir).await?);
     }
 
     info!("Created {} audio chunks", chunks.len());
     Ok(chunks)
 }
 
+/// Path of the chunk file for the given index.
+pub fn chunk_path(output_dir: &Path, index: usize) -> PathBuf {
+    output_dir.join(format!("chunk_{:04}.wav", index))
+}
+
+/// Create a single audio chunk file.
+pub async fn create_chunk(
+    source_audio: &Path,
+    region: &SpeechRegion,
+    index: usize,
+    output_dir: &Path,
+) -> Result<AudioChunk> {
+    let chunk_path = chunk_path(output_dir, index);
+
+    debug!(
+        "Creating chunk {}: {:?} to {:?}",
+        index, region.start, region.end
+    );
+
+    let _metadata =
+        extract_audio_segment(source_audio, &chunk_path, region.start, region.end).await?;
+
+    Ok(AudioChunk {
+        region: region.clone(),
+        path: chunk_path,
+        index,
+    })
+}
+
+/// Split a chunk into two halves, writing each half next to the chunk file.
+///
+/// Returns `None` when the halves would be shorter than `min_half`.
+pub async fn split_chunk(
+    chunk: &AudioChunk,
+    min_half: Duration,
+) -> Result<Option<(AudioChunk, AudioChunk)>> {
+    let Some((first, second)) = chunk.region.split_in_half(min_half) else {
+        return Ok(None);
+    };
+
+    let first = extract_subchunk(chunk, first, "a").await?;
+    let second = extract_subchunk(chunk, second, "b").await?;
+    Ok(Some((first, second)))
+}
+
+/// Cut `region` (in file time, within the chunk) out of a chunk into a file
+/// named after the chunk with `suffix` appended.
+pub async fn extract_subchunk(
+    chunk: &AudioChunk,
+    region: SpeechRegion,
+    suffix: &str,
+) -> Result<AudioChunk> {
+    let stem = chunk
+        .path
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_else(|| format!("chunk_{:04}", chunk.index));
+    let path = chunk.path.with_file_name(format!("{}{}.wav", stem, suffix));
+
+    extract_audio_segment(
+        &chunk.path,
+        &path,
+        region.start.saturating_sub(chunk.region.start),
+        region.end.saturating_sub(chunk.region.start),
+    )
+    .await?;
+
+    Ok(AudioChunk {
+        region,
+        path,
+        index: chunk.index,
+    })
+}
+
 /// Clean up chunk files.
 pub fn cleanup_chunks(chunks: &[AudioChunk]) -> Result<()> {
     for chunk in chunks {
@@ -318,6 +378,22 @@ mod tests {
         assert_eq!(size, expected);
     }
 
+    #[test]
+    fn test_split_region_in_half() {
+        let region = SpeechRegion {
+            start: Duration::from_secs(60),
+            end: Duration::from_secs(120),
+        };
+
+        let (first, second) = region.split_in_half(Duration::from_secs(10)).unwrap();
+        assert_eq!(first.end, Duration::from_secs(90));
+        assert_eq!(second.start, Duration::from_secs(90));
+        assert_eq!(second.end, Duration::from_secs(120));
+        assert_eq!(region.to_string(), "00:01:00-00:02:00");
+
+        assert!(region.split_in_half(Duration::from_secs(31)).is_none());
+    }
+
     #[test]
     fn test_get_temp_chunk_dir() {
         let dir = get_temp_chunk_dir();
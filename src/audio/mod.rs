@@ -3,7 +3,8 @@ pub mod extract;
 pub mod vad;
 
 pub use chunk::{
-    cleanup_chunks, create_chunks, estimate_wav_size, get_temp_chunk_dir, plan_chunks, ChunkConfig,
+    chunk_path, cleanup_chunks, create_chunk, create_chunks, estimate_wav_size, extract_subchunk,
+    get_temp_chunk_dir, plan_chunks, split_chunk, ChunkConfig,
 };
 pub use extract::{
     check_ffmpeg, check_ffprobe, extract_audio, extract_audio_segment, extract_audio_with_progress,
@@ -11,6 +12,7 @@ pub use extract::{
 };
 pub use vad::{detect_speech_regions, has_speech, total_speech_duration, VadConfig};
 
+use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use std::time::Duration;
 
@@ -23,7 +25,7 @@ pub struct AudioMetadata {
 }
 
 /// A region of speech detected in audio.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SpeechRegion {
     pub start: Duration,
     pub end: Duration,
@@ -34,6 +36,40 @@ impl SpeechRegion {
     pub fn duration(&self) -> Duration {
         self.end.saturating_sub(self.start)
     }
+
+    /// Split the region into two halves, unless the halves would be shorter than `min_half`.
+    pub fn split_in_half(&self, min_half: Duration) -> Option<(SpeechRegion, SpeechRegion)> {
+        let half = self.duration() / 2;
+        if half < min_half {
+            return None;
+        }
+        let middle = self.start + half;
+        Some((
+            SpeechRegion {
+                start: self.start,
+                end: middle,
+            },
+            SpeechRegion {
+                start: middle,
+                end: self.end,
+            },
+        ))
+    }
+}
+
+impl std::fmt::Display for SpeechRegion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let hms = |d: Duration| {
+            let secs = d.as_secs();
+            format!(
+                "{:02}:{:02}:{:02}",
+                secs / 3600,
+                (secs % 3600) / 60,
+                secs % 60
+            )
+        };
+        write!(f, "{}-{}", hms(self.start), hms(self.end))
+    }
 }
 
 /// A chunk of audio ready for transcription.
@@ -1,8 +1,11 @@
 use crate::error::{AutosubError, Result};
-use serde::{Deserialize, Serialize};
+use crate::http::HttpConfig;
+use crate::keys::{split_keys, ApiKeyPool};
+use crate::usage::{ModelPrice, PriceTable};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::HashMap;
 use std::path::PathBuf;
-
-
+use std::process::Command;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -49,19 +52,134 @@ impl OutputFormat {
     }
 }
 
+/// One transcription provider in the fallback chain.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProviderConfig {
+    /// Provider type. Only `gemini` is currently supported.
+    pub provider: String,
+    #[serde(default)]
+    pub model: Option<String>,
+    /// API key for this provider; defaults to `gemini_api_key`.
+    #[serde(default)]
+    pub api_key: Option<String>,
+    /// Alternative endpoint, e.g. a regional proxy.
+    #[serde(default)]
+    pub base_url: Option<String>,
+    /// Request token log probabilities to score segment confidence.
+    #[serde(default)]
+    pub logprobs: bool,
+}
+
+impl ProviderConfig {
+    /// Gemini with the default model and key.
+    pub fn gemini() -> Self {
+        Self {
+            provider: "gemini".to_string(),
+            model: None,
+            api_key: None,
+            base_url: None,
+            logprobs: false,
+        }
+    }
+}
+
+/// Translation providers that can be selected by name.
+pub const TRANSLATORS: [&str; 4] = ["gemini", "deepl", "libretranslate", "openai"];
+
+/// The translation provider, selected by name in the `[translator]` table.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TranslatorConfig {
+    /// Provider type: `gemini`, `deepl`, `libretranslate` or `openai`
+    /// (any OpenAI-compatible chat completions API).
+    pub provider: String,
+    /// Model for Gemini and chat providers; defaults to the provider's own default.
+    #[serde(default)]
+    pub model: Option<String>,
+    /// API key; defaults to the provider's environment variable, or for Gemini to `gemini_api_key`.
+    #[serde(default)]
+    pub api_key: Option<String>,
+    /// Alternative endpoint, e.g. DeepL Pro, a self-hosted LibreTranslate or a local chat server.
+    #[serde(default)]
+    pub base_url: Option<String>,
+}
+
+impl TranslatorConfig {
+    /// The provider called `provider` with its default model, key and endpoint.
+    pub fn named(provider: &str) -> Self {
+        Self {
+            provider: provider.to_lowercase(),
+            model: None,
+            api_key: None,
+            base_url: None,
+        }
+    }
+
+    /// Environment variable holding the provider's API key, if it has its own.
+    pub fn key_env(&self) -> Option<&'static str> {
+        match self.provider.as_str() {
+            "deepl" => Some("DEEPL_API_KEY"),
+            "libretranslate" => Some("LIBRETRANSLATE_API_KEY"),
+            "openai" => Some("OPENAI_API_KEY"),
+            _ => None,
+        }
+    }
+
+    /// Take the API key from the provider's environment variable if none is set.
+    fn with_env_key(mut self) -> Self {
+        if self.api_key.is_none() {
+            self.api_key = self.key_env().and_then(|name| std::env::var(name).ok());
+        }
+        self
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
+    /// One key, several comma-separated keys, or a list of keys in config.toml.
+    #[serde(default, deserialize_with = "deserialize_api_keys")]
     pub gemini_api_key: Option<String>,
+    /// Command printing API keys, one per line, used when no key is set.
+    #[serde(default)]
+    pub gemini_api_key_command: Option<String>,
     pub default_format: OutputFormat,
     pub concurrency: usize,
+    /// Per-model prices (USD per million tokens) overriding the built-in table.
+    #[serde(default)]
+    pub pricing: HashMap<String, ModelPrice>,
+    /// Transcription providers tried in order when one fails.
+    #[serde(default)]
+    pub transcription_providers: Vec<ProviderConfig>,
+    /// Template file replacing the built-in transcription prompt.
+    #[serde(default)]
+    pub transcription_prompt: Option<PathBuf>,
+    /// Template file replacing the built-in translation prompt.
+    #[serde(default)]
+    pub translation_prompt: Option<PathBuf>,
+    /// Glossary file fixing how terms are translated.
+    #[serde(default)]
+    pub glossary: Option<PathBuf>,
+    /// Translation provider; Gemini with the default model when not set.
+    #[serde(default)]
+    pub translator: Option<TranslatorConfig>,
+    /// Timeouts, proxy, CA bundle and user agent for provider requests.
+    #[serde(default)]
+    pub http: HttpConfig,
 }
 
 impl Default for Config {
     fn default() -> Self {
         Self {
             gemini_api_key: None,
+            gemini_api_key_command: None,
             default_format: OutputFormat::default(),
             concurrency: 4,
+            pricing: HashMap::new(),
+            transcription_providers: Vec::new(),
+            transcription_prompt: None,
+            translation_prompt: None,
+            glossary: None,
+            translator: None,
+            http: HttpConfig::default(),
         }
     }
 }
@@ -95,31 +213,173 @@ impl Config {
             }
         }
 
+        if config.gemini_api_key.is_none() {
+            config.run_api_key_command()?;
+        }
+        config.translator = config.translator.map(TranslatorConfig::with_env_key);
+
         Ok(config)
     }
 
+    /// Translate with the provider called `name`, keeping its configured settings if
+    /// it is the one already configured.
+    pub fn select_translator(&mut self, name: &str) {
+        let name = name.to_lowercase();
+        if self.translator.as_ref().is_some_and(|t| t.provider == name) {
+            return;
+        }
+        self.translator = Some(TranslatorConfig::named(&name).with_env_key());
+    }
+
+    /// The translator in use without its API key, as recorded for resuming a job.
+    pub fn translator_settings(&self) -> TranslatorConfig {
+        TranslatorConfig {
+            api_key: None,
+            ..self
+                .translator
+                .clone()
+                .unwrap_or_else(|| TranslatorConfig::named("gemini"))
+        }
+    }
+
+    /// Translate with the provider, model and endpoint a job was started with,
+    /// taking the API key from the current configuration.
+    pub fn restore_translator(&mut self, settings: &TranslatorConfig) {
+        self.select_translator(&settings.provider);
+        if let Some(ref mut translator) = self.translator {
+            translator.model = settings.model.clone();
+            translator.base_url = settings.base_url.clone();
+        }
+    }
+
+    /// Name of the translation provider in use.
+    pub fn translator_name(&self) -> &str {
+        self.translator
+            .as_ref()
+            .map_or("gemini", |t| t.provider.as_str())
+    }
+
+    /// Fill `gemini_api_key` with the keys printed by `gemini_api_key_command`.
+    pub fn run_api_key_command(&mut self) -> Result<()> {
+        let Some(ref command) = self.gemini_api_key_command else {
+            return Ok(());
+        };
+
+        let output = if cfg!(windows) {
+            Command::new("cmd").arg("/C").arg(command).output()
+        } else {
+            Command::new("sh").arg("-c").arg(command).output()
+        }
+        .map_err(|e| AutosubError::Config(format!("Cannot run gemini_api_key_command: {}", e)))?;
+
+        if !output.status.success() {
+            return Err(AutosubError::Config(format!(
+                "gemini_api_key_command failed ({}): {}",
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+
+        let keys = split_keys(&String::from_utf8_lossy(&output.stdout));
+        if keys.is_empty() {
+            return Err(AutosubError::Config(
+                "gemini_api_key_command printed no API keys".to_string(),
+            ));
+        }
+        self.gemini_api_key = Some(keys.join(","));
+        Ok(())
+    }
+
+    /// The configured Gemini API keys.
+    pub fn api_keys(&self) -> Vec<String> {
+        self.gemini_api_key
+            .as_deref()
+            .map(split_keys)
+            .unwrap_or_default()
+    }
+
+    /// A pool rotating over the configured Gemini API keys, if any are set.
+    pub fn key_pool(&self) -> Option<ApiKeyPool> {
+        let pool = ApiKeyPool::new(self.api_keys());
+        (!pool.is_empty()).then_some(pool)
+    }
+
     pub fn validate(&self) -> Result<()> {
-        if self.gemini_api_key.is_none() {
+        let providers_have_keys = !self.transcription_providers.is_empty()
+            && self
+                .transcription_providers
+                .iter()
+                .all(|p| p.api_key.is_some());
+        if self.api_keys().is_empty() && !providers_have_keys {
+            return Err(AutosubError::Config(
+                "GEMINI_API_KEY not set. Get one at https://aistudio.google.com/apikey".to_string(),
+            ));
+        }
+
+        self.validate_common()
+    }
+
+    /// Check the settings translating a subtitle file needs. Unlike transcription,
+    /// it needs no Gemini key unless Gemini translates.
+    pub fn validate_translation(&self) -> Result<()> {
+        let gemini_key = self.translator.as_ref().and_then(|t| t.api_key.as_ref());
+        if self.translator_name() == "gemini" && gemini_key.is_none() && self.api_keys().is_empty()
+        {
             return Err(AutosubError::Config(
-                "GEMINI_API_KEY not set. Get one at https://aistudio.google.com/apikey"
-                    .to_string(),
+                "GEMINI_API_KEY not set. Get one at https://aistudio.google.com/apikey".to_string(),
             ));
         }
 
+        self.validate_common()
+    }
+
+    fn validate_common(&self) -> Result<()> {
         if self.concurrency == 0 {
             return Err(AutosubError::Config(
                 "Concurrency must be greater than 0".to_string(),
             ));
         }
 
+        // Surface a bad proxy URL or CA bundle before any work starts
+        self.http.build_client()?;
+
         Ok(())
     }
 
+    /// Prices for cost estimates, with configured overrides applied.
+    pub fn price_table(&self) -> PriceTable {
+        PriceTable::new(&self.pricing)
+    }
+
+    /// Directory holding autosub's config file and on-disk state.
+    pub fn config_dir() -> Option<PathBuf> {
+        dirs::config_dir().map(|p| p.join("autosub"))
+    }
+
     fn config_file_path() -> Option<PathBuf> {
-        dirs::config_dir().map(|p| p.join("autosub").join("config.toml"))
+        Self::config_dir().map(|p| p.join("config.toml"))
     }
 }
 
+/// Accept `gemini_api_key` as a string or a list of strings.
+fn deserialize_api_keys<'de, D>(deserializer: D) -> std::result::Result<Option<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum ApiKeys {
+        One(String),
+        Many(Vec<String>),
+    }
+
+    Ok(match Option::<ApiKeys>::deserialize(deserializer)? {
+        Some(ApiKeys::One(key)) => Some(key),
+        Some(ApiKeys::Many(keys)) => Some(keys.join(",")),
+        None => None,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -152,10 +412,134 @@ mod tests {
         assert!(config.validate().is_err());
     }
 
+    #[test]
+    fn test_pricing_from_toml() {
+        let config: Config = toml::from_str(
+            r#"
+            default_format = "srt"
+            concurrency = 4
+
+            [pricing."gemini-2.5-flash"]
+            input_per_million = 0.5
+            output_per_million = 1.5
+            "#,
+        )
+        .unwrap();
+
+        let table = config.price_table();
+        assert_eq!(
+            table.price("gemini-2.5-flash").unwrap().input_per_million,
+            0.5
+        );
+        assert!(table.price("gemini-2.0-flash").is_some());
+    }
+
+    #[test]
+    fn test_api_key_list() {
+        let config: Config = toml::from_str(
+            r#"
+            gemini_api_key = ["key-a", "key-b"]
+            default_format = "srt"
+            concurrency = 4
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.api_keys(), ["key-a", "key-b"]);
+        assert_eq!(config.key_pool().unwrap().len(), 2);
+
+        let config = Config {
+            gemini_api_key: Some("key-a, key-b,key-c".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(config.api_keys().len(), 3);
+        assert!(Config::default().key_pool().is_none());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_api_key_command() {
+        let mut config = Config {
+            gemini_api_key_command: Some("printf 'key-a\\nkey-b\\n'".to_string()),
+            ..Default::default()
+        };
+        config.run_api_key_command().unwrap();
+        assert_eq!(config.api_keys(), ["key-a", "key-b"]);
+
+        let mut config = Config {
+            gemini_api_key_command: Some("exit 3".to_string()),
+            ..Default::default()
+        };
+        assert!(config.run_api_key_command().is_err());
+    }
+
+    #[test]
+    fn test_translator_from_toml() {
+        let mut config: Config = toml::from_str(
+            r#"
+            default_format = "srt"
+            concurrency = 4
+
+            [translator]
+            provider = "libretranslate"
+            base_url = "http://localhost:5000"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.translator_name(), "libretranslate");
+        // A translator that does not use Gemini needs no Gemini key
+        assert!(config.validate_translation().is_ok());
+        assert!(config.validate().is_err());
+
+        // Selecting the configured provider keeps its settings
+        config.select_translator("LibreTranslate");
+        assert_eq!(
+            config.translator.as_ref().unwrap().base_url.as_deref(),
+            Some("http://localhost:5000")
+        );
+        config.select_translator("gemini");
+        assert_eq!(config.translator, Some(TranslatorConfig::named("gemini")));
+        assert!(config.validate_translation().is_err());
+
+        assert_eq!(Config::default().translator_name(), "gemini");
+    }
+
+    #[test]
+    fn test_restore_translator() {
+        let mut config = Config {
+            translator: Some(TranslatorConfig {
+                model: Some("gpt-4o".to_string()),
+                api_key: Some("sk-secret".to_string()),
+                base_url: Some("http://localhost:8080/v1".to_string()),
+                ..TranslatorConfig::named("openai")
+            }),
+            ..Default::default()
+        };
+        let settings = config.translator_settings();
+        assert_eq!(settings.api_key, None);
+        assert_eq!(settings.model.as_deref(), Some("gpt-4o"));
+
+        // A resumed job keeps its provider and model, with the key configured now
+        let mut resumed = Config::default();
+        resumed.restore_translator(&settings);
+        assert_eq!(resumed.translator_name(), "openai");
+        assert_eq!(resumed.translator_settings(), settings);
+
+        config.translator = Some(TranslatorConfig {
+            model: Some("gpt-4o-mini".to_string()),
+            ..config.translator.unwrap()
+        });
+        config.restore_translator(&settings);
+        let translator = config.translator.unwrap();
+        assert_eq!(translator.model.as_deref(), Some("gpt-4o"));
+        assert_eq!(translator.api_key.as_deref(), Some("sk-secret"));
+    }
+
     #[test]
     fn test_validate_with_api_key() {
-        let mut config = Config::default();
-        config.gemini_api_key = Some("test-key".to_string());
+        let config = Config {
+            gemini_api_key: Some("test-key".to_string()),
+            ..Default::default()
+        };
         assert!(config.validate().is_ok());
     }
 }
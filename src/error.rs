@@ -11,12 +11,25 @@ pub enum AutosubError {
     #[error("API error: {0}")]
     Api(String),
 
+    /// The provider refused the request itself (bad request, invalid key).
+    #[error("API error: {0}")]
+    ApiRejected(String),
+
+    #[error("Response blocked ({reason}) for audio {range}")]
+    ContentBlocked { reason: String, range: String },
+
+    #[error("Output truncated at the token limit for audio {0}")]
+    OutputTruncated(String),
+
     #[error("File not found: {0}")]
     FileNotFound(String),
 
     #[error("Invalid configuration: {0}")]
     Config(String),
 
+    #[error("Invalid subtitle file: {0}")]
+    SubtitleParse(String),
+
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
 
@@ -27,4 +40,18 @@ pub enum AutosubError {
     Json(#[from] serde_json::Error),
 }
 
+impl AutosubError {
+    /// Whether the error came from the provider (outage, quota, network, blocked or
+    /// malformed response) rather than the request itself, so another provider may succeed.
+    pub fn is_provider_error(&self) -> bool {
+        matches!(
+            self,
+            AutosubError::Api(_)
+                | AutosubError::Http(_)
+                | AutosubError::Json(_)
+                | AutosubError::ContentBlocked { .. }
+        )
+    }
+}
+
 pub type Result<T> = std::result::Result<T, AutosubError>;
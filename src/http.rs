@@ -0,0 +1,143 @@
+//! Shared HTTP client configuration.
+//!
+//! Every provider sends its requests through a client built here, so timeouts,
+//! proxy, extra CA certificates and the user agent are configured in one place
+//! (the `[http]` table of `config.toml`).
+
+use crate::error::{AutosubError, Result};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// User agent sent with every request unless overridden.
+pub const DEFAULT_USER_AGENT: &str = concat!("autosub/", env!("CARGO_PKG_VERSION"));
+
+/// HTTP settings shared by all providers.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HttpConfig {
+    /// Seconds to wait for a connection to be established.
+    pub connect_timeout_secs: u64,
+    /// Seconds a whole request, including the response body, may take.
+    pub request_timeout_secs: u64,
+    /// Proxy for all requests, e.g. `http://proxy.corp:3128`.
+    /// Without it, the `HTTPS_PROXY`/`HTTP_PROXY` environment variables apply.
+    pub proxy: Option<String>,
+    /// PEM file with extra CA certificates to trust, e.g. a corporate root.
+    pub ca_bundle: Option<PathBuf>,
+    pub user_agent: String,
+}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        Self {
+            connect_timeout_secs: 10,
+            // Uploads and long chunk transcriptions can legitimately take minutes
+            request_timeout_secs: 300,
+            proxy: None,
+            ca_bundle: None,
+            user_agent: DEFAULT_USER_AGENT.to_string(),
+        }
+    }
+}
+
+impl HttpConfig {
+    /// Build a client with these settings.
+    pub fn build_client(&self) -> Result<reqwest::Client> {
+        if self.connect_timeout_secs == 0 || self.request_timeout_secs == 0 {
+            return Err(AutosubError::Config(
+                "HTTP timeouts must be greater than 0".to_string(),
+            ));
+        }
+
+        let mut builder = reqwest::Client::builder()
+            .connect_timeout(Duration::from_secs(self.connect_timeout_secs))
+            .timeout(Duration::from_secs(self.request_timeout_secs))
+            .user_agent(&self.user_agent);
+
+        if let Some(ref proxy) = self.proxy {
+            let proxy = reqwest::Proxy::all(proxy).map_err(|e| {
+                AutosubError::Config(format!("Invalid HTTP proxy {}: {}", proxy, e))
+            })?;
+            builder = builder.proxy(proxy);
+        }
+
+        if let Some(ref path) = self.ca_bundle {
+            let pem = std::fs::read(path).map_err(|e| {
+                AutosubError::Config(format!("Cannot read CA bundle {}: {}", path.display(), e))
+            })?;
+            let certificates = reqwest::Certificate::from_pem_bundle(&pem).map_err(|e| {
+                AutosubError::Config(format!("Invalid CA bundle {}: {}", path.display(), e))
+            })?;
+            if certificates.is_empty() {
+                return Err(AutosubError::Config(format!(
+                    "No certificates found in CA bundle {}",
+                    path.display()
+                )));
+            }
+            for certificate in certificates {
+                builder = builder.add_root_certificate(certificate);
+            }
+        }
+
+        builder
+            .build()
+            .map_err(|e| AutosubError::Config(format!("Failed to build HTTP client: {}", e)))
+    }
+}
+
+/// Client with the default settings, for providers created without a config.
+pub fn default_client() -> reqwest::Client {
+    HttpConfig::default()
+        .build_client()
+        .unwrap_or_else(|_| reqwest::Client::new())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_http_config_from_toml() {
+        let config: HttpConfig = toml::from_str(
+            r#"
+            request_timeout_secs = 60
+            proxy = "http://proxy.example.com:3128"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.request_timeout_secs, 60);
+        assert_eq!(config.connect_timeout_secs, 10);
+        assert_eq!(config.user_agent, DEFAULT_USER_AGENT);
+        assert!(config.build_client().is_ok());
+    }
+
+    #[test]
+    fn test_invalid_settings_are_rejected() {
+        let config = HttpConfig {
+            request_timeout_secs: 0,
+            ..Default::default()
+        };
+        assert!(config.build_client().is_err());
+
+        let config = HttpConfig {
+            ca_bundle: Some(PathBuf::from("/nonexistent/ca.pem")),
+            ..Default::default()
+        };
+        assert!(config
+            .build_client()
+            .unwrap_err()
+            .to_string()
+            .contains("ca.pem"));
+
+        let dir = tempfile::tempdir().unwrap();
+        let bundle = dir.path().join("bad.pem");
+        std::fs::write(&bundle, "not a certificate").unwrap();
+        let config = HttpConfig {
+            ca_bundle: Some(bundle),
+            ..Default::default()
+        };
+        assert!(config.build_client().is_err());
+    }
+}
@@ -3,7 +3,7 @@ use crate::pipeline::PipelineConfig;
 use console::style;
 use dialoguer::{Confirm, Input, Select};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 const SUPPORTED_EXTENSIONS: &[&str] = &[
     "mp4", "mkv", "avi", "mov", "webm", // Video
@@ -50,14 +50,20 @@ pub fn run_interactive_wizard() -> anyhow::Result<InteractiveResult> {
     // Step 4: Translation (optional)
     let translate_to = setup_translation(&language)?;
 
-    // Step 5: Select output format
+    // Step 5: Speaker identification (optional)
+    let diarize = Confirm::new()
+        .with_prompt("Identify different speakers?")
+        .default(false)
+        .interact()?;
+
+    // Step 6: Select output format
     let format = select_output_format()?;
 
     // Derive output path
     let output = derive_output_path(&input, &format);
 
-    // Step 6: Confirm
-    print_summary(&input, &output, &language, &translate_to, &format);
+    // Step 7: Confirm
+    print_summary(&input, &output, &language, &translate_to, diarize, &format);
 
     if !Confirm::new()
         .with_prompt("Proceed with these settings?")
@@ -72,10 +78,13 @@ pub fn run_interactive_wizard() -> anyhow::Result<InteractiveResult> {
     let pipeline_config = PipelineConfig {
         format,
         language,
-        translate_to,
+        translate_to: translate_to.into_iter().collect(),
         concurrency: config.concurrency,
-        post_process: Some(crate::subtitle::PostProcessConfig::default()),
-        show_progress: true,
+        diarize,
+        transcription_prompt: config.transcription_prompt.clone(),
+        translation_prompt: config.translation_prompt.clone(),
+        glossary: config.glossary.clone(),
+        ..Default::default()
     };
 
     Ok(InteractiveResult {
@@ -107,17 +116,11 @@ fn setup_api_key() -> anyhow::Result<Config> {
     let mut config = Config::load().unwrap_or_default();
 
     if config.gemini_api_key.is_some() {
-        println!(
-            "{} API key configured",
-            style("✓").green()
-        );
+        println!("{} API key configured", style("✓").green());
         return Ok(config);
     }
 
-    println!(
-        "{} Gemini API key not found",
-        style("!").yellow()
-    );
+    println!("{} Gemini API key not found", style("!").yellow());
     println!("  Get one at: https://aistudio.google.com/apikey\n");
 
     let api_key: String = Input::new()
@@ -293,8 +296,12 @@ fn setup_translation(source_lang: &str) -> anyhow::Result<Option<String>> {
 }
 
 fn select_output_format() -> anyhow::Result<OutputFormat> {
-    let formats = vec![
-        ("SRT", "Most compatible (VLC, YouTube, etc.)", OutputFormat::Srt),
+    let formats = [
+        (
+            "SRT",
+            "Most compatible (VLC, YouTube, etc.)",
+            OutputFormat::Srt,
+        ),
         ("VTT", "Web/HTML5 video", OutputFormat::Vtt),
         ("JSON", "Programmatic access", OutputFormat::Json),
     ];
@@ -313,18 +320,19 @@ fn select_output_format() -> anyhow::Result<OutputFormat> {
     Ok(formats[selection].2)
 }
 
-fn derive_output_path(input: &PathBuf, format: &OutputFormat) -> PathBuf {
+fn derive_output_path(input: &Path, format: &OutputFormat) -> PathBuf {
     let stem = input.file_stem().unwrap_or_default();
-    let mut output = input.clone();
+    let mut output = input.to_path_buf();
     output.set_file_name(format!("{}.{}", stem.to_string_lossy(), format.extension()));
     output
 }
 
 fn print_summary(
-    input: &PathBuf,
-    output: &PathBuf,
+    input: &Path,
+    output: &Path,
     language: &str,
     translate_to: &Option<String>,
+    diarize: bool,
     format: &OutputFormat,
 ) {
     println!("\n{}", style("═══ Summary ═══").bold());
@@ -334,6 +342,9 @@ fn print_summary(
     if let Some(target) = translate_to {
         println!("  Translate: → {}", get_language_name(target));
     }
+    if diarize {
+        println!("  Speakers:  identified");
+    }
     println!("  Format:    {}", format.extension().to_uppercase());
     println!();
 }
@@ -368,10 +379,10 @@ mod tests {
     #[test]
     fn test_derive_output_path() {
         let input = PathBuf::from("/path/to/video.mp4");
-        
+
         let srt = derive_output_path(&input, &OutputFormat::Srt);
         assert_eq!(srt, PathBuf::from("/path/to/video.srt"));
-        
+
         let vtt = derive_output_path(&input, &OutputFormat::Vtt);
         assert_eq!(vtt, PathBuf::from("/path/to/video.vtt"));
     }
@@ -0,0 +1,793 @@
+//! Persistent, resumable pipeline jobs.
+//!
+//! A job directory lives under the config directory and holds everything a
+//! long run needs to survive a crash or Ctrl+C: the extracted audio, the chunk
+//! plan and speech regions, each finished chunk transcript and each finished
+//! translation batch.
+//! Re-running the same command (or `autosub resume <job>`) reuses whatever is
+//! already there and only does the remaining work. The directory is removed
+//! once the output file has been written.
+
+use crate::audio::SpeechRegion;
+use crate::config::{Config, OutputFormat, TranslatorConfig};
+use crate::error::{AutosubError, Result};
+use crate::pipeline::PipelineConfig;
+use crate::prompt::{PromptTemplate, PromptTemplates};
+use crate::subtitle::{OriginalStyle, SpeakerFormat};
+use crate::transcribe::Transcript;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+use tracing::{debug, warn};
+
+const MANIFEST_FILE: &str = "job.json";
+const AUDIO_FILE: &str = "audio.wav";
+const AUDIO_DONE_FILE: &str = "audio.done";
+const PLAN_FILE: &str = "plan.json";
+const SPEECH_FILE: &str = "speech.json";
+
+/// Everything needed to re-run a job from `autosub resume`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobManifest {
+    pub input: PathBuf,
+    pub output: PathBuf,
+    pub format: OutputFormat,
+    pub language: String,
+    pub translate_to: Vec<String>,
+    pub concurrency: usize,
+    pub translation_batch_size: usize,
+    pub translation_context: usize,
+    pub translation_summary: bool,
+    pub max_cps: Option<f64>,
+    pub diarize: bool,
+    pub sdh: bool,
+    pub speaker_format: SpeakerFormat,
+    pub validate: bool,
+    pub use_cache: bool,
+    pub incremental: bool,
+    pub low_confidence: f64,
+    pub retranscribe_below: Option<f64>,
+    pub transcription_prompt: Option<PathBuf>,
+    pub translation_prompt: Option<PathBuf>,
+    pub glossary: Option<PathBuf>,
+    pub translation_memory: bool,
+    pub memory_fuzzy: Option<f64>,
+    pub dual_subtitles: Option<OriginalStyle>,
+    /// Translation provider, model and endpoint; the API key is left out.
+    pub translator: TranslatorConfig,
+    /// Settings the chunk checkpoints were made with, see [`JobFingerprints`].
+    pub transcription_fingerprint: String,
+    /// Settings the translation checkpoints were made with, see [`JobFingerprints`].
+    pub translation_fingerprint: String,
+}
+
+/// Hashes of the settings that shape checkpointed work without being part of the job ID.
+///
+/// When one differs from the previous run, the checkpoints of that stage are discarded
+/// rather than mixed with output made with the new settings.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct JobFingerprints {
+    /// Providers, models, log probabilities, prompt template, validation and re-transcription.
+    pub transcription: String,
+    /// Translator, model, prompt template, glossary, context, summary, memory,
+    /// batch size and reading speed.
+    pub translation: String,
+}
+
+impl JobFingerprints {
+    /// Fingerprint the settings of a run.
+    pub fn new(
+        config: &Config,
+        pipeline_config: &PipelineConfig,
+        prompts: &PromptTemplates,
+    ) -> Self {
+        // Keys select an account, not the output
+        let providers: Vec<_> = config
+            .transcription_providers
+            .iter()
+            .map(|p| {
+                format!(
+                    "{}|{:?}|{:?}|{}",
+                    p.provider, p.model, p.base_url, p.logprobs
+                )
+            })
+            .collect();
+        let transcription = fingerprint(&[
+            providers.join(">"),
+            template_hash(prompts.transcription.as_ref()),
+            pipeline_config.validate.to_string(),
+            format!("{:?}", pipeline_config.retranscribe_below),
+        ]);
+
+        let translator = config.translator_settings();
+        let glossary = pipeline_config.glossary.as_ref().map(|path| {
+            fs::read(path)
+                .map(|contents| fingerprint(&[String::from_utf8_lossy(&contents).into_owned()]))
+                .unwrap_or_else(|_| path.display().to_string())
+        });
+        let translation = fingerprint(&[
+            format!(
+                "{}|{:?}|{:?}",
+                translator.provider, translator.model, translator.base_url
+            ),
+            template_hash(prompts.translation.as_ref()),
+            format!("{:?}", glossary),
+            pipeline_config.translation_context.to_string(),
+            pipeline_config.translation_summary.to_string(),
+            pipeline_config.translation_memory.to_string(),
+            format!("{:?}", pipeline_config.memory_fuzzy),
+            pipeline_config.translation_batch_size.to_string(),
+            format!("{:?}", pipeline_config.max_cps),
+        ]);
+
+        Self {
+            transcription,
+            translation,
+        }
+    }
+}
+
+fn template_hash(template: Option<&PromptTemplate>) -> String {
+    template.map(|t| t.hash().to_string()).unwrap_or_default()
+}
+
+/// Hex SHA-256 of `parts`, each terminated so that no two lists hash alike.
+fn fingerprint(parts: &[String]) -> String {
+    let mut hasher = Sha256::new();
+    for part in parts {
+        hasher.update(part.as_bytes());
+        hasher.update([0u8]);
+    }
+    hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+impl JobManifest {
+    /// Rebuild the pipeline configuration the job was started with.
+    pub fn pipeline_config(&self) -> PipelineConfig {
+        PipelineConfig {
+            format: self.format,
+            language: self.language.clone(),
+            translate_to: self.translate_to.clone(),
+            concurrency: self.concurrency,
+            translation_batch_size: self.translation_batch_size,
+            translation_context: self.translation_context,
+            translation_summary: self.translation_summary,
+            max_cps: self.max_cps,
+            diarize: self.diarize,
+            sdh: self.sdh,
+            speaker_format: self.speaker_format.clone(),
+            validate: self.validate,
+            use_cache: self.use_cache,
+            incremental: self.incremental,
+            low_confidence: self.low_confidence,
+            retranscribe_below: self.retranscribe_below,
+            transcription_prompt: self.transcription_prompt.clone(),
+            translation_prompt: self.translation_prompt.clone(),
+            glossary: self.glossary.clone(),
+            translation_memory: self.translation_memory,
+            memory_fuzzy: self.memory_fuzzy,
+            dual_subtitles: self.dual_subtitles.clone(),
+            ..Default::default()
+        }
+    }
+}
+
+/// A resumable job and its on-disk checkpoints.
+#[derive(Debug)]
+pub struct Job {
+    id: String,
+    dir: PathBuf,
+    manifest: JobManifest,
+}
+
+impl Job {
+    /// Directory holding all job directories.
+    pub fn jobs_dir() -> Result<PathBuf> {
+        Config::config_dir()
+            .map(|dir| dir.join("jobs"))
+            .ok_or_else(|| {
+                AutosubError::Config("Could not determine config directory for jobs".to_string())
+            })
+    }
+
+    /// Compute the job ID for an input file and the settings that affect transcription.
+    ///
+    /// The same file transcribed the same way always maps to the same job, so
+    /// re-running a command picks up where the last run stopped.
+    pub fn id_for(input: &Path, pipeline_config: &PipelineConfig) -> Result<String> {
+        let input = input.canonicalize()?;
+        let metadata = fs::metadata(&input)?;
+        let modified = metadata
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+
+        let mut hasher = Sha256::new();
+        hasher.update(input.to_string_lossy().as_bytes());
+        hasher.update(metadata.len().to_le_bytes());
+        hasher.update(modified.to_le_bytes());
+        hasher.update(pipeline_config.language.as_bytes());
+        hasher.update([pipeline_config.diarize as u8]);
+        hasher.update([pipeline_config.sdh as u8]);
+        Ok(hasher
+            .finalize()
+            .iter()
+            .take(6)
+            .map(|b| format!("{:02x}", b))
+            .collect())
+    }
+
+    /// Open the job for this input and settings, creating it if needed.
+    pub fn open_or_create(
+        input: &Path,
+        output: &Path,
+        pipeline_config: &PipelineConfig,
+        translator: &TranslatorConfig,
+        fingerprints: &JobFingerprints,
+    ) -> Result<Self> {
+        let id = Self::id_for(input, pipeline_config)?;
+        Self::open_or_create_in(
+            &Self::jobs_dir()?,
+            id,
+            input,
+            output,
+            pipeline_config,
+            translator,
+            fingerprints,
+        )
+    }
+
+    pub(crate) fn open_or_create_in(
+        jobs_dir: &Path,
+        id: String,
+        input: &Path,
+        output: &Path,
+        pipeline_config: &PipelineConfig,
+        translator: &TranslatorConfig,
+        fingerprints: &JobFingerprints,
+    ) -> Result<Self> {
+        let dir = jobs_dir.join(&id);
+        if dir.join(MANIFEST_FILE).exists() {
+            debug!("Resuming job {} in {:?}", id, dir);
+        }
+
+        fs::create_dir_all(dir.join("chunks"))?;
+        if let Ok(previous) = Self::read_manifest(&dir) {
+            if previous.transcription_fingerprint != fingerprints.transcription {
+                debug!("Transcription settings changed, discarding chunk checkpoints");
+                for entry in fs::read_dir(dir.join("chunks"))?.flatten() {
+                    if entry.path().extension().is_some_and(|ext| ext == "json") {
+                        let _ = fs::remove_file(entry.path());
+                    }
+                }
+            }
+            if previous.translation_fingerprint != fingerprints.translation {
+                debug!("Translation settings changed, discarding translation checkpoints");
+                let _ = fs::remove_dir_all(dir.join("translations"));
+            }
+        }
+        // Output and formatting may change between runs without invalidating checkpoints.
+        let manifest = JobManifest {
+            input: input.canonicalize()?,
+            output: std::path::absolute(output)?,
+            format: pipeline_config.format,
+            language: pipeline_config.language.clone(),
+            translate_to: pipeline_config.translate_to.clone(),
+            concurrency: pipeline_config.concurrency,
+            translation_batch_size: pipeline_config.translation_batch_size,
+            translation_context: pipeline_config.translation_context,
+            translation_summary: pipeline_config.translation_summary,
+            max_cps: pipeline_config.max_cps,
+            diarize: pipeline_config.diarize,
+            sdh: pipeline_config.sdh,
+            speaker_format: pipeline_config.speaker_format.clone(),
+            validate: pipeline_config.validate,
+            use_cache: pipeline_config.use_cache,
+            incremental: pipeline_config.incremental,
+            low_confidence: pipeline_config.low_confidence,
+            retranscribe_below: pipeline_config.retranscribe_below,
+            transcription_prompt: pipeline_config.transcription_prompt.clone(),
+            translation_prompt: pipeline_config.translation_prompt.clone(),
+            glossary: pipeline_config.glossary.clone(),
+            translation_memory: pipeline_config.translation_memory,
+            memory_fuzzy: pipeline_config.memory_fuzzy,
+            dual_subtitles: pipeline_config.dual_subtitles.clone(),
+            translator: translator.clone(),
+            transcription_fingerprint: fingerprints.transcription.clone(),
+            translation_fingerprint: fingerprints.translation.clone(),
+        };
+        let job = Self { id, dir, manifest };
+        job.write_manifest()?;
+        Ok(job)
+    }
+
+    /// Load an existing job by ID.
+    pub fn load(id: &str) -> Result<Self> {
+        Self::load_from(&Self::jobs_dir()?.join(id))
+    }
+
+    fn load_from(dir: &Path) -> Result<Self> {
+        let id = dir
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let manifest = Self::read_manifest(dir)
+            .map_err(|_| AutosubError::Config(format!("No resumable job '{}'", id)))?;
+        Ok(Self {
+            id,
+            dir: dir.to_path_buf(),
+            manifest,
+        })
+    }
+
+    /// List all unfinished jobs.
+    pub fn list() -> Result<Vec<Job>> {
+        Self::list_in(&Self::jobs_dir()?)
+    }
+
+    fn list_in(jobs_dir: &Path) -> Result<Vec<Job>> {
+        let mut jobs = Vec::new();
+        if !jobs_dir.exists() {
+            return Ok(jobs);
+        }
+        for entry in fs::read_dir(jobs_dir)? {
+            let entry = entry?;
+            if entry.file_type()?.is_dir() {
+                match Self::load_from(&entry.path()) {
+                    Ok(job) => jobs.push(job),
+                    Err(e) => warn!("Skipping unreadable job {:?}: {}", entry.path(), e),
+                }
+            }
+        }
+        jobs.sort_by(|a, b| a.id.cmp(&b.id));
+        Ok(jobs)
+    }
+
+    fn read_manifest(dir: &Path) -> Result<JobManifest> {
+        let contents = fs::read_to_string(dir.join(MANIFEST_FILE))?;
+        Ok(serde_json::from_str(&contents)?)
+    }
+
+    fn write_manifest(&self) -> Result<()> {
+        write_atomic(
+            &self.dir.join(MANIFEST_FILE),
+            &serde_json::to_vec_pretty(&self.manifest)?,
+        )
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn manifest(&self) -> &JobManifest {
+        &self.manifest
+    }
+
+    /// Path of the extracted audio.
+    pub fn audio_path(&self) -> PathBuf {
+        self.dir.join(AUDIO_FILE)
+    }
+
+    /// Whether the audio has already been fully extracted.
+    pub fn has_audio(&self) -> bool {
+        self.dir.join(AUDIO_DONE_FILE).exists() && self.audio_path().exists()
+    }
+
+    /// Record that audio extraction finished.
+    pub fn mark_audio_extracted(&self) -> Result<()> {
+        fs::write(self.dir.join(AUDIO_DONE_FILE), b"")?;
+        Ok(())
+    }
+
+    /// Directory where chunk audio is written.
+    pub fn chunk_dir(&self) -> PathBuf {
+        self.dir.join("chunks")
+    }
+
+    /// Load the saved chunk plan.
+    pub fn load_plan(&self) -> Option<Vec<SpeechRegion>> {
+        let contents = fs::read_to_string(self.dir.join(PLAN_FILE)).ok()?;
+        serde_json::from_str(&contents).ok()
+    }
+
+    /// Save the chunk plan.
+    pub fn save_plan(&self, plan: &[SpeechRegion]) -> Result<()> {
+        write_atomic(&self.dir.join(PLAN_FILE), &serde_json::to_vec(plan)?)
+    }
+
+    /// Load the speech regions found by VAD.
+    pub fn load_speech_regions(&self) -> Option<Vec<SpeechRegion>> {
+        let contents = fs::read_to_string(self.dir.join(SPEECH_FILE)).ok()?;
+        serde_json::from_str(&contents).ok()
+    }
+
+    /// Save the speech regions found by VAD.
+    pub fn save_speech_regions(&self, regions: &[SpeechRegion]) -> Result<()> {
+        write_atomic(&self.dir.join(SPEECH_FILE), &serde_json::to_vec(regions)?)
+    }
+
+    fn chunk_checkpoint(&self, index: usize) -> PathBuf {
+        self.chunk_dir().join(format!("chunk_{:04}.json", index))
+    }
+
+    /// Whether a chunk has a finished transcript.
+    pub fn has_chunk(&self, index: usize) -> bool {
+        self.chunk_checkpoint(index).exists()
+    }
+
+    /// Load the finished transcript of a chunk.
+    pub fn load_chunk(&self, index: usize) -> Option<Transcript> {
+        let contents = fs::read_to_string(self.chunk_checkpoint(index)).ok()?;
+        match serde_json::from_str(&contents) {
+            Ok(transcript) => Some(transcript),
+            Err(e) => {
+                warn!("Ignoring corrupt checkpoint for chunk {}: {}", index, e);
+                None
+            }
+        }
+    }
+
+    /// Save the finished transcript of a chunk.
+    pub fn save_chunk(&self, index: usize, transcript: &Transcript) -> Result<()> {
+        write_atomic(
+            &self.chunk_checkpoint(index),
+            &serde_json::to_vec(transcript)?,
+        )
+    }
+
+    fn batch_checkpoint(&self, target_lang: &str, key: &str) -> PathBuf {
+        self.dir
+            .join("translations")
+            .join(target_lang)
+            .join(format!("{}.json", key))
+    }
+
+    /// Load a finished translation batch by the key of its source texts and context.
+    pub fn load_batch(&self, target_lang: &str, key: &str) -> Option<Vec<String>> {
+        let contents = fs::read_to_string(self.batch_checkpoint(target_lang, key)).ok()?;
+        serde_json::from_str(&contents).ok()
+    }
+
+    /// Save a finished translation batch under the key of its source texts and context.
+    pub fn save_batch(&self, target_lang: &str, key: &str, texts: &[String]) -> Result<()> {
+        let path = self.batch_checkpoint(target_lang, key);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        write_atomic(&path, &serde_json::to_vec(texts)?)
+    }
+
+    /// Delete the job directory once its output has been written.
+    pub fn remove(&self) -> Result<()> {
+        fs::remove_dir_all(&self.dir)?;
+        Ok(())
+    }
+}
+
+/// Write a file via a temp file so a crash never leaves a partial checkpoint.
+fn write_atomic(path: &Path, contents: &[u8]) -> Result<()> {
+    let tmp = path.with_extension("tmp");
+    fs::write(&tmp, contents)?;
+    fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transcribe::{SegmentKind, TranscriptSegment};
+    use std::time::Duration;
+
+    fn fingerprints(config: &PipelineConfig) -> JobFingerprints {
+        JobFingerprints::new(&Config::default(), config, &PromptTemplates::default())
+    }
+
+    fn create_job(jobs_dir: &Path, input: &Path) -> Job {
+        let config = PipelineConfig::default();
+        let id = Job::id_for(input, &config).unwrap();
+        Job::open_or_create_in(
+            jobs_dir,
+            id,
+            input,
+            Path::new("out.srt"),
+            &config,
+            &TranslatorConfig::named("gemini"),
+            &fingerprints(&config),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_id_depends_on_settings() {
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("video.mp4");
+        fs::write(&input, b"video").unwrap();
+
+        let default = PipelineConfig::default();
+        let id = Job::id_for(&input, &default).unwrap();
+        assert_eq!(id.len(), 12);
+        assert_eq!(id, Job::id_for(&input, &default).unwrap());
+
+        let japanese = PipelineConfig {
+            language: "ja".to_string(),
+            ..Default::default()
+        };
+        assert_ne!(id, Job::id_for(&input, &japanese).unwrap());
+
+        // Output format does not affect the transcription work
+        let vtt = PipelineConfig {
+            format: OutputFormat::Vtt,
+            ..Default::default()
+        };
+        assert_eq!(id, Job::id_for(&input, &vtt).unwrap());
+    }
+
+    #[test]
+    fn test_checkpoints_survive_reopen() {
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("video.mp4");
+        fs::write(&input, b"video").unwrap();
+        let jobs_dir = dir.path().join("jobs");
+
+        let job = create_job(&jobs_dir, &input);
+        let plan = vec![SpeechRegion {
+            start: Duration::ZERO,
+            end: Duration::from_secs(30),
+        }];
+        job.save_plan(&plan).unwrap();
+        job.save_speech_regions(&plan).unwrap();
+        fs::write(job.audio_path(), b"wav").unwrap();
+        job.mark_audio_extracted().unwrap();
+        job.save_chunk(
+            0,
+            &Transcript::single(TranscriptSegment {
+                text: "Done".to_string(),
+                start: Duration::ZERO,
+                end: Duration::from_secs(2),
+                words: None,
+                confidence: None,
+                speaker: None,
+                kind: SegmentKind::Speech,
+                original_text: None,
+            }),
+        )
+        .unwrap();
+        job.save_batch("es", "batch", &["Hecho".to_string()])
+            .unwrap();
+
+        let job = create_job(&jobs_dir, &input);
+        assert!(job.has_audio());
+        assert_eq!(job.load_plan().unwrap().len(), 1);
+        assert_eq!(job.load_speech_regions().unwrap().len(), 1);
+        assert_eq!(job.load_chunk(0).unwrap().segments[0].text, "Done");
+        assert!(job.load_chunk(1).is_none());
+        assert_eq!(
+            job.load_batch("es", "batch").unwrap(),
+            vec!["Hecho".to_string()]
+        );
+        assert!(job.load_batch("fr", "batch").is_none());
+
+        let listed = Job::list_in(&jobs_dir).unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].manifest().language, "en");
+
+        listed.into_iter().next().unwrap().remove().unwrap();
+        assert!(Job::list_in(&jobs_dir).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_validation_change_discards_chunks() {
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("video.mp4");
+        fs::write(&input, b"video").unwrap();
+        let jobs_dir = dir.path().join("jobs");
+
+        let job = create_job(&jobs_dir, &input);
+        let segment = TranscriptSegment {
+            text: "Done".to_string(),
+            start: Duration::ZERO,
+            end: Duration::from_secs(2),
+            words: None,
+            confidence: None,
+            speaker: None,
+            kind: SegmentKind::Speech,
+            original_text: None,
+        };
+        job.save_chunk(0, &Transcript::single(segment)).unwrap();
+        job.save_batch("es", "batch", &["Hecho".to_string()])
+            .unwrap();
+
+        let config = PipelineConfig {
+            validate: false,
+            ..Default::default()
+        };
+        let job = Job::open_or_create_in(
+            &jobs_dir,
+            job.id().to_string(),
+            &input,
+            Path::new("out.srt"),
+            &config,
+            &TranslatorConfig::named("gemini"),
+            &fingerprints(&config),
+        )
+        .unwrap();
+        assert!(!job.has_chunk(0));
+        assert!(job.load_batch("es", "batch").is_some());
+    }
+
+    #[test]
+    fn test_manifest_round_trips_dual_style() {
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("video.mp4");
+        fs::write(&input, b"video").unwrap();
+        let job = create_job(&dir.path().join("jobs"), &input);
+
+        let mut manifest = serde_json::to_value(job.manifest()).unwrap();
+        manifest["dual_subtitles"] = serde_json::json!("color:yellow");
+        let read: JobManifest = serde_json::from_value(manifest).unwrap();
+        assert_eq!(
+            read.dual_subtitles,
+            Some(OriginalStyle::Color("yellow".to_string()))
+        );
+        assert_eq!(
+            serde_json::to_value(&read).unwrap()["dual_subtitles"],
+            "color:yellow"
+        );
+    }
+
+    #[test]
+    fn test_batch_size_change_discards_translations() {
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("video.mp4");
+        fs::write(&input, b"video").unwrap();
+        let jobs_dir = dir.path().join("jobs");
+
+        let job = create_job(&jobs_dir, &input);
+        job.save_batch("es", "batch", &["Hecho".to_string()])
+            .unwrap();
+
+        let config = PipelineConfig {
+            translation_batch_size: 25,
+            ..Default::default()
+        };
+        let job = Job::open_or_create_in(
+            &jobs_dir,
+            job.id().to_string(),
+            &input,
+            Path::new("out.srt"),
+            &config,
+            &TranslatorConfig::named("gemini"),
+            &fingerprints(&config),
+        )
+        .unwrap();
+        assert!(job.load_batch("es", "batch").is_none());
+        assert_eq!(job.manifest().translation_batch_size, 25);
+
+        job.save_batch("es", "batch", &["Hecho".to_string()])
+            .unwrap();
+        let config = PipelineConfig {
+            glossary: Some(PathBuf::from("glossary.toml")),
+            ..config
+        };
+        let job = Job::open_or_create_in(
+            &jobs_dir,
+            job.id().to_string(),
+            &input,
+            Path::new("out.srt"),
+            &config,
+            &TranslatorConfig::named("gemini"),
+            &fingerprints(&config),
+        )
+        .unwrap();
+        assert!(job.load_batch("es", "batch").is_none());
+
+        job.save_batch("es", "batch", &["Hecho".to_string()])
+            .unwrap();
+        let config = PipelineConfig {
+            max_cps: Some(17.0),
+            ..config
+        };
+        let job = Job::open_or_create_in(
+            &jobs_dir,
+            job.id().to_string(),
+            &input,
+            Path::new("out.srt"),
+            &config,
+            &TranslatorConfig::named("gemini"),
+            &fingerprints(&config),
+        )
+        .unwrap();
+        assert!(job.load_batch("es", "batch").is_none());
+    }
+
+    #[test]
+    fn test_provider_and_prompt_changes_discard_checkpoints() {
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("video.mp4");
+        fs::write(&input, b"video").unwrap();
+        let jobs_dir = dir.path().join("jobs");
+        let pipeline_config = PipelineConfig::default();
+        let reopen = |config: &Config, prompts: &PromptTemplates| {
+            let fingerprints = JobFingerprints::new(config, &pipeline_config, prompts);
+            let id = Job::id_for(&input, &pipeline_config).unwrap();
+            Job::open_or_create_in(
+                &jobs_dir,
+                id,
+                &input,
+                Path::new("out.srt"),
+                &pipeline_config,
+                &config.translator_settings(),
+                &fingerprints,
+            )
+            .unwrap()
+        };
+        let checkpoint = |job: &Job| {
+            let segment = TranscriptSegment {
+                text: "Done".to_string(),
+                start: Duration::ZERO,
+                end: Duration::from_secs(2),
+                words: None,
+                confidence: None,
+                speaker: None,
+                kind: SegmentKind::Speech,
+                original_text: None,
+            };
+            job.save_chunk(0, &Transcript::single(segment)).unwrap();
+            job.save_batch("es", "batch", &["Hecho".to_string()])
+                .unwrap();
+        };
+
+        let config = Config::default();
+        let job = reopen(&config, &PromptTemplates::default());
+        checkpoint(&job);
+        // Same settings, nothing discarded
+        let job = reopen(&config, &PromptTemplates::default());
+        assert!(job.has_chunk(0));
+        assert!(job.load_batch("es", "batch").is_some());
+
+        // Another translation model keeps the transcripts
+        let other_translator = Config {
+            translator: Some(TranslatorConfig {
+                model: Some("gemini-2.5-pro".to_string()),
+                ..TranslatorConfig::named("gemini")
+            }),
+            ..Default::default()
+        };
+        let job = reopen(&other_translator, &PromptTemplates::default());
+        assert!(job.has_chunk(0));
+        assert!(job.load_batch("es", "batch").is_none());
+
+        // Log probabilities change the transcripts, and a prompt template the translations
+        checkpoint(&job);
+        let mut logprobs = other_translator.clone();
+        logprobs.transcription_providers = vec![crate::config::ProviderConfig {
+            logprobs: true,
+            ..crate::config::ProviderConfig::gemini()
+        }];
+        let job = reopen(&logprobs, &PromptTemplates::default());
+        assert!(!job.has_chunk(0));
+        assert!(job.load_batch("es", "batch").is_some());
+
+        let template = dir.path().join("translate.txt");
+        fs::write(
+            &template,
+            "Translate into {language}, as [{\"id\": N, \"text\": \"...\"}]:\n{texts}",
+        )
+        .unwrap();
+        let prompts = PromptTemplates::load(None, Some(&template)).unwrap();
+        let job = reopen(&logprobs, &prompts);
+        assert!(job.load_batch("es", "batch").is_none());
+    }
+}
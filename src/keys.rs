@@ -0,0 +1,367 @@
+//! Gemini API key pools.
+//!
+//! Work can be spread across several Gemini projects by configuring more than
+//! one key. Requests take the keys in turn; when a key reports a quota error
+//! (HTTP 429, or a 403 mentioning quota) it rests for a while and the request
+//! moves on to the next key. Per-key counters are reported in the run summary.
+
+use crate::error::{AutosubError, Result};
+use crate::usage::TokenUsage;
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+/// How long a key that hit its quota is skipped.
+pub const QUOTA_COOLDOWN: Duration = Duration::from_secs(60);
+
+/// Requests made with one key, identified by its masked value.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeyUsage {
+    /// The key with all but its last characters hidden, e.g. `...a1b2`.
+    pub key: String,
+    #[serde(flatten)]
+    pub usage: TokenUsage,
+    /// Requests rejected because the key's quota was exhausted.
+    pub quota_errors: u64,
+}
+
+/// A key handed out for a request. Formats masked so it never leaks into logs.
+#[derive(Clone)]
+pub struct ApiKey {
+    index: usize,
+    value: String,
+}
+
+impl ApiKey {
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+}
+
+impl std::fmt::Display for ApiKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", mask_key(&self.value))
+    }
+}
+
+impl std::fmt::Debug for ApiKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ApiKey({})", mask_key(&self.value))
+    }
+}
+
+/// Status and body of a response, with the key that produced it.
+#[derive(Debug)]
+pub struct KeyResponse {
+    pub key: ApiKey,
+    pub status: StatusCode,
+    pub body: String,
+}
+
+struct KeySlot {
+    value: String,
+    usage: KeyUsage,
+    resting_until: Option<Instant>,
+}
+
+impl KeySlot {
+    fn is_available(&self, now: Instant) -> bool {
+        self.resting_until.is_none_or(|until| until <= now)
+    }
+}
+
+/// Shared, round-robin set of API keys. Clones share the same keys and counters.
+#[derive(Clone)]
+pub struct ApiKeyPool {
+    slots: Arc<Mutex<Vec<KeySlot>>>,
+    next: Arc<AtomicUsize>,
+    cooldown: Duration,
+}
+
+impl ApiKeyPool {
+    /// Pool of the given keys. Duplicates and blank entries are dropped.
+    pub fn new(keys: impl IntoIterator<Item = String>) -> Self {
+        Self::with_cooldown(keys, QUOTA_COOLDOWN)
+    }
+
+    /// Pool whose keys rest for `cooldown` after a quota error.
+    pub fn with_cooldown(keys: impl IntoIterator<Item = String>, cooldown: Duration) -> Self {
+        let mut slots: Vec<KeySlot> = Vec::new();
+        for key in keys {
+            let key = key.trim().to_string();
+            if key.is_empty() || slots.iter().any(|s| s.value == key) {
+                continue;
+            }
+            slots.push(KeySlot {
+                usage: KeyUsage {
+                    key: mask_key(&key),
+                    usage: TokenUsage::default(),
+                    quota_errors: 0,
+                },
+                value: key,
+                resting_until: None,
+            });
+        }
+        Self {
+            slots: Arc::new(Mutex::new(slots)),
+            next: Arc::new(AtomicUsize::new(0)),
+            cooldown,
+        }
+    }
+
+    /// Pool from a comma- or newline-separated list of keys.
+    pub fn parse(keys: &str) -> Self {
+        Self::new(split_keys(keys))
+    }
+
+    pub fn len(&self) -> usize {
+        self.slots.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The next key in turn, skipping resting keys while any other is available.
+    pub fn acquire(&self) -> Result<ApiKey> {
+        self.next_key(true).ok_or_else(|| {
+            AutosubError::Config(
+                "Gemini API key not set. Set GEMINI_API_KEY environment variable.".to_string(),
+            )
+        })
+    }
+
+    /// The next key in turn that is not resting, if any.
+    fn next_available(&self) -> Option<ApiKey> {
+        self.next_key(false)
+    }
+
+    fn next_key(&self, allow_resting: bool) -> Option<ApiKey> {
+        let slots = self.slots.lock().unwrap();
+        if slots.is_empty() {
+            return None;
+        }
+        let now = Instant::now();
+        let start = self.next.fetch_add(1, Ordering::Relaxed);
+
+        let available = (0..slots.len())
+            .map(|offset| (start + offset) % slots.len())
+            .find(|&index| slots[index].is_available(now));
+        // With every key resting, use the one that recovers first rather than failing
+        let index = match available {
+            Some(index) => index,
+            None if allow_resting => (0..slots.len()).min_by_key(|&i| slots[i].resting_until)?,
+            None => return None,
+        };
+
+        Some(ApiKey {
+            index,
+            value: slots[index].value.clone(),
+        })
+    }
+
+    /// Count a request made with `key`.
+    pub fn record_request(&self, key: &ApiKey) {
+        if let Some(slot) = self.slots.lock().unwrap().get_mut(key.index) {
+            slot.usage.usage.requests += 1;
+        }
+    }
+
+    /// Add the tokens of a response to `key`'s totals.
+    pub fn record_tokens(&self, key: &ApiKey, usage: TokenUsage) {
+        if let Some(slot) = self.slots.lock().unwrap().get_mut(key.index) {
+            slot.usage.usage.input_tokens += usage.input_tokens;
+            slot.usage.usage.output_tokens += usage.output_tokens;
+        }
+    }
+
+    /// Note that `key` hit its quota and rest it.
+    pub fn record_quota_error(&self, key: &ApiKey) {
+        if let Some(slot) = self.slots.lock().unwrap().get_mut(key.index) {
+            slot.usage.quota_errors += 1;
+            slot.resting_until = Some(Instant::now() + self.cooldown);
+        }
+    }
+
+    /// Per-key counters, in configuration order.
+    pub fn usage(&self) -> Vec<KeyUsage> {
+        self.slots
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|slot| slot.usage.clone())
+            .collect()
+    }
+
+    /// Send a request built for the next key, moving on to another key when one is over quota.
+    ///
+    /// When no other key is available the quota error response is returned as is.
+    pub async fn send<F>(&self, build: F) -> Result<KeyResponse>
+    where
+        F: Fn(&str) -> reqwest::RequestBuilder,
+    {
+        let mut key = self.acquire()?;
+        loop {
+            let response = self.send_with_key(&key, &build).await?;
+            if !is_quota_error(response.status, &response.body) {
+                return Ok(response);
+            }
+            match self.next_available() {
+                Some(next) => {
+                    warn!("API key {} is over quota, switching to {}", key, next);
+                    key = next;
+                }
+                None => return Ok(response),
+            }
+        }
+    }
+
+    /// Send a request with a specific key, e.g. one that owns an uploaded file.
+    pub async fn send_with_key<F>(&self, key: &ApiKey, build: F) -> Result<KeyResponse>
+    where
+        F: Fn(&str) -> reqwest::RequestBuilder,
+    {
+        let response = build(key.value()).send().await;
+        self.record_request(key);
+        let response = response?;
+
+        let status = response.status();
+        let body = response.text().await?;
+        if is_quota_error(status, &body) {
+            self.record_quota_error(key);
+        }
+
+        Ok(KeyResponse {
+            key: key.clone(),
+            status,
+            body,
+        })
+    }
+}
+
+impl std::fmt::Debug for ApiKeyPool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ApiKeyPool")
+            .field("keys", &self.len())
+            .finish()
+    }
+}
+
+/// Whether a response means the key's quota is exhausted rather than the request being bad.
+pub fn is_quota_error(status: StatusCode, body: &str) -> bool {
+    if status == StatusCode::TOO_MANY_REQUESTS {
+        return true;
+    }
+    status == StatusCode::FORBIDDEN
+        && (body.contains("RESOURCE_EXHAUSTED") || body.to_lowercase().contains("quota"))
+}
+
+/// Split a comma- or newline-separated list of keys.
+pub fn split_keys(keys: &str) -> Vec<String> {
+    keys.split([',', '\n'])
+        .map(str::trim)
+        .filter(|k| !k.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Hide all but the last four characters of a key.
+pub fn mask_key(key: &str) -> String {
+    let chars: Vec<char> = key.chars().collect();
+    if chars.len() <= 8 {
+        return "****".to_string();
+    }
+    format!("...{}", chars[chars.len() - 4..].iter().collect::<String>())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pool(keys: &[&str]) -> ApiKeyPool {
+        ApiKeyPool::new(keys.iter().map(|k| k.to_string()))
+    }
+
+    #[test]
+    fn test_keys_rotate() {
+        let pool = pool(&["key-one-aaaa", "key-two-bbbb", "key-one-aaaa", " "]);
+        assert_eq!(pool.len(), 2);
+
+        let used: Vec<String> = (0..4)
+            .map(|_| pool.acquire().unwrap().value().to_string())
+            .collect();
+        assert_eq!(
+            used,
+            [
+                "key-one-aaaa",
+                "key-two-bbbb",
+                "key-one-aaaa",
+                "key-two-bbbb"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_quota_error_rests_key() {
+        let pool = pool(&["key-one-aaaa", "key-two-bbbb"]);
+        let first = pool.acquire().unwrap();
+        pool.record_request(&first);
+        pool.record_quota_error(&first);
+
+        for _ in 0..3 {
+            assert_eq!(pool.acquire().unwrap().value(), "key-two-bbbb");
+        }
+
+        // Once every key rests, the one recovering first is still handed out
+        let second = pool.acquire().unwrap();
+        pool.record_quota_error(&second);
+        assert!(pool.next_available().is_none());
+        assert_eq!(pool.acquire().unwrap().value(), "key-one-aaaa");
+
+        let usage = pool.usage();
+        assert_eq!(usage[0].key, "...aaaa");
+        assert_eq!(usage[0].usage.requests, 1);
+        assert_eq!(usage[0].quota_errors, 1);
+    }
+
+    #[test]
+    fn test_rested_key_recovers() {
+        let pool = ApiKeyPool::with_cooldown(
+            ["key-one-aaaa".to_string(), "key-two-bbbb".to_string()],
+            Duration::ZERO,
+        );
+        let first = pool.acquire().unwrap();
+        pool.record_quota_error(&first);
+        assert!(pool.next_available().is_some());
+        assert_eq!(pool.acquire().unwrap().value(), "key-one-aaaa");
+    }
+
+    #[test]
+    fn test_is_quota_error() {
+        assert!(is_quota_error(StatusCode::TOO_MANY_REQUESTS, ""));
+        assert!(is_quota_error(
+            StatusCode::FORBIDDEN,
+            r#"{"error": {"status": "RESOURCE_EXHAUSTED"}}"#
+        ));
+        assert!(is_quota_error(
+            StatusCode::FORBIDDEN,
+            "Quota exceeded for project"
+        ));
+        assert!(!is_quota_error(StatusCode::FORBIDDEN, "API key not valid"));
+        assert!(!is_quota_error(StatusCode::BAD_REQUEST, "quota"));
+    }
+
+    #[test]
+    fn test_split_and_mask_keys() {
+        assert_eq!(split_keys("a1, b2\nc3,,"), ["a1", "b2", "c3"]);
+        assert_eq!(mask_key("AIzaSyExample1234"), "...1234");
+        assert_eq!(mask_key("short"), "****");
+        assert_eq!(
+            format!("{:?}", pool(&["AIzaSyExample1234"]).acquire().unwrap()),
+            "ApiKey(...1234)"
+        );
+    }
+}
@@ -1,14 +1,21 @@
 pub mod audio;
 pub mod config;
 pub mod error;
+pub mod http;
 pub mod interactive;
+pub mod job;
+pub mod keys;
 pub mod pipeline;
+pub mod prompt;
+pub mod record;
 pub mod subtitle;
 pub mod transcribe;
 pub mod translate;
+pub mod usage;
 
 pub use config::Config;
 pub use error::{AutosubError, Result};
 pub use pipeline::{
-    generate_subtitles, print_summary, PipelineConfig, PipelineResult, PipelineStats,
+    generate_subtitles, print_summary, LanguageStats, PipelineConfig, PipelineResult,
+    PipelineStats, DEFAULT_LOW_CONFIDENCE,
 };
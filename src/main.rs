@@ -1,8 +1,18 @@
 use anyhow::{Context, Result};
-use autosub::config::{Config, OutputFormat};
+use autosub::audio::ChunkConfig;
+use autosub::config::{Config, OutputFormat, TRANSLATORS};
 use autosub::interactive::run_interactive_wizard;
-use autosub::{print_summary, PipelineConfig};
-use clap::Parser;
+use autosub::job::Job;
+use autosub::prompt::PromptTemplates;
+use autosub::record::ProviderMode;
+use autosub::subtitle::{read_subtitles, OriginalStyle, SpeakerFormat, SpeakerMap, SpeakerStyle};
+use autosub::transcribe::TranscriptCache;
+use autosub::translate::{
+    Glossary, PruneFilter, TranslationMemory, DEFAULT_BATCH_SIZE, DEFAULT_CONTEXT_LINES,
+};
+use autosub::usage::estimate_usage;
+use autosub::{print_summary, PipelineConfig, DEFAULT_LOW_CONFIDENCE};
+use clap::{Args, Parser, Subcommand};
 use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
@@ -12,8 +22,14 @@ use tracing_subscriber::FmtSubscriber;
 #[derive(Parser)]
 #[command(name = "autosub")]
 #[command(version, about = "Automatic subtitle generation using AI")]
-#[command(long_about = "Generate subtitles from video/audio files using Google Gemini API.\n\nRun without arguments for interactive mode.")]
+#[command(
+    long_about = "Generate subtitles from video/audio files using Google Gemini API.\n\nRun without arguments for interactive mode."
+)]
+#[command(args_conflicts_with_subcommands = true)]
 struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     /// Input video/audio file (omit for interactive mode)
     input: Option<PathBuf>,
 
@@ -29,14 +45,116 @@ struct Cli {
     #[arg(short, long, default_value = "en")]
     language: String,
 
-    /// Translate to target language (e.g., en, es, fr)
-    #[arg(long)]
-    translate: Option<String>,
+    /// Translate to target languages, comma-separated (e.g., es or es,fr,de)
+    #[arg(long, value_name = "CODES", value_delimiter = ',')]
+    translate: Vec<String>,
 
     /// Number of concurrent API requests
     #[arg(short, long, default_value = "4")]
     concurrency: usize,
 
+    /// Number of subtitle lines sent per translation request
+    #[arg(long, default_value_t = DEFAULT_BATCH_SIZE, value_name = "N")]
+    translation_batch_size: usize,
+
+    /// Subtitle lines before and after each translation batch given as context
+    #[arg(long, default_value_t = DEFAULT_CONTEXT_LINES, value_name = "N")]
+    translation_context: usize,
+
+    /// Summarize the transcript once (characters, setting, register) to guide translation
+    #[arg(long)]
+    translation_summary: bool,
+
+    /// Fit translations to this reading speed in characters per second (e.g. 17),
+    /// condensing those too long for their cue
+    #[arg(long, value_name = "CPS")]
+    max_cps: Option<f64>,
+
+    /// Show the original line under each translated line (bilingual subtitles)
+    #[arg(long)]
+    dual: bool,
+
+    /// How the original line is shown with --dual: plain, italic, color:<name or #hex>
+    #[arg(long, default_value = "plain", value_name = "STYLE")]
+    dual_style: String,
+
+    /// Identify speakers and label subtitles with consistent speaker names
+    #[arg(long)]
+    diarize: bool,
+
+    /// Subtitles for the deaf and hard of hearing: annotate sounds, music, lyrics and off-screen speech
+    #[arg(long)]
+    sdh: bool,
+
+    /// Rename a speaker label, e.g. "Speaker 1=Alice" (repeatable)
+    #[arg(long = "speaker", value_name = "LABEL=NAME")]
+    speakers: Vec<String>,
+
+    /// File with one "Speaker N=Name" mapping per line
+    #[arg(long, value_name = "FILE")]
+    speaker_file: Option<PathBuf>,
+
+    /// How speakers are shown: prefix, dash, voice (VTT <v> tags), none
+    #[arg(long, default_value = "prefix")]
+    speaker_style: String,
+
+    /// Skip hallucination checks (repetition loops, out-of-range timestamps, etc.)
+    #[arg(long)]
+    no_validate: bool,
+
+    /// Ignore cached transcripts and re-transcribe every chunk
+    #[arg(long)]
+    no_cache: bool,
+
+    /// Discard saved progress from an interrupted run of this file and start over
+    #[arg(long)]
+    fresh: bool,
+
+    /// Append cues to the output file as chunks finish (SRT/VTT), for previewing long jobs
+    #[arg(long, conflicts_with = "translate")]
+    incremental: bool,
+
+    /// Highlight cues with a confidence below this for review (VTT NOTE, JSON flag)
+    #[arg(long, default_value_t = DEFAULT_LOW_CONFIDENCE)]
+    low_confidence: f64,
+
+    /// Re-transcribe segments with a confidence below this from a focused clip
+    #[arg(long, value_name = "THRESHOLD")]
+    retranscribe_below: Option<f64>,
+
+    /// Save every provider request and response to this directory
+    #[arg(long, value_name = "DIR", conflicts_with = "replay")]
+    record: Option<PathBuf>,
+
+    /// Serve provider responses from a recording instead of calling the API
+    #[arg(long, value_name = "DIR")]
+    replay: Option<PathBuf>,
+
+    /// Template file replacing the built-in transcription prompt
+    #[arg(long, value_name = "FILE")]
+    transcription_prompt: Option<PathBuf>,
+
+    /// Template file replacing the built-in translation prompt
+    #[arg(long, value_name = "FILE")]
+    translation_prompt: Option<PathBuf>,
+
+    /// Glossary file (TOML) fixing how terms are translated or kept; DeepL and
+    /// LibreTranslate output is only checked against it
+    #[arg(long, value_name = "FILE")]
+    glossary: Option<PathBuf>,
+
+    /// Neither reuse nor remember translations in the translation memory
+    #[arg(long)]
+    no_tm: bool,
+
+    /// Also reuse remembered translations of lines at least this similar (0 to 1, e.g. 0.9)
+    #[arg(long, value_name = "SIMILARITY", conflicts_with = "no_tm")]
+    tm_fuzzy: Option<f64>,
+
+    /// Translation provider: gemini, deepl, libretranslate, openai [default: from config.toml, else gemini]
+    #[arg(long, value_name = "NAME", value_parser = TRANSLATORS)]
+    translator: Option<String>,
+
     /// Enable verbose logging
     #[arg(short, long)]
     verbose: bool,
@@ -49,11 +167,165 @@ struct Cli {
     #[arg(long)]
     dry_run: bool,
 
+    /// Predict token usage and cost from the audio duration without sending anything
+    #[arg(long)]
+    estimate: bool,
+
     /// Overwrite output file if it already exists
     #[arg(long)]
     force: bool,
 }
 
+#[derive(Subcommand)]
+enum Command {
+    /// Manage the on-disk transcript cache
+    Cache {
+        #[command(subcommand)]
+        action: CacheAction,
+    },
+    /// Manage the translation memory of previously translated lines
+    Tm {
+        #[command(subcommand)]
+        action: TmAction,
+    },
+    /// Translate an existing SRT, VTT or JSON subtitle file (no audio or FFmpeg needed)
+    Translate(Box<TranslateArgs>),
+    /// Resume an interrupted job (lists unfinished jobs when no ID is given)
+    Resume {
+        /// Job ID printed when the run was interrupted
+        job: Option<String>,
+
+        /// Suppress progress bars and output
+        #[arg(short, long)]
+        quiet: bool,
+    },
+}
+
+#[derive(Args)]
+struct TranslateArgs {
+    /// Subtitle file to translate (.srt, .vtt or .json)
+    input: PathBuf,
+
+    /// Target languages, comma-separated (e.g., es or es,fr,de)
+    #[arg(long, value_name = "CODES", value_delimiter = ',', required = true)]
+    to: Vec<String>,
+
+    /// Output subtitle file (defaults to input name with the target language, e.g. movie.es.srt)
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+
+    /// Output format: srt, vtt, json (defaults to the input format)
+    #[arg(short, long)]
+    format: Option<String>,
+
+    /// Language of the subtitle file (e.g., en, ja, es)
+    #[arg(short, long, default_value = "en")]
+    language: String,
+
+    /// Number of concurrent API requests
+    #[arg(short, long, default_value = "4")]
+    concurrency: usize,
+
+    /// Number of subtitle lines sent per translation request
+    #[arg(long, default_value_t = DEFAULT_BATCH_SIZE, value_name = "N")]
+    translation_batch_size: usize,
+
+    /// Subtitle lines before and after each translation batch given as context
+    #[arg(long, default_value_t = DEFAULT_CONTEXT_LINES, value_name = "N")]
+    translation_context: usize,
+
+    /// Summarize the subtitles once (characters, setting, register) to guide translation
+    #[arg(long)]
+    translation_summary: bool,
+
+    /// Fit translations to this reading speed in characters per second, condensing those too long
+    #[arg(long, value_name = "CPS")]
+    max_cps: Option<f64>,
+
+    /// Show the original line under each translated line (bilingual subtitles)
+    #[arg(long)]
+    dual: bool,
+
+    /// How the original line is shown with --dual: plain, italic, color:<name or #hex>
+    #[arg(long, default_value = "plain", value_name = "STYLE")]
+    dual_style: String,
+
+    /// Merge, split and re-time the cues before translating, as for transcripts
+    #[arg(long)]
+    post_process: bool,
+
+    /// Template file replacing the built-in translation prompt
+    #[arg(long, value_name = "FILE")]
+    translation_prompt: Option<PathBuf>,
+
+    /// Glossary file (TOML) fixing how terms are translated or kept; DeepL and
+    /// LibreTranslate output is only checked against it
+    #[arg(long, value_name = "FILE")]
+    glossary: Option<PathBuf>,
+
+    /// Neither reuse nor remember translations in the translation memory
+    #[arg(long)]
+    no_tm: bool,
+
+    /// Also reuse remembered translations of lines at least this similar (0 to 1, e.g. 0.9)
+    #[arg(long, value_name = "SIMILARITY", conflicts_with = "no_tm")]
+    tm_fuzzy: Option<f64>,
+
+    /// Translation provider: gemini, deepl, libretranslate, openai [default: from config.toml, else gemini]
+    #[arg(long, value_name = "NAME", value_parser = TRANSLATORS)]
+    translator: Option<String>,
+
+    /// Enable verbose logging
+    #[arg(short, long)]
+    verbose: bool,
+
+    /// Suppress progress bars and output
+    #[arg(short, long)]
+    quiet: bool,
+
+    /// Overwrite output file if it already exists
+    #[arg(long)]
+    force: bool,
+}
+
+#[derive(Subcommand)]
+enum CacheAction {
+    /// Show the number and size of cached transcripts
+    Stats,
+    /// Delete all cached transcripts
+    Clear,
+}
+
+#[derive(Subcommand)]
+enum TmAction {
+    /// Show the number of remembered translations per language pair and model
+    Stats,
+    /// Write every remembered translation to a JSON Lines file
+    Export {
+        /// File to write
+        file: PathBuf,
+    },
+    /// Merge translations exported from another memory
+    Import {
+        /// JSON Lines file written by `tm export`
+        file: PathBuf,
+    },
+    /// Remove remembered translations matching every given filter
+    Prune {
+        /// Translations not used in this many days
+        #[arg(long, value_name = "DAYS")]
+        unused_for: Option<u64>,
+
+        /// Translations into this language
+        #[arg(long, value_name = "CODE")]
+        language: Option<String>,
+
+        /// Translations made by this model
+        #[arg(long)]
+        model: Option<String>,
+    },
+}
+
 fn init_logging(verbose: bool) {
     let level = if verbose { Level::DEBUG } else { Level::INFO };
 
@@ -67,6 +339,121 @@ fn init_logging(verbose: bool) {
         .init();
 }
 
+fn build_speaker_format(
+    assignments: &[String],
+    speaker_file: Option<&Path>,
+    style: &str,
+) -> Result<SpeakerFormat> {
+    let mut names = match speaker_file {
+        Some(path) => SpeakerMap::load(path)
+            .with_context(|| format!("Failed to load speaker file {}", path.display()))?,
+        None => SpeakerMap::new(),
+    };
+    for assignment in assignments {
+        names.add_assignment(assignment)?;
+    }
+    let style: SpeakerStyle = style.parse().map_err(|e: String| anyhow::anyhow!(e))?;
+
+    Ok(SpeakerFormat { names, style })
+}
+
+/// Models an estimate is priced with: the first transcription provider's, since
+/// the others only take over on failure, and the translator's if it bills tokens.
+fn estimate_models(config: &Config) -> (String, Option<String>) {
+    let transcribe_model = config
+        .transcription_providers
+        .first()
+        .and_then(|provider| provider.model.clone())
+        .unwrap_or_else(|| autosub::transcribe::gemini::DEFAULT_MODEL.to_string());
+
+    let translator = config.translator_settings();
+    let default_model = match translator.provider.as_str() {
+        "gemini" => Some(autosub::translate::DEFAULT_MODEL),
+        "openai" => Some(autosub::translate::openai::DEFAULT_MODEL),
+        _ => None,
+    };
+    let translate_model =
+        default_model.map(|default| translator.model.unwrap_or_else(|| default.to_string()));
+
+    (transcribe_model, translate_model)
+}
+
+fn print_estimate(input: &Path, languages: usize, config: &Config) -> Result<()> {
+    let duration = autosub::audio::get_audio_duration(input)
+        .context("Failed to read audio duration (is ffprobe installed?)")?;
+    let chunks = autosub::audio::plan_chunks(&[], duration, &ChunkConfig::gemini()).len();
+    let (transcribe_model, translate_model) = estimate_models(config);
+    let mut usage = estimate_usage(
+        duration,
+        chunks,
+        &transcribe_model,
+        translate_model.as_deref().filter(|_| languages > 0),
+    );
+    // Every target language is translated from the same transcript
+    for entry in usage.iter_mut().skip(1) {
+        entry.usage.input_tokens *= languages as u64;
+        entry.usage.output_tokens *= languages as u64;
+        entry.usage.requests *= languages as u64;
+    }
+
+    println!();
+    println!(
+        "Estimate for {} ({:.1} min, {} chunks):",
+        input.display(),
+        duration.as_secs_f64() / 60.0,
+        chunks
+    );
+    for entry in &usage {
+        println!(
+            "  {}: ~{} in / ~{} out tokens",
+            entry.model, entry.usage.input_tokens, entry.usage.output_tokens
+        );
+    }
+    if languages > 0 && translate_model.is_none() {
+        println!(
+            "  {}: billed per character, not included",
+            config.translator_name()
+        );
+    }
+    match config.price_table().cost(&usage) {
+        Some(cost) => println!("  Estimated cost: ~${:.4}", cost),
+        None => println!("  Estimated cost: unknown (add model prices to config.toml)"),
+    }
+    println!();
+    Ok(())
+}
+
+/// Target languages from the command line, trimmed and without duplicates.
+fn target_languages(languages: &[String]) -> Result<Vec<String>> {
+    let mut translate_to: Vec<String> = Vec::new();
+    for language in languages.iter().map(|l| l.trim()).filter(|l| !l.is_empty()) {
+        if translate_to
+            .iter()
+            .any(|l| l.eq_ignore_ascii_case(language))
+        {
+            anyhow::bail!("Target language {} is listed more than once", language);
+        }
+        translate_to.push(language.to_string());
+    }
+    Ok(translate_to)
+}
+
+/// Check the translation options shared by transcription runs and `translate`.
+fn validate_translation_options(max_cps: Option<f64>, tm_fuzzy: Option<f64>) -> Result<()> {
+    if max_cps.is_some_and(|cps| !(cps > 0.0 && cps.is_finite())) {
+        anyhow::bail!("--max-cps must be a positive number of characters per second");
+    }
+    if let Some(similarity) = tm_fuzzy {
+        if !(similarity > 0.0 && similarity <= 1.0) {
+            anyhow::bail!(
+                "--tm-fuzzy must be above 0 and at most 1, got {}",
+                similarity
+            );
+        }
+    }
+    Ok(())
+}
+
 fn derive_output_path(input: &Path, format: &OutputFormat) -> PathBuf {
     let stem = input.file_stem().unwrap_or_default();
     let mut output = input.to_path_buf();
@@ -78,6 +465,10 @@ fn derive_output_path(input: &Path, format: &OutputFormat) -> PathBuf {
 async fn main() -> Result<()> {
     let cli = Cli::parse();
 
+    if let Some(command) = cli.command {
+        return run_command(command).await;
+    }
+
     // If no input provided, run interactive mode
     if cli.input.is_none() {
         return run_interactive_mode().await;
@@ -94,25 +485,110 @@ async fn main() -> Result<()> {
 
     // Parse format
     let format: OutputFormat = cli.format.parse().map_err(|e: String| anyhow::anyhow!(e))?;
+    if cli.incremental && format == OutputFormat::Json {
+        anyhow::bail!("--incremental supports SRT and VTT output only");
+    }
+    for threshold in std::iter::once(cli.low_confidence).chain(cli.retranscribe_below) {
+        if !(0.0..=1.0).contains(&threshold) {
+            anyhow::bail!(
+                "Confidence thresholds must be between 0 and 1, got {}",
+                threshold
+            );
+        }
+    }
+    validate_translation_options(cli.max_cps, cli.tm_fuzzy)?;
+
+    let speaker_format = build_speaker_format(
+        &cli.speakers,
+        cli.speaker_file.as_deref(),
+        &cli.speaker_style,
+    )?;
+    let dual_subtitles = if cli.dual {
+        if cli.translate.is_empty() {
+            anyhow::bail!("--dual needs --translate: the original is shown under the translation");
+        }
+        let style: OriginalStyle = cli
+            .dual_style
+            .parse()
+            .map_err(|e: String| anyhow::anyhow!(e))?;
+        Some(style)
+    } else {
+        None
+    };
+
+    let translate_to = target_languages(&cli.translate)?;
+
+    if cli.estimate {
+        let mut config = Config::load().context("Failed to load configuration")?;
+        if let Some(ref translator) = cli.translator {
+            config.select_translator(translator);
+        }
+        return print_estimate(&input, translate_to.len(), &config);
+    }
 
     // Derive output path if not specified
     let output = cli
         .output
         .unwrap_or_else(|| derive_output_path(&input, &format));
 
+    // With several target languages, each gets its own file next to the output path
+    let output_paths = autosub::pipeline::output_paths(
+        &output,
+        &PipelineConfig {
+            translate_to: translate_to.clone(),
+            ..Default::default()
+        },
+    );
+
     // Check if output file exists and --force not specified
-    if output.exists() && !cli.force && !cli.dry_run {
-        anyhow::bail!(
-            "Output file already exists: {}\nUse --force to overwrite.",
-            output.display()
-        );
+    if let Some(existing) = output_paths.iter().find(|path| path.exists()) {
+        if !cli.force && !cli.dry_run {
+            anyhow::bail!(
+                "Output file already exists: {}\nUse --force to overwrite.",
+                existing.display()
+            );
+        }
     }
 
     // Load and validate configuration
-    let config = Config::load().context("Failed to load configuration")?;
-    config
-        .validate()
-        .context("Configuration validation failed")?;
+    let mut config = Config::load().context("Failed to load configuration")?;
+    if let Some(ref translator) = cli.translator {
+        config.select_translator(translator);
+    }
+    let provider_mode = match (cli.record, cli.replay) {
+        (Some(dir), _) => ProviderMode::Record(dir),
+        (_, Some(dir)) => {
+            if !dir.is_dir() {
+                anyhow::bail!("Recording directory not found: {}", dir.display());
+            }
+            ProviderMode::Replay(dir)
+        }
+        _ => ProviderMode::Live,
+    };
+    // Replay runs offline and needs no API key
+    if !matches!(provider_mode, ProviderMode::Replay(_)) {
+        config
+            .validate()
+            .context("Configuration validation failed")?;
+    }
+
+    // Prompt templates from the command line take precedence over config.toml
+    let transcription_prompt = cli
+        .transcription_prompt
+        .or_else(|| config.transcription_prompt.clone());
+    let translation_prompt = cli
+        .translation_prompt
+        .or_else(|| config.translation_prompt.clone());
+    let prompts = PromptTemplates::load(
+        transcription_prompt.as_deref(),
+        translation_prompt.as_deref(),
+    )?;
+    let glossary = cli.glossary.or_else(|| config.glossary.clone());
+    let glossary_terms = glossary
+        .as_deref()
+        .map(Glossary::load)
+        .transpose()?
+        .map(|glossary| glossary.len());
 
     // Check FFmpeg availability
     autosub::audio::check_ffmpeg()
@@ -120,11 +596,13 @@ async fn main() -> Result<()> {
 
     if !cli.quiet {
         info!("Input:    {}", input.display());
-        info!("Output:   {}", output.display());
+        for path in &output_paths {
+            info!("Output:   {}", path.display());
+        }
         info!("Format:   {}", format);
         info!("Language: {}", cli.language);
-        if let Some(ref target) = cli.translate {
-            info!("Translate to: {}", target);
+        if !translate_to.is_empty() {
+            info!("Translate to: {}", translate_to.join(", "));
         }
     }
 
@@ -133,13 +611,62 @@ async fn main() -> Result<()> {
         println!();
         println!("✓ Dry run validation successful:");
         println!("  Input file:    {} (exists)", input.display());
-        println!("  Output file:   {}", output.display());
+        for path in &output_paths {
+            println!("  Output file:   {}", path.display());
+        }
         println!("  Format:        {}", format);
         println!("  Language:      {}", cli.language);
         println!("  Concurrency:   {}", cli.concurrency);
+        println!(
+            "  Diarization:   {}",
+            if cli.diarize { "enabled" } else { "disabled" }
+        );
+        println!(
+            "  SDH:           {}",
+            if cli.sdh { "enabled" } else { "disabled" }
+        );
+        println!(
+            "  Cache:         {}",
+            if cli.no_cache { "disabled" } else { "enabled" }
+        );
+        for (kind, template) in [
+            ("Transcription", &prompts.transcription),
+            ("Translation", &prompts.translation),
+        ] {
+            if let Some(template) = template {
+                println!(
+                    "  {} prompt: custom (sha256 {})",
+                    kind,
+                    &template.hash()[..12]
+                );
+            }
+        }
+        if let Some(terms) = glossary_terms {
+            println!("  Glossary:      {} terms", terms);
+        }
+        if !translate_to.is_empty() {
+            println!("  Translator:    {}", config.translator_name());
+            match TranslationMemory::open_default() {
+                _ if cli.no_tm => println!("  Memory:        disabled"),
+                Ok(memory) => println!("  Memory:        {} remembered translations", memory.len()),
+                Err(e) => println!("  Memory:        unavailable ({})", e),
+            }
+        }
+        if let Some(ref style) = dual_subtitles {
+            println!("  Dual:          original line ({})", style);
+        }
         println!("  FFmpeg:        available");
-        println!("  Gemini API:    configured");
-        if output.exists() {
+        match provider_mode {
+            ProviderMode::Replay(ref dir) => {
+                println!("  Gemini API:    replaying {}", dir.display())
+            }
+            ProviderMode::Record(ref dir) => println!(
+                "  Gemini API:    configured, recording to {}",
+                dir.display()
+            ),
+            ProviderMode::Live => println!("  Gemini API:    configured"),
+        }
+        if output_paths.iter().any(|path| path.exists()) {
             println!("  ⚠ Output file exists (will be overwritten with --force)");
         }
         println!();
@@ -147,7 +674,153 @@ async fn main() -> Result<()> {
         return Ok(());
     }
 
-    run_pipeline(&input, &output, &config, cli.language, cli.translate, format, cli.concurrency, !cli.quiet).await
+    let pipeline_config = PipelineConfig {
+        format,
+        language: cli.language,
+        translate_to,
+        concurrency: cli.concurrency,
+        translation_batch_size: cli.translation_batch_size,
+        translation_context: cli.translation_context,
+        translation_summary: cli.translation_summary,
+        max_cps: cli.max_cps,
+        diarize: cli.diarize,
+        sdh: cli.sdh,
+        speaker_format,
+        validate: !cli.no_validate,
+        use_cache: !cli.no_cache,
+        resumable: true,
+        incremental: cli.incremental,
+        low_confidence: cli.low_confidence,
+        retranscribe_below: cli.retranscribe_below,
+        provider_mode,
+        transcription_prompt,
+        translation_prompt,
+        glossary,
+        translation_memory: !cli.no_tm,
+        memory_fuzzy: cli.tm_fuzzy,
+        dual_subtitles,
+        post_process: Some(autosub::subtitle::PostProcessConfig::default()),
+        show_progress: !cli.quiet,
+    };
+
+    if cli.fresh {
+        let job = Job::load(&Job::id_for(&input, &pipeline_config)?);
+        if let Ok(job) = job {
+            info!("Discarding saved progress of job {}", job.id());
+            job.remove()?;
+        }
+    }
+
+    run_pipeline(&input, &output, &config, pipeline_config).await
+}
+
+async fn run_command(command: Command) -> Result<()> {
+    match command {
+        Command::Cache { action } => {
+            let cache = TranscriptCache::open_default()?;
+            match action {
+                CacheAction::Stats => {
+                    let stats = cache.stats()?;
+                    println!("Cache directory: {}", cache.dir().display());
+                    println!("Entries:         {}", stats.entries);
+                    println!(
+                        "Size:            {:.1} MB",
+                        stats.bytes as f64 / 1_048_576.0
+                    );
+                }
+                CacheAction::Clear => {
+                    let removed = cache.clear()?;
+                    println!(
+                        "Removed {} cached transcripts ({:.1} MB)",
+                        removed.entries,
+                        removed.bytes as f64 / 1_048_576.0
+                    );
+                }
+            }
+            Ok(())
+        }
+        Command::Tm { action } => {
+            let memory = TranslationMemory::open_default()?;
+            match action {
+                TmAction::Stats => {
+                    println!("Memory file: {}", memory.path().display());
+                    println!("Entries:     {}", memory.len());
+                    for (scope, entries) in memory.scopes() {
+                        println!("  {}: {}", scope, entries);
+                    }
+                }
+                TmAction::Export { file } => {
+                    let exported = memory.export(&file)?;
+                    println!("Exported {} translations to {}", exported, file.display());
+                }
+                TmAction::Import { file } => {
+                    let imported = memory.import(&file)?;
+                    println!("Imported {} translations from {}", imported, file.display());
+                }
+                TmAction::Prune {
+                    unused_for,
+                    language,
+                    model,
+                } => {
+                    if unused_for.is_none() && language.is_none() && model.is_none() {
+                        anyhow::bail!("Give at least one of --unused-for, --language or --model");
+                    }
+                    let filter = PruneFilter {
+                        unused_since: unused_for.map(|days| {
+                            std::time::SystemTime::now()
+                                - std::time::Duration::from_secs(days * 86_400)
+                        }),
+                        target_language: language,
+                        model,
+                    };
+                    let removed = memory.prune(&filter)?;
+                    println!("Removed {} translations, {} remain", removed, memory.len());
+                }
+            }
+            Ok(())
+        }
+        Command::Translate(args) => run_translate(*args).await,
+        Command::Resume { job: None, .. } => {
+            let jobs = Job::list()?;
+            if jobs.is_empty() {
+                println!("No unfinished jobs.");
+            }
+            for job in jobs {
+                println!(
+                    "{}  {} -> {}",
+                    job.id(),
+                    job.manifest().input.display(),
+                    job.manifest().output.display()
+                );
+            }
+            Ok(())
+        }
+        Command::Resume {
+            job: Some(id),
+            quiet,
+        } => {
+            init_logging(false);
+
+            let job = Job::load(&id)?;
+            let manifest = job.manifest().clone();
+            let mut config = Config::load().context("Failed to load configuration")?;
+            // Finish with the translator the job started with, not today's default
+            config.restore_translator(&manifest.translator);
+            config
+                .validate()
+                .context("Configuration validation failed")?;
+
+            let pipeline_config = PipelineConfig {
+                show_progress: !quiet,
+                ..manifest.pipeline_config()
+            };
+            if !quiet {
+                info!("Resuming job {}: {}", id, manifest.input.display());
+            }
+
+            run_pipeline(&manifest.input, &manifest.output, &config, pipeline_config).await
+        }
+    }
 }
 
 async fn run_interactive_mode() -> Result<()> {
@@ -161,7 +834,10 @@ async fn run_interactive_mode() -> Result<()> {
     if result.output.exists() {
         use dialoguer::Confirm;
         if !Confirm::new()
-            .with_prompt(format!("Output file {} already exists. Overwrite?", result.output.display()))
+            .with_prompt(format!(
+                "Output file {} already exists. Overwrite?",
+                result.output.display()
+            ))
             .default(false)
             .interact()?
         {
@@ -173,25 +849,152 @@ async fn run_interactive_mode() -> Result<()> {
         &result.input,
         &result.output,
         &result.config,
-        result.pipeline_config.language,
-        result.pipeline_config.translate_to,
-        result.pipeline_config.format,
-        result.pipeline_config.concurrency,
-        result.pipeline_config.show_progress,
-    ).await
+        result.pipeline_config,
+    )
+    .await
 }
 
-async fn run_pipeline(
-    input: &Path,
-    output: &Path,
-    config: &Config,
-    language: String,
-    translate_to: Option<String>,
-    format: OutputFormat,
-    concurrency: usize,
-    show_progress: bool,
-) -> Result<()> {
-    // Setup Ctrl+C handler for graceful cancellation
+async fn run_translate(args: TranslateArgs) -> Result<()> {
+    init_logging(args.verbose);
+
+    // Reject unreadable subtitles before loading anything else
+    let (entries, input_format) = read_subtitles(&args.input)
+        .with_context(|| format!("Failed to read subtitles from {}", args.input.display()))?;
+    let format = match args.format {
+        Some(ref format) => format.parse().map_err(|e: String| anyhow::anyhow!(e))?,
+        None => input_format,
+    };
+    validate_translation_options(args.max_cps, args.tm_fuzzy)?;
+    let translate_to = target_languages(&args.to)?;
+    if translate_to.is_empty() {
+        anyhow::bail!("--to needs at least one target language");
+    }
+    let dual_subtitles = if args.dual {
+        let style: OriginalStyle = args
+            .dual_style
+            .parse()
+            .map_err(|e: String| anyhow::anyhow!(e))?;
+        Some(style)
+    } else {
+        None
+    };
+
+    // A single translation goes next to the input, named after its language
+    let output = match args.output {
+        Some(output) => output,
+        None if translate_to.len() == 1 => autosub::pipeline::language_output_path(
+            &derive_output_path(&args.input, &format),
+            &translate_to[0],
+        ),
+        None => derive_output_path(&args.input, &format),
+    };
+    let pipeline_config = PipelineConfig {
+        translate_to,
+        ..Default::default()
+    };
+    let output_paths = autosub::pipeline::output_paths(&output, &pipeline_config);
+    if output_paths.contains(&args.input) {
+        anyhow::bail!(
+            "Output would overwrite the input file {}",
+            args.input.display()
+        );
+    }
+    if let Some(existing) = output_paths.iter().find(|path| path.exists()) {
+        if !args.force {
+            anyhow::bail!(
+                "Output file already exists: {}\nUse --force to overwrite.",
+                existing.display()
+            );
+        }
+    }
+
+    let mut config = Config::load().context("Failed to load configuration")?;
+    if let Some(ref translator) = args.translator {
+        config.select_translator(translator);
+    }
+    config
+        .validate_translation()
+        .context("Configuration validation failed")?;
+
+    if !args.quiet {
+        info!(
+            "Input:    {} ({} cues)",
+            args.input.display(),
+            entries.len()
+        );
+        for path in &output_paths {
+            info!("Output:   {}", path.display());
+        }
+        info!(
+            "Translate from {} to {} with {}",
+            args.language,
+            pipeline_config.translate_to.join(", "),
+            config.translator_name()
+        );
+    }
+
+    let pipeline_config = PipelineConfig {
+        format,
+        language: args.language,
+        concurrency: args.concurrency,
+        translation_batch_size: args.translation_batch_size,
+        translation_context: args.translation_context,
+        translation_summary: args.translation_summary,
+        max_cps: args.max_cps,
+        speaker_format: SpeakerFormat {
+            style: input_speaker_style(input_format),
+            ..Default::default()
+        },
+        translation_prompt: args
+            .translation_prompt
+            .or_else(|| config.translation_prompt.clone()),
+        glossary: args.glossary.or_else(|| config.glossary.clone()),
+        translation_memory: !args.no_tm,
+        memory_fuzzy: args.tm_fuzzy,
+        dual_subtitles,
+        post_process: args
+            .post_process
+            .then(autosub::subtitle::PostProcessConfig::default),
+        show_progress: !args.quiet,
+        ..pipeline_config
+    };
+
+    let show_progress = pipeline_config.show_progress;
+    match autosub::pipeline::translate_subtitles(
+        &args.input,
+        &output,
+        &config,
+        pipeline_config,
+        cancel_on_ctrlc(),
+    )
+    .await
+    {
+        Ok(result) => {
+            if show_progress {
+                print_summary(&result);
+            }
+            Ok(())
+        }
+        Err(e) => {
+            error!("Translation failed: {}", e);
+            Err(anyhow::anyhow!("{}", e))
+        }
+    }
+}
+
+/// How speakers read from a subtitle file are written back.
+///
+/// WebVTT voice tags are parsed off the text and rendered again; SRT and JSON
+/// cues already carry any speaker label in their text, so none is added.
+fn input_speaker_style(format: OutputFormat) -> SpeakerStyle {
+    match format {
+        OutputFormat::Vtt => SpeakerStyle::Voice,
+        OutputFormat::Srt | OutputFormat::Json => SpeakerStyle::None,
+    }
+}
+
+/// Flag set by the first Ctrl+C so the run can stop gracefully; a second one quits.
+fn cancel_on_ctrlc() -> Arc<AtomicBool> {
     let cancelled = Arc::new(AtomicBool::new(false));
     let cancelled_clone = cancelled.clone();
 
@@ -204,14 +1007,19 @@ async fn run_pipeline(
     })
     .ok();
 
-    let pipeline_config = PipelineConfig {
-        format,
-        language,
-        translate_to,
-        concurrency,
-        post_process: Some(autosub::subtitle::PostProcessConfig::default()),
-        show_progress,
-    };
+    cancelled
+}
+
+async fn run_pipeline(
+    input: &Path,
+    output: &Path,
+    config: &Config,
+    pipeline_config: PipelineConfig,
+) -> Result<()> {
+    // Setup Ctrl+C handler for graceful cancellation
+    let cancelled = cancel_on_ctrlc();
+
+    let show_progress = pipeline_config.show_progress;
 
     match autosub::pipeline::generate_subtitles_with_cancel(
         input,
@@ -252,4 +1060,165 @@ mod tests {
         let json_output = derive_output_path(&input, &OutputFormat::Json);
         assert_eq!(json_output, PathBuf::from("/path/to/video.json"));
     }
+
+    #[test]
+    fn test_build_speaker_format() {
+        let assignments = vec!["Speaker 1=Alice".to_string()];
+        let speaker_format = build_speaker_format(&assignments, None, "dash").unwrap();
+
+        assert_eq!(speaker_format.style, SpeakerStyle::Dash);
+        assert_eq!(speaker_format.names.resolve("Speaker 1"), "Alice");
+
+        assert!(build_speaker_format(&[], None, "fancy").is_err());
+        assert!(build_speaker_format(&["Alice".to_string()], None, "prefix").is_err());
+    }
+
+    #[test]
+    fn test_estimate_models() {
+        let (transcribe, translate) = estimate_models(&Config::default());
+        assert_eq!(transcribe, autosub::transcribe::gemini::DEFAULT_MODEL);
+        assert_eq!(
+            translate.as_deref(),
+            Some(autosub::translate::DEFAULT_MODEL)
+        );
+
+        let mut config = Config {
+            transcription_providers: vec![autosub::config::ProviderConfig {
+                model: Some("gemini-2.5-pro".to_string()),
+                ..autosub::config::ProviderConfig::gemini()
+            }],
+            ..Default::default()
+        };
+        config.select_translator("openai");
+        let (transcribe, translate) = estimate_models(&config);
+        assert_eq!(transcribe, "gemini-2.5-pro");
+        assert_eq!(
+            translate.as_deref(),
+            Some(autosub::translate::openai::DEFAULT_MODEL)
+        );
+
+        // Character-billed services are left out
+        config.select_translator("deepl");
+        assert_eq!(estimate_models(&config).1, None);
+    }
+
+    #[test]
+    fn test_parse_cache_command() {
+        let cli = Cli::try_parse_from(["autosub", "cache", "clear"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Some(Command::Cache {
+                action: CacheAction::Clear
+            })
+        ));
+
+        let cli = Cli::try_parse_from(["autosub", "video.mp4", "--no-cache"]).unwrap();
+        assert!(cli.command.is_none());
+        assert!(cli.no_cache);
+    }
+
+    #[test]
+    fn test_parse_tm_command() {
+        let cli = Cli::try_parse_from([
+            "autosub",
+            "tm",
+            "prune",
+            "--unused-for",
+            "90",
+            "--language",
+            "es",
+        ])
+        .unwrap();
+        assert!(matches!(
+            cli.command,
+            Some(Command::Tm {
+                action: TmAction::Prune { unused_for: Some(90), language: Some(ref lang), model: None }
+            }) if lang == "es"
+        ));
+
+        let cli = Cli::try_parse_from(["autosub", "tm", "export", "memory.jsonl"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Some(Command::Tm {
+                action: TmAction::Export { .. }
+            })
+        ));
+
+        assert!(
+            Cli::try_parse_from(["autosub", "video.mp4", "--no-tm", "--tm-fuzzy", "0.9"]).is_err()
+        );
+    }
+
+    #[test]
+    fn test_parse_translate_command() {
+        let cli = Cli::try_parse_from([
+            "autosub",
+            "translate",
+            "movie.srt",
+            "--to",
+            "es,fr",
+            "-f",
+            "vtt",
+        ])
+        .unwrap();
+        match cli.command {
+            Some(Command::Translate(args)) => {
+                assert_eq!(args.input, PathBuf::from("movie.srt"));
+                assert_eq!(args.to, vec!["es", "fr"]);
+                assert_eq!(args.format.as_deref(), Some("vtt"));
+                assert_eq!(args.language, "en");
+                assert!(!args.post_process);
+            }
+            _ => panic!("expected the translate command"),
+        }
+        assert_eq!(input_speaker_style(OutputFormat::Vtt), SpeakerStyle::Voice);
+        assert_eq!(input_speaker_style(OutputFormat::Srt), SpeakerStyle::None);
+
+        assert!(Cli::try_parse_from(["autosub", "translate", "movie.srt"]).is_err());
+
+        let cli = Cli::try_parse_from([
+            "autosub",
+            "translate",
+            "movie.srt",
+            "--to",
+            "de",
+            "--translator",
+            "deepl",
+        ])
+        .unwrap();
+        assert!(
+            matches!(cli.command, Some(Command::Translate(ref args)) if args.translator.as_deref() == Some("deepl"))
+        );
+        assert!(
+            Cli::try_parse_from(["autosub", "video.mp4", "--translator", "babelfish"]).is_err()
+        );
+        assert!(Cli::try_parse_from([
+            "autosub",
+            "video.mp4",
+            "--incremental",
+            "--translate",
+            "es"
+        ])
+        .is_err());
+        assert!(target_languages(&["es".to_string(), " ES".to_string()]).is_err());
+        assert_eq!(
+            target_languages(&["es".to_string(), "".to_string()]).unwrap(),
+            vec!["es"]
+        );
+    }
+
+    #[test]
+    fn test_parse_resume_command() {
+        let cli = Cli::try_parse_from(["autosub", "resume", "3f2a9c01d4e5"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Some(Command::Resume { job: Some(ref id), quiet: false }) if id == "3f2a9c01d4e5"
+        ));
+
+        let cli = Cli::try_parse_from(["autosub", "resume"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Some(Command::Resume { job: None, .. })
+        ));
+    }
 }
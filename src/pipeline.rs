@@ -1,12 +1,30 @@
 use crate::audio::{
-    check_ffmpeg, cleanup_chunks, create_chunks, extract_audio, get_audio_duration, plan_chunks,
-    AudioChunk, ChunkConfig,
+    check_ffmpeg, chunk_path, cleanup_chunks, create_chunk, detect_speech_regions, extract_audio,
+    get_audio_duration, plan_chunks, AudioChunk, ChunkConfig, VadConfig,
 };
 use crate::config::{Config, OutputFormat};
 use crate::error::{AutosubError, Result};
-use crate::subtitle::{convert_with_defaults, create_formatter, PostProcessConfig, SubtitleEntry};
-use crate::transcribe::{GeminiClient, Transcriber, TranscriptionOrchestrator};
-use crate::translate::create_translator;
+use crate::job::{Job, JobFingerprints};
+use crate::keys::{ApiKeyPool, KeyUsage};
+use crate::prompt::PromptTemplates;
+use crate::record::{ProviderMode, RecordStore};
+use crate::subtitle::{
+    convert_with_speaker_format, create_formatter_with_options, post_process, read_subtitles,
+    shown_durations, strip_sound_events, FormatOptions, IncrementalWriter, OriginalStyle,
+    OutputMetadata, PostProcessConfig, SpeakerFormat, SubtitleEntry,
+};
+use crate::transcribe::{
+    create_transcriber_with, FlaggedChunk, OrderedChunk, ProviderShare, RecordingTranscriber,
+    ReplayTranscriber, Transcriber, TranscriberOptions, TranscriptCache, TranscriptionOrchestrator,
+    ValidationConfig, ValidationCounts,
+};
+use crate::translate::{
+    budget_len, char_budget, create_translator_with, Glossary, LanguageTranslation, MemoryHits,
+    MemoryTranslator, RecordingTranslator, ReplayTranslator, TranslationMemory,
+    TranslationOrchestrator, Translator, TranslatorOptions, DEFAULT_BATCH_SIZE,
+    DEFAULT_CONTEXT_LINES,
+};
+use crate::usage::{merge_usage, ModelUsage};
 use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -14,8 +32,12 @@ use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tempfile::TempDir;
+use tokio::sync::mpsc;
 use tracing::{debug, info, warn};
 
+/// Default confidence below which cues are highlighted for review.
+pub const DEFAULT_LOW_CONFIDENCE: f64 = 0.5;
+
 /// Configuration for the subtitle generation pipeline.
 #[derive(Debug, Clone)]
 pub struct PipelineConfig {
@@ -23,10 +45,52 @@ pub struct PipelineConfig {
     pub format: OutputFormat,
     /// Source language code.
     pub language: String,
-    /// Target language for translation (optional).
-    pub translate_to: Option<String>,
+    /// Target languages to translate to, each written to its own output file.
+    pub translate_to: Vec<String>,
     /// Number of concurrent API requests.
     pub concurrency: usize,
+    /// Number of texts sent per translation request.
+    pub translation_batch_size: usize,
+    /// Lines before and after each translation batch shown as untranslated context.
+    pub translation_context: usize,
+    /// Summarize the whole transcript once and give the summary to every translation batch.
+    pub translation_summary: bool,
+    /// Reading speed translations must fit: each cue gets a character budget of its
+    /// duration times this rate, and longer translations are asked to be condensed.
+    pub max_cps: Option<f64>,
+    /// Identify speakers and label segments with stable speaker identities.
+    pub diarize: bool,
+    /// Subtitles for the deaf and hard of hearing: annotate sound events, music,
+    /// lyrics and off-screen speech. Without it, such annotations are stripped.
+    pub sdh: bool,
+    /// Speaker names and how speakers are rendered in the output.
+    pub speaker_format: SpeakerFormat,
+    /// Check transcripts for hallucinations and retry flagged chunks.
+    pub validate: bool,
+    /// Reuse cached transcripts for chunks transcribed in earlier runs.
+    pub use_cache: bool,
+    /// Checkpoint progress to a job directory so an interrupted run can be resumed.
+    pub resumable: bool,
+    /// Append cues to the output file as chunks finish, before the final rewrite.
+    pub incremental: bool,
+    /// Cues with a confidence below this are highlighted for review (VTT NOTE, JSON flag).
+    pub low_confidence: f64,
+    /// Re-transcribe segments with a confidence below this from a clip of just that segment.
+    pub retranscribe_below: Option<f64>,
+    /// Call providers live, record their responses, or replay a recording offline.
+    pub provider_mode: ProviderMode,
+    /// Template file replacing the built-in transcription prompt.
+    pub transcription_prompt: Option<PathBuf>,
+    /// Template file replacing the built-in translation prompt.
+    pub translation_prompt: Option<PathBuf>,
+    /// Glossary file fixing how terms are translated.
+    pub glossary: Option<PathBuf>,
+    /// Reuse and remember translated lines in the persistent translation memory.
+    pub translation_memory: bool,
+    /// Also reuse remembered translations of lines at least this similar (0 to 1).
+    pub memory_fuzzy: Option<f64>,
+    /// Show the original text under each translated cue, styled this way.
+    pub dual_subtitles: Option<OriginalStyle>,
     /// Post-processing configuration.
     pub post_process: Option<PostProcessConfig>,
     /// Show progress bars.
@@ -38,8 +102,28 @@ impl Default for PipelineConfig {
         Self {
             format: OutputFormat::default(),
             language: "en".to_string(),
-            translate_to: None,
+            translate_to: Vec::new(),
             concurrency: 4,
+            translation_batch_size: DEFAULT_BATCH_SIZE,
+            translation_context: DEFAULT_CONTEXT_LINES,
+            translation_summary: false,
+            max_cps: None,
+            diarize: false,
+            sdh: false,
+            speaker_format: SpeakerFormat::default(),
+            validate: true,
+            use_cache: true,
+            resumable: true,
+            incremental: false,
+            low_confidence: DEFAULT_LOW_CONFIDENCE,
+            retranscribe_below: None,
+            provider_mode: ProviderMode::Live,
+            transcription_prompt: None,
+            translation_prompt: None,
+            glossary: None,
+            translation_memory: true,
+            memory_fuzzy: None,
+            dual_subtitles: None,
             post_process: Some(PostProcessConfig::default()),
             show_progress: true,
         }
@@ -47,7 +131,7 @@ impl Default for PipelineConfig {
 }
 
 /// Statistics from the subtitle generation process.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct PipelineStats {
     /// Total time taken for the entire pipeline.
     pub total_time: Duration,
@@ -59,22 +143,72 @@ pub struct PipelineStats {
     pub translation_time: Option<Duration>,
     /// Number of audio chunks processed.
     pub chunks_processed: usize,
+    /// Number of audio chunks that failed to transcribe.
+    pub failed_chunks: usize,
     /// Number of subtitle entries generated.
     pub subtitle_entries: usize,
     /// Total audio duration.
     pub audio_duration: Duration,
     /// Provider used for transcription.
     pub provider: String,
-    /// Target language for translation (if performed).
-    pub translated_to: Option<String>,
+    /// Target languages translated to, in order.
+    pub translated_to: Vec<String>,
+    /// Chunks still flagged by transcript validation after retries, for review.
+    pub flagged_chunks: Vec<FlaggedChunk>,
+    /// Chunks re-transcribed because validation flagged them.
+    pub retried_chunks: usize,
+    /// Validation issues found, by kind.
+    pub validation: ValidationCounts,
+    /// Chunks served from the transcript cache.
+    pub cache_hits: usize,
+    /// Chunks restored from an interrupted run of the same job.
+    pub resumed_chunks: usize,
+    /// Chunks split and re-transcribed because the output hit the token limit.
+    pub split_chunks: usize,
+    /// Chunks transcribed by each provider, when a fallback chain is configured.
+    pub providers: Vec<ProviderShare>,
+    /// Low-confidence segments improved by focused re-transcription.
+    pub refined_segments: usize,
+    /// Cues in the output still below the low-confidence threshold.
+    pub low_confidence_cues: usize,
+    /// Glossary terms not translated as required, counted per cue, across all languages.
+    pub glossary_misses: usize,
+    /// Lines taken from the translation memory instead of the translator.
+    pub memory_hits: MemoryHits,
+    /// Translations shortened to fit the reading speed, across all languages.
+    pub condensed_cues: usize,
+    /// Translated cues still longer than their reading-speed budget, across all languages.
+    pub over_budget_cues: usize,
+    /// Output and translation statistics per target language.
+    pub languages: Vec<LanguageStats>,
+    /// Tokens used per model during this run.
+    pub token_usage: Vec<ModelUsage>,
+    /// Requests, tokens and quota errors per API key, when several keys are configured.
+    pub key_usage: Vec<KeyUsage>,
+    /// Estimated cost of this run in US dollars, if every model has a known price.
+    pub estimated_cost: Option<f64>,
+}
+
+/// Output and translation statistics for one target language.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LanguageStats {
+    pub language: String,
+    pub output_path: PathBuf,
+    pub subtitle_entries: usize,
+    /// Time from the start of translation until this language finished.
+    pub translation_time: Duration,
+    /// Glossary terms not translated as required, counted per cue.
+    pub glossary_misses: usize,
+    /// Cues still longer than their reading-speed budget.
+    pub over_budget: usize,
 }
 
 /// Result of the subtitle generation pipeline.
 #[derive(Debug)]
 pub struct PipelineResult {
-    /// Path to the output subtitle file.
+    /// Path to the output subtitle file, the first one when several languages are written.
     pub output_path: PathBuf,
-    /// Generated subtitle entries.
+    /// Generated subtitle entries of `output_path`.
     pub entries: Vec<SubtitleEntry>,
     /// Pipeline statistics.
     pub stats: PipelineStats,
@@ -102,6 +236,28 @@ impl Drop for TempCleanupGuard {
     }
 }
 
+/// Output file for `language` in a run writing several languages: `video.srt` becomes `video.es.srt`.
+pub fn language_output_path(output: &Path, language: &str) -> PathBuf {
+    let stem = output.file_stem().unwrap_or_default().to_string_lossy();
+    let name = match output.extension() {
+        Some(extension) => format!("{}.{}.{}", stem, language, extension.to_string_lossy()),
+        None => format!("{}.{}", stem, language),
+    };
+    output.with_file_name(name)
+}
+
+/// Files a run writes: `output` itself, or one per target language when there are several.
+pub fn output_paths(output: &Path, pipeline_config: &PipelineConfig) -> Vec<PathBuf> {
+    if pipeline_config.translate_to.len() <= 1 {
+        return vec![output.to_path_buf()];
+    }
+    pipeline_config
+        .translate_to
+        .iter()
+        .map(|language| language_output_path(output, language))
+        .collect()
+}
+
 /// Generate subtitles from a video or audio file.
 ///
 /// This is the main entry point for the autosub pipeline. It:
@@ -122,6 +278,9 @@ pub async fn generate_subtitles(
 }
 
 /// Generate subtitles with cancellation support.
+///
+/// When `resumable` is set, progress is checkpointed to a job directory that
+/// survives failures and cancellation and is removed once the output is written.
 pub async fn generate_subtitles_with_cancel(
     input: &Path,
     output: &Path,
@@ -129,8 +288,6 @@ pub async fn generate_subtitles_with_cancel(
     pipeline_config: PipelineConfig,
     cancelled: Arc<AtomicBool>,
 ) -> Result<PipelineResult> {
-    let start_time = Instant::now();
-
     // Validate input file exists
     if !input.exists() {
         return Err(AutosubError::FileNotFound(input.display().to_string()));
@@ -143,24 +300,236 @@ pub async fn generate_subtitles_with_cancel(
         )
     })?;
 
-    // Create temp directory for intermediate files
-    let temp_dir = TempDir::new().map_err(|e| {
-        AutosubError::Io(std::io::Error::other(format!(
-            "Failed to create temp directory: {}",
-            e
-        )))
-    })?;
+    // Reject broken prompt templates before any work is done
+    let prompts = PromptTemplates::load(
+        pipeline_config.transcription_prompt.as_deref(),
+        pipeline_config.translation_prompt.as_deref(),
+    )?;
+
+    // Recording and replay need every chunk to reach the provider, so no resuming
+    let job = if pipeline_config.resumable && pipeline_config.provider_mode.is_live() {
+        let fingerprints = JobFingerprints::new(config, &pipeline_config, &prompts);
+        let translator = config.translator_settings();
+        match Job::open_or_create(input, output, &pipeline_config, &translator, &fingerprints) {
+            Ok(job) => Some(Arc::new(job)),
+            Err(e) => {
+                warn!(
+                    "Could not create job directory, progress will not be resumable: {}",
+                    e
+                );
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    let result = run_stages(
+        input,
+        output,
+        config,
+        &pipeline_config,
+        &prompts,
+        cancelled,
+        job.clone(),
+    )
+    .await;
+
+    if let Some(job) = job {
+        match result {
+            Ok(ref result) if result.stats.failed_chunks == 0 => {
+                if let Err(e) = job.remove() {
+                    warn!("Failed to remove finished job {}: {}", job.id(), e);
+                }
+            }
+            Ok(ref result) => warn!(
+                "{} chunks failed. Retry them with: autosub resume {}",
+                result.stats.failed_chunks,
+                job.id()
+            ),
+            Err(_) => warn!("Progress saved. Resume with: autosub resume {}", job.id()),
+        }
+    }
+
+    result
+}
+
+/// Translate an existing SRT, WebVTT or JSON subtitle file without any audio.
+///
+/// Cues keep their timings and numbering unless `post_process` is set; only their text
+/// is translated into each of `translate_to` and written in `format`, one file per
+/// language when there are several.
+pub async fn translate_subtitles(
+    input: &Path,
+    output: &Path,
+    config: &Config,
+    pipeline_config: PipelineConfig,
+    cancelled: Arc<AtomicBool>,
+) -> Result<PipelineResult> {
+    let start_time = Instant::now();
+    if !input.exists() {
+        return Err(AutosubError::FileNotFound(input.display().to_string()));
+    }
+    if pipeline_config.translate_to.is_empty() {
+        return Err(AutosubError::Config(
+            "No target language to translate to".to_string(),
+        ));
+    }
+
+    let prompts = PromptTemplates::load(None, pipeline_config.translation_prompt.as_deref())?;
+    let glossary = match pipeline_config.glossary {
+        Some(ref path) => Some(Glossary::load(path)?),
+        None => None,
+    };
+    let (source_entries, input_format) = read_subtitles(input)?;
+    let source_entries = match pipeline_config.post_process {
+        Some(ref post_process_config) => post_process(source_entries, post_process_config),
+        None => source_entries,
+    };
+    info!(
+        "Read {} {} cues from {:?}, translating to {}",
+        source_entries.len(),
+        input_format,
+        input,
+        pipeline_config.translate_to.join(", ")
+    );
+
+    let multi_progress = pipeline_config.show_progress.then(MultiProgress::new);
+    let key_pool = config.key_pool();
+    let translation_stage = TranslationStage {
+        config,
+        pipeline_config: &pipeline_config,
+        prompts: &prompts,
+        glossary: glossary.as_ref(),
+        key_pool: key_pool.clone(),
+        cancelled,
+        job: None,
+        multi_progress: multi_progress.as_ref(),
+    };
+    let texts: Vec<String> = source_entries.iter().map(|e| e.text.clone()).collect();
+    let durations: Vec<Duration> = source_entries
+        .iter()
+        .map(|e| e.end.saturating_sub(e.start))
+        .collect();
+    let translation = translation_stage.run(&texts, &durations).await?;
+
+    let token_usage = merge_usage(translation.token_usage);
+    let estimated_cost = config.price_table().cost(&token_usage);
+    let metadata = OutputMetadata {
+        source_file: input.file_name().map(|n| n.to_string_lossy().into_owned()),
+        language: None,
+        provider: Some(translation.provider.clone()),
+        providers: Vec::new(),
+        token_usage: token_usage.clone(),
+        estimated_cost,
+        transcription_prompt_hash: None,
+        translation_prompt_hash: prompts.translation.as_ref().map(|t| t.hash().to_string()),
+    };
+
+    let output_paths = output_paths(output, &pipeline_config);
+    let mut outputs: Vec<Vec<SubtitleEntry>> = Vec::new();
+    let mut languages: Vec<LanguageStats> = Vec::new();
+    for (index, (path, translated)) in output_paths
+        .iter()
+        .zip(&translation.translations)
+        .enumerate()
+    {
+        let mut entries = source_entries.clone();
+        for (entry, text) in entries.iter_mut().zip(&translated.texts) {
+            entry.original_text = Some(std::mem::replace(&mut entry.text, text.clone()));
+        }
+        let metadata = OutputMetadata {
+            language: Some(translated.language.clone()),
+            ..metadata.clone()
+        };
+        let formatter = create_formatter_with_options(
+            pipeline_config.format,
+            &format_options(&pipeline_config, metadata),
+        );
+        fs::write(path, formatter.format(&entries))?;
+        info!("Wrote {} entries to {:?}", entries.len(), path);
+
+        languages.push(LanguageStats {
+            language: translated.language.clone(),
+            output_path: path.clone(),
+            subtitle_entries: entries.len(),
+            translation_time: translated.elapsed,
+            glossary_misses: translation.glossary_misses.get(index).copied().unwrap_or(0),
+            over_budget: translation.over_budget.get(index).copied().unwrap_or(0),
+        });
+        outputs.push(entries);
+    }
+    let entries = outputs.into_iter().next().unwrap_or_default();
+
+    let stats = PipelineStats {
+        total_time: start_time.elapsed(),
+        translation_time: translation.translation_time,
+        subtitle_entries: entries.len(),
+        provider: translation.provider,
+        translated_to: pipeline_config.translate_to.clone(),
+        glossary_misses: languages.iter().map(|l| l.glossary_misses).sum(),
+        memory_hits: translation.memory_hits,
+        condensed_cues: translation.condensed_cues,
+        over_budget_cues: languages.iter().map(|l| l.over_budget).sum(),
+        languages,
+        token_usage,
+        key_usage: key_pool
+            .filter(|pool| pool.len() > 1)
+            .map(|pool| pool.usage())
+            .unwrap_or_default(),
+        estimated_cost,
+        ..Default::default()
+    };
+
+    Ok(PipelineResult {
+        output_path: output_paths[0].clone(),
+        entries,
+        stats,
+        detected_language: None,
+    })
+}
+
+/// Run every pipeline stage, reusing whatever the job already holds.
+async fn run_stages(
+    input: &Path,
+    output: &Path,
+    config: &Config,
+    pipeline_config: &PipelineConfig,
+    prompts: &PromptTemplates,
+    cancelled: Arc<AtomicBool>,
+    job: Option<Arc<Job>>,
+) -> Result<PipelineResult> {
+    let start_time = Instant::now();
+
+    // A broken glossary should fail the run before any audio is processed
+    let glossary = match pipeline_config.glossary {
+        Some(ref path) if !pipeline_config.translate_to.is_empty() => Some(Glossary::load(path)?),
+        _ => None,
+    };
+
+    let output_paths = output_paths(output, pipeline_config);
+
+    // Work in the job directory if there is one, otherwise in a temp directory
+    let (work_dir, temp_dir) = match job {
+        Some(ref job) => (job.dir().to_path_buf(), None),
+        None => {
+            let temp_dir = TempDir::new().map_err(|e| {
+                AutosubError::Io(std::io::Error::other(format!(
+                    "Failed to create temp directory: {}",
+                    e
+                )))
+            })?;
+            (temp_dir.path().to_path_buf(), Some(temp_dir))
+        }
+    };
 
     let _cleanup_guard = TempCleanupGuard {
-        temp_dir: Some(TempDir::new().unwrap_or_else(|_| {
-            // Fallback: just use the existing temp_dir path
-            TempDir::new().expect("Failed to create temp dir")
-        })),
+        temp_dir,
         cancelled: cancelled.clone(),
     };
 
-    let temp_path = temp_dir.path();
-    debug!("Using temp directory: {:?}", temp_path);
+    let temp_path = work_dir.as_path();
+    debug!("Using work directory: {:?}", temp_path);
 
     // Setup progress bars if enabled
     let multi_progress = if pipeline_config.show_progress {
@@ -194,20 +563,35 @@ pub async fn generate_subtitles_with_cancel(
         pb
     });
 
-    let audio_path = temp_path.join("audio.wav");
-    let audio_metadata = extract_audio(input, &audio_path).await?;
+    let audio_path = match job {
+        Some(ref job) => job.audio_path(),
+        None => temp_path.join("audio.wav"),
+    };
+    let extracted_duration = match job {
+        Some(ref job) if job.has_audio() => {
+            info!("Reusing audio extracted by job {}", job.id());
+            get_audio_duration(&audio_path)?
+        }
+        _ => {
+            let audio_metadata = extract_audio(input, &audio_path).await?;
+            if let Some(ref job) = job {
+                job.mark_audio_extracted()?;
+            }
+            audio_metadata.duration
+        }
+    };
 
     if let Some(pb) = extraction_pb {
         pb.finish_with_message(format!(
             "✓ Audio extracted ({:.1}s)",
-            audio_metadata.duration.as_secs_f64()
+            extracted_duration.as_secs_f64()
         ));
     }
 
     let extraction_time = extraction_start.elapsed();
     info!(
         "Audio extraction complete: {:.1}s duration in {:.2}s",
-        audio_metadata.duration.as_secs_f64(),
+        extracted_duration.as_secs_f64(),
         extraction_time.as_secs_f64()
     );
 
@@ -239,18 +623,61 @@ pub async fn generate_subtitles_with_cancel(
     let chunk_config = ChunkConfig::gemini();
 
     // Get audio duration
-    let audio_duration = get_audio_duration(&audio_path).unwrap_or(audio_metadata.duration);
+    let audio_duration = get_audio_duration(&audio_path).unwrap_or(extracted_duration);
+
+    // Plan chunks (use empty regions for fixed-duration chunking). A resumed
+    // job keeps its original plan so checkpoints line up with chunk indices.
+    let planned_chunks = match job.as_ref().and_then(|job| job.load_plan()) {
+        Some(plan) => plan,
+        None => {
+            let empty_regions: Vec<crate::audio::SpeechRegion> = Vec::new();
+            let plan = plan_chunks(&empty_regions, audio_duration, &chunk_config);
+            if let Some(ref job) = job {
+                job.save_plan(&plan)?;
+            }
+            plan
+        }
+    };
 
-    // Plan chunks (use empty regions for fixed-duration chunking)
-    let empty_regions: Vec<crate::audio::SpeechRegion> = Vec::new();
-    let planned_chunks = plan_chunks(&empty_regions, audio_duration, &chunk_config);
+    // VAD regions let validation catch speech invented over silence. They are
+    // found once per job and saved with the plan, so a resumed run skips the scan.
+    let speech_regions = if pipeline_config.validate {
+        match job.as_ref().and_then(|job| job.load_speech_regions()) {
+            Some(regions) => Some(regions),
+            None => match detect_speech_regions(&audio_path, &VadConfig::default()) {
+                Ok(regions) => {
+                    if let Some(ref job) = job {
+                        job.save_speech_regions(&regions)?;
+                    }
+                    Some(regions)
+                }
+                Err(e) => {
+                    warn!("VAD failed, skipping silence check: {}", e);
+                    None
+                }
+            },
+        }
+    } else {
+        None
+    };
 
     if let Some(pb) = &chunking_pb {
         pb.set_message(format!("Creating {} chunks...", planned_chunks.len()));
     }
 
-    // Create actual chunk files
-    let chunks: Vec<AudioChunk> = create_chunks(&audio_path, &planned_chunks, temp_path).await?;
+    // Create actual chunk files, skipping chunks the job already transcribed
+    let mut chunks: Vec<AudioChunk> = Vec::with_capacity(planned_chunks.len());
+    for (index, region) in planned_chunks.iter().enumerate() {
+        if job.as_ref().is_some_and(|job| job.has_chunk(index)) {
+            chunks.push(AudioChunk {
+                region: region.clone(),
+                path: chunk_path(temp_path, index),
+                index,
+            });
+        } else {
+            chunks.push(create_chunk(&audio_path, region, index, temp_path).await?);
+        }
+    }
 
     if let Some(pb) = chunking_pb {
         pb.finish_with_message(format!("✓ Created {} audio chunks", chunks.len()));
@@ -269,29 +696,106 @@ pub async fn generate_subtitles_with_cancel(
     // ═══════════════════════════════════════════════════════════════════════
     // Stage 3: Transcription
     // ═══════════════════════════════════════════════════════════════════════
+    // Transcription and translation rotate over the same keys, so quota errors are shared
+    let key_pool = config.key_pool();
+
+    // Create transcriber (or fallback chain) with language set
+    let transcriber_options = TranscriberOptions {
+        language: Some(pipeline_config.language.clone()),
+        diarize: pipeline_config.diarize,
+        sdh: pipeline_config.sdh,
+        prompt_template: prompts.transcription.clone(),
+        key_pool: key_pool.clone(),
+    };
+    let transcriber: Box<dyn Transcriber> = match pipeline_config.provider_mode {
+        ProviderMode::Live => create_transcriber_with(config, &transcriber_options)?,
+        ProviderMode::Record(ref dir) => Box::new(RecordingTranscriber::new(
+            create_transcriber_with(config, &transcriber_options)?,
+            RecordStore::new(dir),
+        )),
+        ProviderMode::Replay(ref dir) => Box::new(ReplayTranscriber::new(RecordStore::new(dir))),
+    };
+    let provider_name = transcriber.name();
     info!(
-        "Stage 3/4: Transcribing with Gemini (concurrency: {})",
-        pipeline_config.concurrency
+        "Stage 3/4: Transcribing with {} (concurrency: {})",
+        provider_name, pipeline_config.concurrency
     );
     let transcription_start = Instant::now();
 
-    // Create transcriber with language set
-    let api_key = config.gemini_api_key.as_ref().ok_or_else(|| {
-        AutosubError::Config(
-            "Gemini API key not set. Set GEMINI_API_KEY environment variable.".to_string(),
-        )
-    })?;
-    let transcriber: Box<dyn Transcriber> = Box::new(
-        GeminiClient::new(api_key.clone()).with_language(pipeline_config.language.clone()),
-    );
-
     // Create orchestrator
-    let orchestrator = TranscriptionOrchestrator::new(transcriber, pipeline_config.concurrency)
+    let mut orchestrator = TranscriptionOrchestrator::new(transcriber, pipeline_config.concurrency)
         .with_progress(pipeline_config.show_progress);
 
-    // Process chunks
-    let (transcription_result, transcription_stats) =
-        orchestrator.process_chunks(chunks.clone()).await?;
+    if pipeline_config.validate {
+        orchestrator = orchestrator.with_validation(ValidationConfig {
+            speech_regions,
+            ..Default::default()
+        });
+    }
+
+    if pipeline_config.use_cache && pipeline_config.provider_mode.is_live() {
+        match TranscriptCache::open_default() {
+            Ok(cache) => orchestrator = orchestrator.with_cache(cache),
+            Err(e) => warn!("Transcript cache unavailable: {}", e),
+        }
+    }
+
+    if let Some(ref job) = job {
+        orchestrator = orchestrator.with_job(job.clone());
+    }
+
+    if let Some(threshold) = pipeline_config.retranscribe_below {
+        orchestrator = orchestrator.with_confidence_review(threshold);
+    }
+
+    // Process chunks, previewing finished ones in the output file if requested
+    // Previews are in the source language, so translated runs never write them
+    let preview = pipeline_config.incremental && pipeline_config.translate_to.is_empty();
+    let (transcription_result, transcription_stats) = if preview {
+        // Previews go to the output and are replaced once it is written
+        let preview = &output_paths[0];
+        let mut writer = IncrementalWriter::create(
+            preview,
+            pipeline_config.format,
+            &FormatOptions::for_speaker_style(pipeline_config.speaker_format.style)
+                .with_low_confidence(pipeline_config.low_confidence),
+            pipeline_config.post_process.clone(),
+            pipeline_config.speaker_format.clone(),
+        )?;
+        let (sender, mut receiver) = mpsc::unbounded_channel::<OrderedChunk>();
+
+        let write_cues = async {
+            while let Some(chunk) = receiver.recv().await {
+                let Some(transcript) = chunk.transcript else {
+                    continue;
+                };
+                let segments = if pipeline_config.sdh {
+                    transcript.segments
+                } else {
+                    strip_sound_events(transcript.segments)
+                };
+                if let Err(e) = writer.append(segments) {
+                    warn!("Incremental output stopped at chunk {}: {}", chunk.index, e);
+                    break;
+                }
+            }
+            debug!(
+                "Wrote {} preview cues to {:?}",
+                writer.cues_written(),
+                preview
+            );
+        };
+
+        let (result, ()) = tokio::join!(
+            orchestrator.process_chunks_streaming(chunks.clone(), sender),
+            write_cues
+        );
+        result?
+    } else {
+        orchestrator.process_chunks(chunks.clone()).await?
+    };
+
+    let mut token_usage = orchestrator.usage();
 
     let transcription_time = transcription_start.elapsed();
     info!(
@@ -313,70 +817,51 @@ pub async fn generate_subtitles_with_cancel(
     // ═══════════════════════════════════════════════════════════════════════
     // Stage 4: Translation (Optional)
     // ═══════════════════════════════════════════════════════════════════════
-    let mut translation_time: Option<Duration> = None;
-    let mut translated_to: Option<String> = None;
-
-    let mut segments = transcription_result.segments.clone();
-
-    if let Some(ref target_lang) = pipeline_config.translate_to {
-        info!("Stage 4/5: Translating to {}", target_lang);
-        let translation_start = Instant::now();
-
-        let translation_pb = multi_progress.as_ref().map(|mp| {
-            let pb = mp.add(ProgressBar::new(segments.len() as u64));
-            pb.set_style(
-                ProgressStyle::default_bar()
-                    .template("{spinner:.green} [{bar:40.cyan/blue}] {pos}/{len} {msg}")
-                    .unwrap()
-                    .progress_chars("█▓░"),
-            );
-            pb.set_message("Translating...");
-            pb
-        });
-
-        // Create translator using Gemini API key
-        let translator = create_translator(config.gemini_api_key.as_deref())?;
-
-        // Translate in batches for efficiency
-        let batch_size = 10;
-        let mut translated_segments = Vec::with_capacity(segments.len());
-
-        for batch in segments.chunks(batch_size) {
-            // Check for cancellation
-            if cancelled.load(Ordering::Relaxed) {
-                return Err(AutosubError::Transcription(
-                    "Pipeline cancelled during translation".to_string(),
-                ));
-            }
-
-            let texts: Vec<&str> = batch.iter().map(|s| s.text.as_str()).collect();
-            let translations = translator.translate_batch(&texts, target_lang).await?;
-
-            for (segment, translated_text) in batch.iter().zip(translations.into_iter()) {
-                let mut new_segment = segment.clone();
-                new_segment.text = translated_text;
-                translated_segments.push(new_segment);
-
-                if let Some(ref pb) = translation_pb {
-                    pb.inc(1);
-                }
-            }
-        }
-
-        segments = translated_segments;
-        translation_time = Some(translation_start.elapsed());
-        translated_to = Some(target_lang.clone());
-
-        if let Some(pb) = translation_pb {
-            pb.finish_with_message(format!("✓ Translated to {}", target_lang));
-        }
+    let segments = if pipeline_config.sdh {
+        transcription_result.segments.clone()
+    } else {
+        strip_sound_events(transcription_result.segments.clone())
+    };
 
+    if !pipeline_config.translate_to.is_empty() {
         info!(
-            "Translation complete: {} segments in {:.2}s",
-            segments.len(),
-            translation_time.unwrap().as_secs_f64()
+            "Stage 4/5: Translating to {}",
+            pipeline_config.translate_to.join(", ")
         );
     }
+    let translation_stage = TranslationStage {
+        config,
+        pipeline_config,
+        prompts,
+        glossary: glossary.as_ref(),
+        key_pool: key_pool.clone(),
+        cancelled: cancelled.clone(),
+        job: job.clone(),
+        multi_progress: multi_progress.as_ref(),
+    };
+    let texts: Vec<String> = segments.iter().map(|s| s.text.clone()).collect();
+    // Budgets follow the cues as they are shown, after merging and re-timing
+    let durations: Vec<Duration> = match pipeline_config.post_process {
+        Some(ref post_process_config) => shown_durations(
+            convert_with_speaker_format(segments.clone(), None, &pipeline_config.speaker_format),
+            post_process_config,
+        ),
+        None => segments
+            .iter()
+            .map(|s| s.end.saturating_sub(s.start))
+            .collect(),
+    };
+    let TranslationOutcome {
+        translations,
+        glossary_misses,
+        over_budget,
+        condensed_cues,
+        memory_hits,
+        token_usage: translation_usage,
+        translation_time,
+        ..
+    } = translation_stage.run(&texts, &durations).await?;
+    token_usage.extend(translation_usage);
 
     // Check for cancellation
     if cancelled.load(Ordering::Relaxed) {
@@ -388,10 +873,10 @@ pub async fn generate_subtitles_with_cancel(
     // ═══════════════════════════════════════════════════════════════════════
     // Stage 5: Subtitle Generation
     // ═══════════════════════════════════════════════════════════════════════
-    let stage_num = if pipeline_config.translate_to.is_some() {
-        "5/5"
-    } else {
+    let stage_num = if pipeline_config.translate_to.is_empty() {
         "4/4"
+    } else {
+        "5/5"
     };
     info!(
         "Stage {}: Generating {} subtitles",
@@ -410,29 +895,87 @@ pub async fn generate_subtitles_with_cancel(
         pb
     });
 
-    // Convert transcript to subtitle entries with post-processing
-    let subtitle_entries = if pipeline_config.post_process.is_some() {
-        convert_with_defaults(segments)
-    } else {
-        crate::subtitle::quick_convert(segments)
+    // Chunks restored from old checkpoints may not record their provider
+    let providers = transcription_stats.providers.clone();
+    let primary_provider = providers
+        .first()
+        .map(|share| share.provider.clone())
+        .unwrap_or_else(|| provider_name.to_string());
+
+    let token_usage = merge_usage(token_usage);
+    let estimated_cost = config.price_table().cost(&token_usage);
+
+    let metadata = OutputMetadata {
+        source_file: input.file_name().map(|n| n.to_string_lossy().into_owned()),
+        language: Some(pipeline_config.language.clone()),
+        provider: Some(primary_provider.clone()),
+        providers: providers.clone(),
+        token_usage: token_usage.clone(),
+        estimated_cost,
+        transcription_prompt_hash: prompts.transcription.as_ref().map(|t| t.hash().to_string()),
+        translation_prompt_hash: None,
     };
 
-    // Format subtitles
-    let formatter = create_formatter(pipeline_config.format);
-    let subtitle_content = formatter.format(&subtitle_entries);
+    // One file per target language, or a single file in the source language
+    let mut outputs: Vec<Vec<SubtitleEntry>> = Vec::new();
+    let mut languages: Vec<LanguageStats> = Vec::new();
+    for (index, path) in output_paths.iter().enumerate() {
+        let translated = translations.get(index);
+        let mut segments = segments.clone();
+        if let Some(translated) = translated {
+            for (segment, text) in segments.iter_mut().zip(&translated.texts) {
+                segment.original_text = Some(std::mem::replace(&mut segment.text, text.clone()));
+            }
+        }
+
+        // Convert transcript to subtitle entries with post-processing
+        let entries = convert_with_speaker_format(
+            segments,
+            pipeline_config.post_process.clone(),
+            &pipeline_config.speaker_format,
+        );
 
-    // Write output file
-    fs::write(output, &subtitle_content)?;
+        let metadata = match translated {
+            Some(translated) => OutputMetadata {
+                language: Some(translated.language.clone()),
+                translation_prompt_hash: prompts.translation.as_ref().map(|t| t.hash().to_string()),
+                ..metadata.clone()
+            },
+            None => metadata.clone(),
+        };
+        let formatter = create_formatter_with_options(
+            pipeline_config.format,
+            &format_options(pipeline_config, metadata),
+        );
+        fs::write(path, formatter.format(&entries))?;
+        info!("Wrote {} entries to {:?}", entries.len(), path);
+
+        if let Some(translated) = translated {
+            languages.push(LanguageStats {
+                language: translated.language.clone(),
+                output_path: path.clone(),
+                subtitle_entries: entries.len(),
+                translation_time: translated.elapsed,
+                glossary_misses: glossary_misses.get(index).copied().unwrap_or(0),
+                over_budget: over_budget.get(index).copied().unwrap_or(0),
+            });
+        }
+        outputs.push(entries);
+    }
+    let subtitle_entries = outputs.into_iter().next().unwrap_or_default();
 
     if let Some(pb) = subtitle_pb {
+        let files = match output_paths.len() {
+            1 => String::new(),
+            count => format!(" in {} files", count),
+        };
         pb.finish_with_message(format!(
-            "✓ Generated {} subtitle entries",
-            subtitle_entries.len()
+            "✓ Generated {} subtitle entries{}",
+            subtitle_entries.len(),
+            files
         ));
     }
 
-    info!("Wrote {} entries to {:?}", subtitle_entries.len(), output);
-
     // Build result
     let total_time = start_time.elapsed();
 
@@ -442,10 +985,34 @@ pub async fn generate_subtitles_with_cancel(
         transcription_time,
         translation_time,
         chunks_processed: transcription_stats.successful_chunks,
+        failed_chunks: transcription_stats.failed_chunks,
         subtitle_entries: subtitle_entries.len(),
         audio_duration,
-        provider: "gemini".to_string(),
-        translated_to,
+        provider: primary_provider,
+        translated_to: pipeline_config.translate_to.clone(),
+        flagged_chunks: transcription_stats.flagged_chunks,
+        retried_chunks: transcription_stats.retried_chunks,
+        validation: transcription_stats.validation,
+        cache_hits: transcription_stats.cache_hits,
+        resumed_chunks: transcription_stats.resumed_chunks,
+        split_chunks: transcription_stats.split_chunks,
+        providers,
+        refined_segments: transcription_stats.refined_segments,
+        low_confidence_cues: subtitle_entries
+            .iter()
+            .filter(|e| e.is_low_confidence(pipeline_config.low_confidence))
+            .count(),
+        glossary_misses: languages.iter().map(|l| l.glossary_misses).sum(),
+        memory_hits,
+        condensed_cues,
+        over_budget_cues: languages.iter().map(|l| l.over_budget).sum(),
+        languages,
+        token_usage,
+        key_usage: key_pool
+            .filter(|pool| pool.len() > 1)
+            .map(|pool| pool.usage())
+            .unwrap_or_default(),
+        estimated_cost,
     };
 
     let detected_language = if transcription_result.language != pipeline_config.language
@@ -457,13 +1024,199 @@ pub async fn generate_subtitles_with_cancel(
     };
 
     Ok(PipelineResult {
-        output_path: output.to_path_buf(),
+        output_path: output_paths[0].clone(),
         entries: subtitle_entries,
         stats,
         detected_language,
     })
 }
 
+/// How every file a run writes is rendered.
+fn format_options(pipeline_config: &PipelineConfig, metadata: OutputMetadata) -> FormatOptions {
+    let options = FormatOptions::for_speaker_style(pipeline_config.speaker_format.style)
+        .with_low_confidence(pipeline_config.low_confidence)
+        .with_metadata(metadata);
+    match pipeline_config.dual_subtitles {
+        Some(ref style) => options.with_dual(style.clone()),
+        None => options,
+    }
+}
+
+/// Everything translation needs besides the texts, shared by audio and subtitle-file runs.
+struct TranslationStage<'a> {
+    config: &'a Config,
+    pipeline_config: &'a PipelineConfig,
+    prompts: &'a PromptTemplates,
+    glossary: Option<&'a Glossary>,
+    key_pool: Option<ApiKeyPool>,
+    cancelled: Arc<AtomicBool>,
+    job: Option<Arc<Job>>,
+    multi_progress: Option<&'a MultiProgress>,
+}
+
+/// Translations of a run's cues into every target language, with their statistics.
+#[derive(Default)]
+struct TranslationOutcome {
+    /// One per target language, in order.
+    translations: Vec<LanguageTranslation>,
+    /// Glossary misses per target language.
+    glossary_misses: Vec<usize>,
+    /// Cues over their reading-speed budget per target language.
+    over_budget: Vec<usize>,
+    condensed_cues: usize,
+    memory_hits: MemoryHits,
+    token_usage: Vec<ModelUsage>,
+    /// Name of the translator used.
+    provider: String,
+    translation_time: Option<Duration>,
+}
+
+impl TranslationStage<'_> {
+    /// Translate `texts`, shown for `durations`, into every target language.
+    /// Does nothing when the run has no target language.
+    async fn run(&self, texts: &[String], durations: &[Duration]) -> Result<TranslationOutcome> {
+        let pipeline_config = self.pipeline_config;
+        let mut outcome = TranslationOutcome::default();
+        if pipeline_config.translate_to.is_empty() {
+            return Ok(outcome);
+        }
+        let languages = pipeline_config.translate_to.join(", ");
+        let translation_start = Instant::now();
+
+        let translation_pb = self.multi_progress.map(|mp| {
+            let total = texts.len() * pipeline_config.translate_to.len();
+            let pb = mp.add(ProgressBar::new(total as u64));
+            pb.set_style(
+                ProgressStyle::default_bar()
+                    .template("{spinner:.green} [{bar:40.cyan/blue}] {pos}/{len} {msg}")
+                    .unwrap()
+                    .progress_chars("█▓░"),
+            );
+            pb.set_message("Translating...");
+            pb
+        });
+
+        // Create translator using Gemini API key
+        let translator_options = TranslatorOptions {
+            prompt_template: self.prompts.translation.clone(),
+            key_pool: self.key_pool.clone(),
+            glossary: self.glossary.cloned(),
+            source_language: Some(pipeline_config.language.clone()),
+        };
+        let translator: Box<dyn Translator> = match pipeline_config.provider_mode {
+            ProviderMode::Live => create_translator_with(self.config, &translator_options)?,
+            ProviderMode::Record(ref dir) => Box::new(RecordingTranslator::new(
+                create_translator_with(self.config, &translator_options)?,
+                RecordStore::new(dir),
+            )),
+            ProviderMode::Replay(ref dir) => Box::new(ReplayTranslator::new(RecordStore::new(dir))),
+        };
+        outcome.provider = translator.name().to_string();
+
+        // Lines translated in earlier runs are reused instead of re-sent
+        let memory =
+            if pipeline_config.translation_memory && pipeline_config.provider_mode.is_live() {
+                match TranslationMemory::open_default() {
+                    Ok(memory) => Some(Arc::new(memory)),
+                    Err(e) => {
+                        warn!("Translation memory unavailable: {}", e);
+                        None
+                    }
+                }
+            } else {
+                None
+            };
+        let translator: Box<dyn Translator> = match memory {
+            Some(ref memory) => {
+                let mut translator =
+                    MemoryTranslator::new(translator, memory.clone(), &pipeline_config.language);
+                if let Some(threshold) = pipeline_config.memory_fuzzy {
+                    translator = translator.with_fuzzy(threshold);
+                }
+                Box::new(translator)
+            }
+            None => translator,
+        };
+
+        // Translate batches of every language concurrently, under the same request limit as transcription
+        let mut translation = TranslationOrchestrator::new(translator, pipeline_config.concurrency)
+            .with_batch_size(pipeline_config.translation_batch_size)
+            .with_context_lines(pipeline_config.translation_context)
+            .with_summary(pipeline_config.translation_summary)
+            .with_cancel(self.cancelled.clone());
+        if let Some(ref job) = self.job {
+            translation = translation.with_job(job.clone());
+        }
+        if let Some(ref pb) = translation_pb {
+            translation = translation.with_progress_bar(pb.clone());
+        }
+        let budgets: Vec<usize> = match pipeline_config.max_cps {
+            Some(cps) => durations
+                .iter()
+                .map(|duration| char_budget(*duration, cps))
+                .collect(),
+            None => Vec::new(),
+        };
+        if !budgets.is_empty() {
+            translation = translation.with_budgets(budgets.clone());
+        }
+
+        outcome.translations = translation
+            .translate_languages(texts, &pipeline_config.translate_to)
+            .await?;
+
+        for translated in &outcome.translations {
+            let misses = self
+                .glossary
+                .map(|glossary| glossary.check(&translated.language, texts, &translated.texts))
+                .unwrap_or_default();
+            for miss in &misses {
+                warn!(
+                    "Cue {} ({}): glossary term \"{}\" was not translated as \"{}\"",
+                    miss.index + 1,
+                    translated.language,
+                    miss.source,
+                    miss.expected
+                );
+            }
+            outcome.glossary_misses.push(misses.len());
+
+            let too_long = budgets
+                .iter()
+                .zip(&translated.texts)
+                .filter(|(budget, text)| budget_len(text) > **budget)
+                .count();
+            if too_long > 0 {
+                warn!(
+                    "{} cue(s) translated to {} are still longer than the reading speed allows",
+                    too_long, translated.language
+                );
+            }
+            outcome.over_budget.push(too_long);
+        }
+        outcome.condensed_cues = translation.condensed();
+
+        outcome.token_usage = translation.usage();
+        let translation_time = translation_start.elapsed();
+        outcome.translation_time = Some(translation_time);
+        if let Some(ref memory) = memory {
+            outcome.memory_hits = memory.hits();
+        }
+
+        if let Some(pb) = translation_pb {
+            pb.finish_with_message(format!("✓ Translated to {}", languages));
+        }
+
+        info!(
+            "Translation complete: {} segments into {} language(s) in {:.2}s",
+            texts.len(),
+            outcome.translations.len(),
+            translation_time.as_secs_f64()
+        );
+        Ok(outcome)
+    }
+}
+
 /// Print a summary of the pipeline results.
 pub fn print_summary(result: &PipelineResult) {
     println!();
@@ -471,34 +1224,164 @@ pub fn print_summary(result: &PipelineResult) {
     println!("                      Subtitle Generation Complete              ");
     println!("═══════════════════════════════════════════════════════════════");
     println!();
-    println!("  Output:     {}", result.output_path.display());
+    if result.stats.languages.len() > 1 {
+        for (index, language) in result.stats.languages.iter().enumerate() {
+            let label = if index == 0 { "  Output:" } else { "" };
+            println!(
+                "{:<14}{} ({}, {} entries)",
+                label,
+                language.output_path.display(),
+                language.language,
+                language.subtitle_entries
+            );
+        }
+    } else {
+        println!("  Output:     {}", result.output_path.display());
+    }
     println!("  Entries:    {}", result.stats.subtitle_entries);
     println!("  Provider:   {}", result.stats.provider);
-    if let Some(ref target_lang) = result.stats.translated_to {
-        println!("  Translated: {}", target_lang);
+    if result.stats.providers.len() > 1 {
+        for share in &result.stats.providers {
+            println!("              {} ({} chunks)", share.provider, share.chunks);
+        }
+    }
+    if !result.stats.translated_to.is_empty() {
+        println!("  Translated: {}", result.stats.translated_to.join(", "));
+    }
+    // Translating a subtitle file involves no audio
+    let transcribed = result.stats.audio_duration > Duration::ZERO;
+    if transcribed {
+        println!(
+            "  Duration:   {:.1}s audio",
+            result.stats.audio_duration.as_secs_f64()
+        );
     }
-    println!(
-        "  Duration:   {:.1}s audio",
-        result.stats.audio_duration.as_secs_f64()
-    );
     println!();
     println!("  Timing:");
-    println!(
-        "    Extract:     {:.2}s",
-        result.stats.extraction_time.as_secs_f64()
-    );
-    println!(
-        "    Transcribe:  {:.2}s ({} chunks)",
-        result.stats.transcription_time.as_secs_f64(),
-        result.stats.chunks_processed
-    );
+    if transcribed {
+        println!(
+            "    Extract:     {:.2}s",
+            result.stats.extraction_time.as_secs_f64()
+        );
+        println!(
+            "    Transcribe:  {:.2}s ({} chunks)",
+            result.stats.transcription_time.as_secs_f64(),
+            result.stats.chunks_processed
+        );
+    }
+    if result.stats.split_chunks > 0 {
+        println!(
+            "    Split:       {} chunks (output truncated)",
+            result.stats.split_chunks
+        );
+    }
+    if result.stats.resumed_chunks > 0 {
+        println!(
+            "    Resumed:     {}/{} chunks",
+            result.stats.resumed_chunks, result.stats.chunks_processed
+        );
+    }
+    if result.stats.cache_hits > 0 {
+        println!(
+            "    Cached:      {}/{} chunks",
+            result.stats.cache_hits, result.stats.chunks_processed
+        );
+    }
+    if result.stats.retried_chunks > 0 || !result.stats.flagged_chunks.is_empty() {
+        let v = &result.stats.validation;
+        println!(
+            "    Validation:  {} retried, {} still flagged (loops: {}, out of range: {}, density: {}, silence: {})",
+            result.stats.retried_chunks,
+            result.stats.flagged_chunks.len(),
+            v.repetition_loops,
+            v.timestamps_out_of_range,
+            v.implausible_density,
+            v.speech_in_silence
+        );
+        for chunk in &result.stats.flagged_chunks {
+            let issues: Vec<String> = chunk.issues.iter().map(|i| i.to_string()).collect();
+            println!("      Check {}: {}", chunk.region, issues.join(", "));
+        }
+    }
+    if result.stats.refined_segments > 0 {
+        println!(
+            "    Refined:     {} low-confidence segments re-transcribed",
+            result.stats.refined_segments
+        );
+    }
+    if result.stats.low_confidence_cues > 0 {
+        println!(
+            "    Review:      {} low-confidence cues flagged",
+            result.stats.low_confidence_cues
+        );
+    }
+    if result.stats.glossary_misses > 0 {
+        println!(
+            "    Glossary:    {} terms not translated as required (see warnings)",
+            result.stats.glossary_misses
+        );
+    }
+    if result.stats.condensed_cues > 0 || result.stats.over_budget_cues > 0 {
+        println!(
+            "    Reading:     {} translations condensed, {} still too long",
+            result.stats.condensed_cues, result.stats.over_budget_cues
+        );
+    }
+    if result.stats.memory_hits.total() > 0 {
+        println!(
+            "    Memory:      {} lines reused ({} exact, {} fuzzy)",
+            result.stats.memory_hits.total(),
+            result.stats.memory_hits.exact,
+            result.stats.memory_hits.fuzzy
+        );
+    }
     if let Some(translation_time) = result.stats.translation_time {
         println!("    Translate:   {:.2}s", translation_time.as_secs_f64());
+        if result.stats.languages.len() > 1 {
+            for language in &result.stats.languages {
+                println!(
+                    "      {}: {:.2}s",
+                    language.language,
+                    language.translation_time.as_secs_f64()
+                );
+            }
+        }
     }
     println!(
         "    Total:       {:.2}s",
         result.stats.total_time.as_secs_f64()
     );
+    if !result.stats.token_usage.is_empty() {
+        println!();
+        println!("  Usage:");
+        for entry in &result.stats.token_usage {
+            println!(
+                "    {}: {} in / {} out tokens ({} requests)",
+                entry.model,
+                entry.usage.input_tokens,
+                entry.usage.output_tokens,
+                entry.usage.requests
+            );
+        }
+        match result.stats.estimated_cost {
+            Some(cost) => println!("    Est. cost:   ${:.4}", cost),
+            None => println!("    Est. cost:   unknown (add model prices to config.toml)"),
+        }
+    }
+    if !result.stats.key_usage.is_empty() {
+        println!();
+        println!("  API keys:");
+        for entry in &result.stats.key_usage {
+            println!(
+                "    {}: {} requests, {} in / {} out tokens, {} quota errors",
+                entry.key,
+                entry.usage.requests,
+                entry.usage.input_tokens,
+                entry.usage.output_tokens,
+                entry.quota_errors
+            );
+        }
+    }
     if let Some(ref lang) = result.detected_language {
         println!();
         println!(
@@ -520,10 +1403,41 @@ mod tests {
         assert_eq!(config.format, OutputFormat::Srt);
         assert_eq!(config.language, "en");
         assert_eq!(config.concurrency, 4);
+        assert!(!config.diarize);
+        assert!(config.validate);
         assert!(config.post_process.is_some());
         assert!(config.show_progress);
     }
 
+    #[test]
+    fn test_output_paths_per_language() {
+        let output = Path::new("/subs/video.srt");
+        assert_eq!(output_paths(output, &PipelineConfig::default()), [output]);
+
+        // A single target language keeps the requested path
+        let spanish = PipelineConfig {
+            translate_to: vec!["es".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(output_paths(output, &spanish), [output]);
+
+        let several = PipelineConfig {
+            translate_to: vec!["es".to_string(), "pt-BR".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(
+            output_paths(output, &several),
+            [
+                PathBuf::from("/subs/video.es.srt"),
+                PathBuf::from("/subs/video.pt-BR.srt")
+            ]
+        );
+        assert_eq!(
+            language_output_path(Path::new("subs"), "fr"),
+            PathBuf::from("subs.fr")
+        );
+    }
+
     #[test]
     fn test_pipeline_stats_display() {
         let stats = PipelineStats {
@@ -535,7 +1449,8 @@ mod tests {
             subtitle_entries: 50,
             audio_duration: Duration::from_secs(300),
             provider: "gemini".to_string(),
-            translated_to: None,
+            translated_to: Vec::new(),
+            ..Default::default()
         };
 
         assert_eq!(stats.chunks_processed, 5);
@@ -553,10 +1468,11 @@ mod tests {
             subtitle_entries: 50,
             audio_duration: Duration::from_secs(300),
             provider: "gemini".to_string(),
-            translated_to: Some("es".to_string()),
+            translated_to: vec!["es".to_string()],
+            ..Default::default()
         };
 
         assert!(stats.translation_time.is_some());
-        assert_eq!(stats.translated_to, Some("es".to_string()));
+        assert_eq!(stats.translated_to, ["es"]);
     }
 }
@@ -0,0 +1,250 @@
+//! User-defined prompt templates.
+//!
+//! A template is a text file with `{placeholder}` fields that replaces the
+//! built-in transcription or translation prompt. Templates are checked when
+//! loaded: unknown placeholders are rejected, and the template must still ask
+//! for the output format the response parser expects.
+
+use crate::error::{AutosubError, Result};
+use regex::Regex;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Which prompt a template replaces.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PromptKind {
+    Transcription,
+    Translation,
+}
+
+impl PromptKind {
+    /// Placeholders a template of this kind may use.
+    pub fn placeholders(&self) -> &'static [&'static str] {
+        match self {
+            PromptKind::Transcription => &["language", "offset", "speakers", "sdh"],
+            PromptKind::Translation => &[
+                "language",
+                "texts",
+                "count",
+                "glossary",
+                "previous_context",
+                "next_context",
+                "summary",
+                "budgets",
+            ],
+        }
+    }
+
+    /// Text the template must contain so responses stay parseable, with the reason.
+    fn required(&self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            PromptKind::Transcription => {
+                &[("[MM:SS]", "ask for lines in the `[MM:SS] text` format")]
+            }
+            PromptKind::Translation => &[
+                ("{texts}", "include the texts to translate"),
+                (
+                    "\"id\"",
+                    "ask for replies as a JSON array of `{\"id\": N, \"text\": \"...\"}` objects",
+                ),
+            ],
+        }
+    }
+}
+
+impl std::fmt::Display for PromptKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PromptKind::Transcription => write!(f, "transcription"),
+            PromptKind::Translation => write!(f, "translation"),
+        }
+    }
+}
+
+/// A validated prompt template.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PromptTemplate {
+    kind: PromptKind,
+    source: String,
+    hash: String,
+}
+
+impl PromptTemplate {
+    /// Read and validate a template file.
+    pub fn load(path: &Path, kind: PromptKind) -> Result<Self> {
+        let source = std::fs::read_to_string(path).map_err(|e| {
+            AutosubError::Config(format!(
+                "Cannot read {} prompt template {}: {}",
+                kind,
+                path.display(),
+                e
+            ))
+        })?;
+        Self::parse(source, kind).map_err(|e| match e {
+            AutosubError::Config(msg) => {
+                AutosubError::Config(format!("{}: {}", path.display(), msg))
+            }
+            other => other,
+        })
+    }
+
+    /// Validate template text.
+    pub fn parse(source: impl Into<String>, kind: PromptKind) -> Result<Self> {
+        let source = source.into();
+
+        for name in placeholder_names(&source) {
+            if !kind.placeholders().contains(&name.as_str()) {
+                return Err(AutosubError::Config(format!(
+                    "Unknown placeholder {{{}}} in {} prompt template. Available: {}",
+                    name,
+                    kind,
+                    kind.placeholders()
+                        .iter()
+                        .map(|p| format!("{{{}}}", p))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )));
+            }
+        }
+
+        for (required, reason) in kind.required() {
+            if !source.contains(required) {
+                return Err(AutosubError::Config(format!(
+                    "The {} prompt template must {} (missing \"{}\")",
+                    kind, reason, required
+                )));
+            }
+        }
+
+        let hash = Sha256::digest(source.as_bytes())
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect();
+
+        Ok(Self { kind, source, hash })
+    }
+
+    pub fn kind(&self) -> PromptKind {
+        self.kind
+    }
+
+    /// SHA-256 of the template text, recorded in JSON output for reproducibility.
+    pub fn hash(&self) -> &str {
+        &self.hash
+    }
+
+    /// Fill in placeholders. Placeholders without a value render as empty text.
+    pub fn render(&self, values: &HashMap<&str, String>) -> String {
+        placeholder_regex()
+            .replace_all(&self.source, |caps: &regex::Captures| {
+                values.get(&caps[1]).cloned().unwrap_or_default()
+            })
+            .into_owned()
+    }
+}
+
+/// The templates configured for a run.
+#[derive(Debug, Clone, Default)]
+pub struct PromptTemplates {
+    pub transcription: Option<PromptTemplate>,
+    pub translation: Option<PromptTemplate>,
+}
+
+impl PromptTemplates {
+    /// Load and validate the template files that are set.
+    pub fn load(transcription: Option<&Path>, translation: Option<&Path>) -> Result<Self> {
+        Ok(Self {
+            transcription: transcription
+                .map(|path| PromptTemplate::load(path, PromptKind::Transcription))
+                .transpose()?,
+            translation: translation
+                .map(|path| PromptTemplate::load(path, PromptKind::Translation))
+                .transpose()?,
+        })
+    }
+}
+
+fn placeholder_regex() -> Regex {
+    Regex::new(r"\{([a-z_]+)\}").expect("Invalid regex")
+}
+
+fn placeholder_names(source: &str) -> Vec<String> {
+    placeholder_regex()
+        .captures_iter(source)
+        .map(|caps| caps[1].to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_placeholders() {
+        let template = PromptTemplate::parse(
+            "Transcribe {language} audio starting at {offset}.\n{speakers}Use [MM:SS] text lines.",
+            PromptKind::Transcription,
+        )
+        .unwrap();
+
+        let values = HashMap::from([
+            ("language", "ja".to_string()),
+            ("offset", "00:05:00".to_string()),
+        ]);
+        assert_eq!(
+            template.render(&values),
+            "Transcribe ja audio starting at 00:05:00.\nUse [MM:SS] text lines."
+        );
+        assert_eq!(template.hash().len(), 64);
+    }
+
+    #[test]
+    fn test_rejects_unknown_placeholder() {
+        let err = PromptTemplate::parse("[MM:SS] {lang}", PromptKind::Transcription).unwrap_err();
+        assert!(err.to_string().contains("{lang}"));
+
+        // Placeholders are only available to the prompt that fills them
+        assert!(PromptTemplate::parse("[MM:SS] {glossary}", PromptKind::Transcription).is_err());
+        assert!(
+            PromptTemplate::parse("[MM:SS] {previous_context}", PromptKind::Transcription).is_err()
+        );
+        assert!(PromptTemplate::parse(
+            r#"{texts} [{"id": N, "text": "..."}] {offset}"#,
+            PromptKind::Translation
+        )
+        .is_err());
+        assert!(PromptTemplate::parse(
+            r#"{texts} [{"id": N, "text": "..."}] {speakers}"#,
+            PromptKind::Translation
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_requires_output_contract() {
+        assert!(PromptTemplate::parse("Transcribe this.", PromptKind::Transcription).is_err());
+        assert!(
+            PromptTemplate::parse("Translate to {language}: {texts}", PromptKind::Translation)
+                .is_err()
+        );
+        // The old `[N] translation` line format no longer matches what the parser reads
+        assert!(PromptTemplate::parse(
+            "Translate to {language}, replying as [N] translation:\n{texts}",
+            PromptKind::Translation
+        )
+        .is_err());
+        assert!(PromptTemplate::parse(
+            "Translate to {language}, replying as [{\"id\": N, \"text\": \"...\"}]:\n{texts}",
+            PromptKind::Translation
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn test_hash_changes_with_text() {
+        let a = PromptTemplate::parse("[MM:SS] a", PromptKind::Transcription).unwrap();
+        let b = PromptTemplate::parse("[MM:SS] b", PromptKind::Transcription).unwrap();
+        assert_ne!(a.hash(), b.hash());
+    }
+}
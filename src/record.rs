@@ -0,0 +1,131 @@
+//! Recording and replay of provider calls.
+//!
+//! In record mode every successful transcription and translation call is saved
+//! to a directory as a JSON file holding the request and the provider's response.
+//! Replay mode serves those responses back instead of calling the API, matched
+//! by a hash of the chunk audio (transcription) or of the texts and target
+//! language (translation), so a run can be repeated offline and deterministically.
+
+use crate::error::{AutosubError, Result};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Where provider responses come from.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum ProviderMode {
+    /// Call the configured providers.
+    #[default]
+    Live,
+    /// Call the providers and save every request and response to a directory.
+    Record(PathBuf),
+    /// Serve responses from a recording instead of calling the providers.
+    Replay(PathBuf),
+}
+
+impl ProviderMode {
+    pub fn is_live(&self) -> bool {
+        *self == ProviderMode::Live
+    }
+}
+
+/// Directory of recorded provider calls, one JSON file per call.
+#[derive(Debug, Clone)]
+pub struct RecordStore {
+    dir: PathBuf,
+}
+
+impl RecordStore {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn path(&self, kind: &str, key: &str) -> PathBuf {
+        self.dir.join(kind).join(format!("{}.json", key))
+    }
+
+    /// Save a record of the given kind (e.g. `transcription`) under `key`.
+    pub fn save<T: Serialize>(&self, kind: &str, key: &str, record: &T) -> Result<()> {
+        let path = self.path(kind, key);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        // Write to a temp file first so a crash never leaves a partial record
+        let tmp = path.with_extension("json.tmp");
+        fs::write(&tmp, serde_json::to_vec_pretty(record)?)?;
+        fs::rename(&tmp, &path)?;
+        Ok(())
+    }
+
+    /// Load the record saved under `key`, failing if there is none.
+    pub fn load<T: DeserializeOwned>(&self, kind: &str, key: &str) -> Result<T> {
+        let path = self.path(kind, key);
+        let contents = fs::read_to_string(&path).map_err(|_| {
+            AutosubError::Config(format!(
+                "No recorded {} response {} in {}",
+                kind,
+                key,
+                self.dir.display()
+            ))
+        })?;
+        Ok(serde_json::from_str(&contents)?)
+    }
+}
+
+/// SHA-256 of the given parts, separated so that different splits hash differently.
+pub fn hash_parts<'a>(parts: impl IntoIterator<Item = &'a [u8]>) -> String {
+    let mut hasher = Sha256::new();
+    for part in parts {
+        hasher.update((part.len() as u64).to_le_bytes());
+        hasher.update(part);
+    }
+    hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Record {
+        text: String,
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = RecordStore::new(dir.path());
+        let record = Record {
+            text: "hello".to_string(),
+        };
+
+        store.save("transcription", "abc", &record).unwrap();
+
+        assert_eq!(
+            store.load::<Record>("transcription", "abc").unwrap(),
+            record
+        );
+        assert!(store.load::<Record>("translation", "abc").is_err());
+        assert!(dir.path().join("transcription").join("abc.json").exists());
+    }
+
+    #[test]
+    fn test_hash_parts_separates_parts() {
+        assert_ne!(
+            hash_parts([b"ab".as_slice(), b"c".as_slice()]),
+            hash_parts([b"a".as_slice(), b"bc".as_slice()])
+        );
+        assert_eq!(hash_parts([b"x".as_slice()]).len(), 64);
+    }
+}
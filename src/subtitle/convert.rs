@@ -1,25 +1,76 @@
 use super::postprocess::PostProcessConfig;
+use super::speaker::{SpeakerFormat, SpeakerStyle};
 use super::SubtitleEntry;
-use crate::transcribe::TranscriptSegment;
+use crate::transcribe::{SegmentKind, TranscriptSegment};
 
 /// Convert transcript segments to subtitle entries with optional post-processing.
 pub fn convert_to_subtitles(
     segments: Vec<TranscriptSegment>,
     config: Option<PostProcessConfig>,
 ) -> Vec<SubtitleEntry> {
+    convert_with_speaker_format(segments, config, &SpeakerFormat::default())
+}
+
+/// Convert transcript segments to subtitle entries, applying speaker names and styling.
+pub fn convert_with_speaker_format(
+    segments: Vec<TranscriptSegment>,
+    config: Option<PostProcessConfig>,
+    speaker_format: &SpeakerFormat,
+) -> Vec<SubtitleEntry> {
+    let mut previous_speaker: Option<String> = None;
+
     // Convert segments to entries
     let entries: Vec<SubtitleEntry> = segments
         .into_iter()
         .enumerate()
         .map(|(i, segment)| {
-            let text = format_text_with_speaker(&segment.text, segment.speaker.as_deref());
+            let original_text = segment
+                .original_text
+                .as_deref()
+                .map(|original| decorate_original(original, segment.kind))
+                .filter(|original| !original.is_empty());
+            let (text, speaker) = match segment.kind {
+                // Sound and music descriptions are never attributed to a speaker.
+                SegmentKind::Sound | SegmentKind::Music => {
+                    (format!("[{}]", segment.text.trim()), None)
+                }
+                kind => {
+                    let speaker = segment
+                        .speaker
+                        .as_deref()
+                        .map(|s| speaker_format.names.resolve(s).to_string());
+                    let speaker = match (kind, speaker) {
+                        (SegmentKind::OffScreen, Some(s)) => Some(format!("{} (off-screen)", s)),
+                        (_, speaker) => speaker,
+                    };
+                    let speaker_changed = speaker.is_some() && speaker != previous_speaker;
+                    let text = match kind {
+                        SegmentKind::Lyrics => format!("♪ {} ♪", segment.text.trim()),
+                        SegmentKind::OffScreen if speaker.is_none() => {
+                            format!("[off-screen] {}", segment.text.trim())
+                        }
+                        _ => segment.text.clone(),
+                    };
+                    let text = format_text_with_speaker(
+                        &text,
+                        speaker.as_deref(),
+                        speaker_changed,
+                        speaker_format.style,
+                    );
+                    previous_speaker = speaker.clone();
+                    (text, speaker)
+                }
+            };
 
             SubtitleEntry {
                 index: i + 1,
                 start: segment.start,
                 end: segment.end,
                 text,
-                speaker: segment.speaker,
+                speaker,
+                confidence: segment.confidence,
+                kind: segment.kind,
+                original_text,
             }
         })
         .collect();
@@ -35,10 +86,32 @@ pub fn convert_to_subtitles(
     }
 }
 
-/// Format text with optional speaker label prefix.
-fn format_text_with_speaker(text: &str, speaker: Option<&str>) -> String {
-    match speaker {
-        Some(s) if !s.is_empty() => format!("[{}] {}", s, text.trim()),
+/// Mark up the original of a translated cue like its translation, without the speaker.
+fn decorate_original(text: &str, kind: SegmentKind) -> String {
+    let text = text.trim();
+    match kind {
+        _ if text.is_empty() => String::new(),
+        SegmentKind::Sound | SegmentKind::Music => format!("[{}]", text),
+        SegmentKind::Lyrics => format!("♪ {} ♪", text),
+        _ => text.to_string(),
+    }
+}
+
+/// Format text with the speaker rendered according to the given style.
+///
+/// `speaker_changed` marks the first cue of a new speaker, used by the dash style.
+/// The voice style leaves the text untouched; formatters render the voice tag.
+pub fn format_text_with_speaker(
+    text: &str,
+    speaker: Option<&str>,
+    speaker_changed: bool,
+    style: SpeakerStyle,
+) -> String {
+    match (speaker, style) {
+        (Some(s), SpeakerStyle::Prefix) if !s.is_empty() => format!("[{}] {}", s, text.trim()),
+        (Some(s), SpeakerStyle::Dash) if !s.is_empty() && speaker_changed => {
+            format!("- {}", text.trim())
+        }
         _ => text.trim().to_string(),
     }
 }
@@ -64,6 +137,21 @@ fn fix_overlapping_timestamps(entries: Vec<SubtitleEntry>) -> Vec<SubtitleEntry>
     result
 }
 
+/// Reduce SDH segments to plain subtitles.
+///
+/// Sound and music descriptions are dropped; lyrics and off-screen lines are kept
+/// as ordinary speech.
+pub fn strip_sound_events(segments: Vec<TranscriptSegment>) -> Vec<TranscriptSegment> {
+    segments
+        .into_iter()
+        .filter(|segment| segment.kind.is_spoken())
+        .map(|mut segment| {
+            segment.kind = SegmentKind::Speech;
+            segment
+        })
+        .collect()
+}
+
 /// Quick conversion without post-processing.
 pub fn quick_convert(segments: Vec<TranscriptSegment>) -> Vec<SubtitleEntry> {
     convert_to_subtitles(segments, None)
@@ -87,6 +175,8 @@ mod tests {
             words: None,
             confidence: None,
             speaker: None,
+            kind: SegmentKind::Speech,
+            original_text: None,
         }
     }
 
@@ -103,6 +193,8 @@ mod tests {
             words: None,
             confidence: None,
             speaker: Some(speaker.to_string()),
+            kind: SegmentKind::Speech,
+            original_text: None,
         }
     }
 
@@ -135,6 +227,116 @@ mod tests {
         assert_eq!(entries[1].text, "[Bob] Hi there");
     }
 
+    #[test]
+    fn test_convert_with_speaker_map() {
+        let mut speaker_format = SpeakerFormat::default();
+        speaker_format
+            .names
+            .add_assignment("Speaker 1=Alice")
+            .unwrap();
+
+        let segments = vec![
+            segment_with_speaker(0, 2000, "Hello", "Speaker 1"),
+            segment_with_speaker(2500, 5000, "Hi there", "Speaker 2"),
+        ];
+
+        let entries = convert_with_speaker_format(segments, None, &speaker_format);
+
+        assert_eq!(entries[0].text, "[Alice] Hello");
+        assert_eq!(entries[0].speaker, Some("Alice".to_string()));
+        assert_eq!(entries[1].text, "[Speaker 2] Hi there");
+    }
+
+    #[test]
+    fn test_convert_sdh_kinds() {
+        let mut segments = vec![
+            segment(0, 1000, "door slams"),
+            segment(1000, 2000, "tense music"),
+            segment(2000, 3000, "Hold on"),
+            segment_with_speaker(3000, 4000, "Who's there?", "Bob"),
+            segment(4000, 5000, "Previously..."),
+        ];
+        segments[0].kind = SegmentKind::Sound;
+        segments[0].speaker = Some("Alice".to_string());
+        segments[1].kind = SegmentKind::Music;
+        segments[2].kind = SegmentKind::Lyrics;
+        segments[3].kind = SegmentKind::OffScreen;
+        segments[4].kind = SegmentKind::OffScreen;
+
+        let entries = quick_convert(segments.clone());
+
+        assert_eq!(entries[0].text, "[door slams]");
+        assert_eq!(entries[0].speaker, None);
+        assert_eq!(entries[1].text, "[tense music]");
+        assert_eq!(entries[2].text, "♪ Hold on ♪");
+        assert_eq!(entries[3].text, "[Bob (off-screen)] Who's there?");
+        assert_eq!(entries[4].text, "[off-screen] Previously...");
+        assert_eq!(entries[4].kind, SegmentKind::OffScreen);
+
+        let plain = quick_convert(strip_sound_events(segments));
+        let texts: Vec<&str> = plain.iter().map(|e| e.text.as_str()).collect();
+        assert_eq!(
+            texts,
+            vec!["Hold on", "[Bob] Who's there?", "Previously..."]
+        );
+        assert!(plain.iter().all(|e| e.kind == SegmentKind::Speech));
+    }
+
+    #[test]
+    fn test_convert_with_dash_style() {
+        let speaker_format = SpeakerFormat {
+            style: SpeakerStyle::Dash,
+            ..Default::default()
+        };
+
+        let segments = vec![
+            segment_with_speaker(0, 2000, "Hello", "Alice"),
+            segment_with_speaker(2500, 4000, "Still me", "Alice"),
+            segment_with_speaker(4500, 6000, "Hi there", "Bob"),
+        ];
+
+        let entries = convert_with_speaker_format(segments, None, &speaker_format);
+
+        assert_eq!(entries[0].text, "- Hello");
+        assert_eq!(entries[1].text, "Still me");
+        assert_eq!(entries[2].text, "- Hi there");
+    }
+
+    #[test]
+    fn test_convert_with_voice_and_none_styles() {
+        for style in [SpeakerStyle::Voice, SpeakerStyle::None] {
+            let speaker_format = SpeakerFormat {
+                style,
+                ..Default::default()
+            };
+            let segments = vec![segment_with_speaker(0, 2000, "Hello", "Alice")];
+
+            let entries = convert_with_speaker_format(segments, None, &speaker_format);
+
+            assert_eq!(entries[0].text, "Hello");
+            assert_eq!(entries[0].speaker, Some("Alice".to_string()));
+        }
+    }
+
+    #[test]
+    fn test_convert_keeps_original_text() {
+        let mut segments = vec![
+            segment_with_speaker(0, 2000, "Hola", "Alice"),
+            segment(3000, 4000, "risas"),
+            segment(5000, 6000, "Adiós"),
+        ];
+        segments[0].original_text = Some(" Hello ".to_string());
+        segments[1].original_text = Some("laughter".to_string());
+        segments[1].kind = SegmentKind::Sound;
+
+        let entries = quick_convert(segments);
+
+        assert_eq!(entries[0].text, "[Alice] Hola");
+        assert_eq!(entries[0].original_text.as_deref(), Some("Hello"));
+        assert_eq!(entries[1].original_text.as_deref(), Some("[laughter]"));
+        assert_eq!(entries[2].original_text, None);
+    }
+
     #[test]
     fn test_fix_overlapping_timestamps() {
         let entries = vec![
@@ -144,6 +346,9 @@ mod tests {
                 end: Duration::from_millis(3000), // Overlaps with next
                 text: "First".to_string(),
                 speaker: None,
+                confidence: None,
+                kind: SegmentKind::Speech,
+                original_text: None,
             },
             SubtitleEntry {
                 index: 2,
@@ -151,6 +356,9 @@ mod tests {
                 end: Duration::from_millis(5000),
                 text: "Second".to_string(),
                 speaker: None,
+                confidence: None,
+                kind: SegmentKind::Speech,
+                original_text: None,
             },
         ];
 
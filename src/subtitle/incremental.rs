@@ -0,0 +1,199 @@
+//! Incremental subtitle output.
+//!
+//! Appends finalized cues to the output file as transcribed chunks arrive, so
+//! long jobs can be previewed while they run. Post-processing is applied per
+//! chunk; the pipeline rewrites the file with the fully processed result at the end.
+
+use super::postprocess::PostProcessConfig;
+use super::speaker::SpeakerFormat;
+use super::srt::SrtFormatter;
+use super::vtt::{self, VttFormatter};
+use super::{convert_with_speaker_format, FormatOptions};
+use crate::config::OutputFormat;
+use crate::error::{AutosubError, Result};
+use crate::transcribe::TranscriptSegment;
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+
+/// Appends SRT or WebVTT cues to an output file as they are finalized.
+pub struct IncrementalWriter {
+    file: File,
+    format: OutputFormat,
+    voice_tags: bool,
+    low_confidence: Option<f64>,
+    post_process: Option<PostProcessConfig>,
+    speaker_format: SpeakerFormat,
+    cues_written: usize,
+}
+
+impl IncrementalWriter {
+    /// Create (or truncate) the output file and write the format header.
+    pub fn create(
+        path: &Path,
+        format: OutputFormat,
+        options: &FormatOptions,
+        post_process: Option<PostProcessConfig>,
+        speaker_format: SpeakerFormat,
+    ) -> Result<Self> {
+        if format == OutputFormat::Json {
+            return Err(AutosubError::Config(
+                "Incremental output supports SRT and VTT only".to_string(),
+            ));
+        }
+
+        let mut file = File::create(path)?;
+        if format == OutputFormat::Vtt {
+            file.write_all(vtt::HEADER.as_bytes())?;
+            file.flush()?;
+        }
+
+        Ok(Self {
+            file,
+            format,
+            voice_tags: options.voice_tags,
+            low_confidence: options.low_confidence,
+            post_process,
+            speaker_format,
+            cues_written: 0,
+        })
+    }
+
+    /// Convert the segments of one chunk to cues and append them.
+    ///
+    /// Returns the number of cues written.
+    pub fn append(&mut self, segments: Vec<TranscriptSegment>) -> Result<usize> {
+        let entries =
+            convert_with_speaker_format(segments, self.post_process.clone(), &self.speaker_format);
+
+        let first_cue = self.cues_written;
+        let mut output = String::new();
+        for mut entry in entries {
+            self.cues_written += 1;
+            entry.index = self.cues_written;
+            match self.format {
+                OutputFormat::Srt => {
+                    if self.cues_written > 1 {
+                        output.push('\n');
+                    }
+                    output.push_str(&SrtFormatter::default().format_cue(&entry));
+                }
+                OutputFormat::Vtt => {
+                    let formatter = VttFormatter {
+                        voice_tags: self.voice_tags,
+                        low_confidence: self.low_confidence,
+                        dual: None,
+                    };
+                    output.push_str(&formatter.format_cue(&entry));
+                }
+                OutputFormat::Json => unreachable!("rejected in IncrementalWriter::create"),
+            }
+        }
+
+        self.file.write_all(output.as_bytes())?;
+        self.file.flush()?;
+        Ok(self.cues_written - first_cue)
+    }
+
+    /// Total cues written so far.
+    pub fn cues_written(&self) -> usize {
+        self.cues_written
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::subtitle::{create_formatter, SubtitleEntry};
+    use crate::transcribe::SegmentKind;
+    use std::time::Duration;
+
+    fn segment(start: u64, text: &str) -> TranscriptSegment {
+        TranscriptSegment {
+            text: text.to_string(),
+            start: Duration::from_secs(start),
+            end: Duration::from_secs(start + 2),
+            words: None,
+            confidence: None,
+            speaker: None,
+            kind: SegmentKind::Speech,
+            original_text: None,
+        }
+    }
+
+    fn entry(index: usize, start: u64, text: &str) -> SubtitleEntry {
+        SubtitleEntry {
+            index,
+            start: Duration::from_secs(start),
+            end: Duration::from_secs(start + 2),
+            text: text.to_string(),
+            speaker: None,
+            confidence: None,
+            kind: SegmentKind::Speech,
+            original_text: None,
+        }
+    }
+
+    #[test]
+    fn test_appended_srt_matches_full_output() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("out.srt");
+        let mut writer = IncrementalWriter::create(
+            &path,
+            OutputFormat::Srt,
+            &FormatOptions::default(),
+            None,
+            SpeakerFormat::default(),
+        )
+        .unwrap();
+
+        writer.append(vec![segment(0, "First")]).unwrap();
+        writer.append(Vec::new()).unwrap();
+        writer
+            .append(vec![segment(10, "Second"), segment(20, "Third")])
+            .unwrap();
+        assert_eq!(writer.cues_written(), 3);
+
+        let expected = create_formatter(OutputFormat::Srt).format(&[
+            entry(1, 0, "First"),
+            entry(2, 10, "Second"),
+            entry(3, 20, "Third"),
+        ]);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_appended_vtt_matches_full_output() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("out.vtt");
+        let mut writer = IncrementalWriter::create(
+            &path,
+            OutputFormat::Vtt,
+            &FormatOptions::default(),
+            None,
+            SpeakerFormat::default(),
+        )
+        .unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "WEBVTT\n\n");
+
+        writer.append(vec![segment(0, "First")]).unwrap();
+        writer.append(vec![segment(10, "Second")]).unwrap();
+
+        let expected = create_formatter(OutputFormat::Vtt)
+            .format(&[entry(1, 0, "First"), entry(2, 10, "Second")]);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_json_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let result = IncrementalWriter::create(
+            &dir.path().join("out.json"),
+            OutputFormat::Json,
+            &FormatOptions::default(),
+            None,
+            SpeakerFormat::default(),
+        );
+        assert!(result.is_err());
+    }
+}
@@ -1,12 +1,22 @@
 // JSON subtitle format
 use super::{SubtitleEntry, SubtitleFormatter};
-use serde::Serialize;
+use crate::error::{AutosubError, Result};
+use crate::transcribe::{ProviderShare, SegmentKind};
+use crate::usage::ModelUsage;
+use serde::{Deserialize, Serialize};
 
 #[derive(Default)]
 pub struct JsonFormatter {
     pub source_file: Option<String>,
     pub language: Option<String>,
     pub provider: Option<String>,
+    pub providers: Vec<ProviderShare>,
+    pub token_usage: Vec<ModelUsage>,
+    pub estimated_cost: Option<f64>,
+    pub transcription_prompt_hash: Option<String>,
+    pub translation_prompt_hash: Option<String>,
+    /// Flag cues whose confidence is below this threshold.
+    pub low_confidence: Option<f64>,
 }
 
 #[derive(Serialize)]
@@ -23,7 +33,21 @@ struct JsonMetadata {
     language: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     provider: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    providers: Vec<ProviderShare>,
     subtitle_count: usize,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    token_usage: Vec<ModelUsage>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    estimated_cost_usd: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    transcription_prompt_sha256: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    translation_prompt_sha256: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    low_confidence_threshold: Option<f64>,
+    #[serde(skip_serializing_if = "is_zero")]
+    low_confidence_count: usize,
 }
 
 #[derive(Serialize)]
@@ -35,17 +59,44 @@ struct JsonSubtitle {
     end_formatted: String,
     text: String,
     #[serde(skip_serializing_if = "Option::is_none")]
+    original_text: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     speaker: Option<String>,
+    #[serde(skip_serializing_if = "is_speech")]
+    kind: SegmentKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    confidence: Option<f64>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    low_confidence: bool,
+}
+
+fn is_zero(n: &usize) -> bool {
+    *n == 0
+}
+
+fn is_speech(kind: &SegmentKind) -> bool {
+    *kind == SegmentKind::Speech
 }
 
 impl SubtitleFormatter for JsonFormatter {
     fn format(&self, entries: &[SubtitleEntry]) -> String {
+        let is_low = |e: &SubtitleEntry| {
+            self.low_confidence
+                .is_some_and(|threshold| e.is_low_confidence(threshold))
+        };
         let output = JsonOutput {
             metadata: JsonMetadata {
                 source_file: self.source_file.clone(),
                 language: self.language.clone(),
                 provider: self.provider.clone(),
+                providers: self.providers.clone(),
                 subtitle_count: entries.len(),
+                token_usage: self.token_usage.clone(),
+                estimated_cost_usd: self.estimated_cost,
+                transcription_prompt_sha256: self.transcription_prompt_hash.clone(),
+                translation_prompt_sha256: self.translation_prompt_hash.clone(),
+                low_confidence_threshold: self.low_confidence,
+                low_confidence_count: entries.iter().filter(|e| is_low(e)).count(),
             },
             subtitles: entries
                 .iter()
@@ -56,7 +107,11 @@ impl SubtitleFormatter for JsonFormatter {
                     start_formatted: format_timestamp(e.start),
                     end_formatted: format_timestamp(e.end),
                     text: e.text.clone(),
+                    original_text: e.original_text.clone(),
                     speaker: e.speaker.clone(),
+                    kind: e.kind,
+                    confidence: e.confidence,
+                    low_confidence: is_low(e),
                 })
                 .collect(),
         };
@@ -69,6 +124,53 @@ impl SubtitleFormatter for JsonFormatter {
     }
 }
 
+/// The parts of our own JSON output needed to read it back.
+#[derive(Deserialize)]
+struct JsonInput {
+    subtitles: Vec<JsonCue>,
+}
+
+#[derive(Deserialize)]
+struct JsonCue {
+    index: usize,
+    start: f64,
+    end: f64,
+    text: String,
+    #[serde(default)]
+    speaker: Option<String>,
+    #[serde(default)]
+    kind: SegmentKind,
+    #[serde(default)]
+    confidence: Option<f64>,
+}
+
+/// Parse subtitles written by [`JsonFormatter`].
+pub fn parse(content: &str) -> Result<Vec<SubtitleEntry>> {
+    let input: JsonInput =
+        serde_json::from_str(content).map_err(|e| AutosubError::SubtitleParse(e.to_string()))?;
+    input
+        .subtitles
+        .into_iter()
+        .map(|cue| {
+            let time = |secs: f64| {
+                std::time::Duration::try_from_secs_f64(secs).map_err(|_| {
+                    AutosubError::SubtitleParse(format!("cue {}: invalid time {}", cue.index, secs))
+                })
+            };
+            Ok(SubtitleEntry {
+                index: cue.index,
+                start: time(cue.start)?,
+                end: time(cue.end)?,
+                text: cue.text,
+                speaker: cue.speaker,
+                confidence: cue.confidence,
+                kind: cue.kind,
+                original_text: None,
+            })
+        })
+        .collect()
+}
+
 fn format_timestamp(d: std::time::Duration) -> String {
     let total_secs = d.as_secs();
     let hours = total_secs / 3600;
@@ -91,6 +193,9 @@ mod tests {
             end: Duration::from_millis(4000),
             text: "Hello, world!".to_string(),
             speaker: None,
+            confidence: None,
+            kind: SegmentKind::Speech,
+            original_text: None,
         }];
 
         let formatter = JsonFormatter::default();
@@ -100,4 +205,128 @@ mod tests {
         assert!(output.contains("\"text\": \"Hello, world!\""));
         assert!(output.contains("\"start\": 1.5"));
     }
+
+    #[test]
+    fn test_json_original_text() {
+        let entry = |original: Option<&str>| SubtitleEntry {
+            index: 1,
+            start: Duration::from_millis(0),
+            end: Duration::from_millis(1000),
+            text: "Hola".to_string(),
+            speaker: None,
+            confidence: None,
+            kind: SegmentKind::Speech,
+            original_text: original.map(str::to_string),
+        };
+        let output = JsonFormatter::default().format(&[entry(Some("Hello")), entry(None)]);
+        let json: serde_json::Value = serde_json::from_str(&output).unwrap();
+
+        assert_eq!(json["subtitles"][0]["text"], "Hola");
+        assert_eq!(json["subtitles"][0]["original_text"], "Hello");
+        assert!(json["subtitles"][1].get("original_text").is_none());
+    }
+
+    #[test]
+    fn test_json_prompt_hashes() {
+        let formatter = JsonFormatter {
+            transcription_prompt_hash: Some("abc123".to_string()),
+            ..Default::default()
+        };
+        let json: serde_json::Value = serde_json::from_str(&formatter.format(&[])).unwrap();
+
+        assert_eq!(json["metadata"]["transcription_prompt_sha256"], "abc123");
+        assert!(json["metadata"].get("translation_prompt_sha256").is_none());
+    }
+
+    #[test]
+    fn test_json_segment_kind() {
+        let entry = |kind| SubtitleEntry {
+            index: 1,
+            start: Duration::from_millis(0),
+            end: Duration::from_millis(1000),
+            text: "[laughter]".to_string(),
+            speaker: None,
+            confidence: None,
+            kind,
+            original_text: None,
+        };
+        let output = JsonFormatter::default()
+            .format(&[entry(SegmentKind::Sound), entry(SegmentKind::Speech)]);
+        let json: serde_json::Value = serde_json::from_str(&output).unwrap();
+
+        assert_eq!(json["subtitles"][0]["kind"], "sound");
+        assert!(json["subtitles"][1].get("kind").is_none());
+    }
+
+    #[test]
+    fn test_json_usage_metadata() {
+        let formatter = JsonFormatter {
+            token_usage: vec![ModelUsage {
+                model: "gemini-2.5-flash".to_string(),
+                usage: crate::usage::TokenUsage {
+                    input_tokens: 19200,
+                    output_tokens: 3000,
+                    requests: 1,
+                },
+            }],
+            estimated_cost: Some(0.0267),
+            ..Default::default()
+        };
+        let output = formatter.format(&[]);
+
+        assert!(output.contains("\"model\": \"gemini-2.5-flash\""));
+        assert!(output.contains("\"input_tokens\": 19200"));
+        assert!(output.contains("\"estimated_cost_usd\": 0.0267"));
+
+        let plain = JsonFormatter::default().format(&[]);
+        assert!(!plain.contains("token_usage"));
+    }
+
+    #[test]
+    fn test_json_providers_metadata() {
+        let formatter = JsonFormatter {
+            provider: Some("gemini/gemini-2.5-flash".to_string()),
+            providers: vec![
+                ProviderShare {
+                    provider: "gemini/gemini-2.5-flash".to_string(),
+                    chunks: 9,
+                },
+                ProviderShare {
+                    provider: "gemini/gemini-2.0-flash".to_string(),
+                    chunks: 1,
+                },
+            ],
+            ..Default::default()
+        };
+        let output = formatter.format(&[]);
+
+        assert!(output.contains("\"provider\": \"gemini/gemini-2.0-flash\""));
+        assert!(output.contains("\"chunks\": 9"));
+        assert!(!JsonFormatter::default().format(&[]).contains("providers"));
+    }
+
+    #[test]
+    fn test_json_low_confidence_flag() {
+        let entry = |index, confidence| SubtitleEntry {
+            index,
+            start: Duration::from_secs(index as u64),
+            end: Duration::from_secs(index as u64 + 1),
+            text: "words".to_string(),
+            speaker: None,
+            confidence,
+            kind: SegmentKind::Speech,
+            original_text: None,
+        };
+        let formatter = JsonFormatter {
+            low_confidence: Some(0.5),
+            ..Default::default()
+        };
+        let output = formatter.format(&[entry(1, Some(0.92)), entry(2, Some(0.41))]);
+        let json: serde_json::Value = serde_json::from_str(&output).unwrap();
+
+        assert_eq!(json["metadata"]["low_confidence_count"], 1);
+        assert_eq!(json["subtitles"][0]["confidence"], 0.92);
+        assert!(json["subtitles"][0].get("low_confidence").is_none());
+        assert_eq!(json["subtitles"][1]["low_confidence"], true);
+    }
 }
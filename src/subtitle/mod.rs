@@ -1,13 +1,25 @@
 pub mod convert;
+pub mod incremental;
 pub mod json;
 pub mod postprocess;
+pub mod speaker;
 pub mod srt;
 pub mod vtt;
 
-pub use convert::{convert_to_subtitles, convert_with_defaults, quick_convert};
-pub use postprocess::{post_process, PostProcessConfig};
+pub use convert::{
+    convert_to_subtitles, convert_with_defaults, convert_with_speaker_format, quick_convert,
+    strip_sound_events,
+};
+pub use incremental::IncrementalWriter;
+pub use postprocess::{post_process, shown_durations, PostProcessConfig};
+pub use speaker::{SpeakerFormat, SpeakerMap, SpeakerStyle};
 
 use crate::config::OutputFormat;
+use crate::error::{AutosubError, Result};
+use crate::transcribe::{ProviderShare, SegmentKind};
+use crate::usage::ModelUsage;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
 use std::time::Duration;
 
 #[derive(Debug, Clone)]
@@ -17,6 +29,19 @@ pub struct SubtitleEntry {
     pub end: Duration,
     pub text: String,
     pub speaker: Option<String>,
+    /// Transcription confidence from 0 to 1, if the provider reported one.
+    pub confidence: Option<f64>,
+    /// What the cue contains; only SDH output has cues other than speech.
+    pub kind: SegmentKind,
+    /// Source-language text of a translated cue, shown by bilingual output.
+    pub original_text: Option<String>,
+}
+
+impl SubtitleEntry {
+    /// Whether the provider reported a confidence below `threshold` for this cue.
+    pub fn is_low_confidence(&self, threshold: f64) -> bool {
+        self.confidence.is_some_and(|c| c < threshold)
+    }
 }
 
 pub trait SubtitleFormatter {
@@ -24,10 +49,336 @@ pub trait SubtitleFormatter {
     fn extension(&self) -> &'static str;
 }
 
+/// Details about the run, included by formats that carry metadata (JSON).
+#[derive(Debug, Clone, Default)]
+pub struct OutputMetadata {
+    pub source_file: Option<String>,
+    pub language: Option<String>,
+    pub provider: Option<String>,
+    /// Chunks transcribed by each provider of the fallback chain.
+    pub providers: Vec<ProviderShare>,
+    /// Tokens used per model.
+    pub token_usage: Vec<ModelUsage>,
+    /// Estimated cost of the run in US dollars.
+    pub estimated_cost: Option<f64>,
+    /// SHA-256 of the user-defined transcription prompt template, if one was used.
+    pub transcription_prompt_hash: Option<String>,
+    /// SHA-256 of the user-defined translation prompt template, if one was used.
+    pub translation_prompt_hash: Option<String>,
+}
+
+/// Options that affect how entries are rendered by a formatter.
+#[derive(Debug, Clone, Default)]
+pub struct FormatOptions {
+    /// Render speakers as WebVTT voice tags (`<v Name>`).
+    pub voice_tags: bool,
+    /// Highlight cues whose confidence is below this threshold.
+    pub low_confidence: Option<f64>,
+    /// Run details for formats that carry metadata.
+    pub metadata: OutputMetadata,
+    /// Show the original text under translated cues, styled this way.
+    pub dual: Option<OriginalStyle>,
+}
+
+impl FormatOptions {
+    /// Options matching the given speaker style.
+    pub fn for_speaker_style(style: SpeakerStyle) -> Self {
+        Self {
+            voice_tags: style == SpeakerStyle::Voice,
+            ..Default::default()
+        }
+    }
+
+    /// Highlight cues with a confidence below `threshold`.
+    pub fn with_low_confidence(mut self, threshold: f64) -> Self {
+        self.low_confidence = Some(threshold);
+        self
+    }
+
+    /// Attach run details for formats that carry metadata.
+    pub fn with_metadata(mut self, metadata: OutputMetadata) -> Self {
+        self.metadata = metadata;
+        self
+    }
+
+    /// Stack the original text under translated cues.
+    pub fn with_dual(mut self, style: OriginalStyle) -> Self {
+        self.dual = Some(style);
+        self
+    }
+}
+
+/// How the original line of a bilingual cue is rendered under the translation.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum OriginalStyle {
+    /// The original as plain text.
+    #[default]
+    Plain,
+    /// The original in italics.
+    Italic,
+    /// The original in a colour, such as `yellow` or `#ffcc00`.
+    Color(String),
+}
+
+impl std::fmt::Display for OriginalStyle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OriginalStyle::Plain => write!(f, "plain"),
+            OriginalStyle::Italic => write!(f, "italic"),
+            OriginalStyle::Color(color) => write!(f, "color:{}", color),
+        }
+    }
+}
+
+impl std::str::FromStr for OriginalStyle {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let lower = s.trim().to_lowercase();
+        match lower.as_str() {
+            "plain" => return Ok(OriginalStyle::Plain),
+            "italic" => return Ok(OriginalStyle::Italic),
+            _ => {}
+        }
+        if let Some(color) = lower.strip_prefix("color:") {
+            let color = color.trim();
+            let valid = !color.is_empty()
+                && color
+                    .chars()
+                    .enumerate()
+                    .all(|(i, c)| c.is_ascii_alphanumeric() || (i == 0 && c == '#'));
+            if valid {
+                return Ok(OriginalStyle::Color(color.to_string()));
+            }
+        }
+        Err(format!(
+            "Unknown original line style: {}. Use 'plain', 'italic', or 'color:<name or #hex>'",
+            s
+        ))
+    }
+}
+
+impl TryFrom<String> for OriginalStyle {
+    type Error = String;
+
+    fn try_from(value: String) -> std::result::Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<OriginalStyle> for String {
+    fn from(style: OriginalStyle) -> Self {
+        style.to_string()
+    }
+}
+
 pub fn create_formatter(format: OutputFormat) -> Box<dyn SubtitleFormatter> {
+    create_formatter_with_options(format, &FormatOptions::default())
+}
+
+pub fn create_formatter_with_options(
+    format: OutputFormat,
+    options: &FormatOptions,
+) -> Box<dyn SubtitleFormatter> {
     match format {
-        OutputFormat::Srt => Box::new(srt::SrtFormatter),
-        OutputFormat::Vtt => Box::new(vtt::VttFormatter),
-        OutputFormat::Json => Box::new(json::JsonFormatter::default()),
+        OutputFormat::Srt => Box::new(srt::SrtFormatter {
+            dual: options.dual.clone(),
+        }),
+        OutputFormat::Vtt => Box::new(vtt::VttFormatter {
+            voice_tags: options.voice_tags,
+            low_confidence: options.low_confidence,
+            dual: options.dual.clone(),
+        }),
+        OutputFormat::Json => {
+            let metadata = options.metadata.clone();
+            Box::new(json::JsonFormatter {
+                source_file: metadata.source_file,
+                language: metadata.language,
+                provider: metadata.provider,
+                providers: metadata.providers,
+                token_usage: metadata.token_usage,
+                estimated_cost: metadata.estimated_cost,
+                transcription_prompt_hash: metadata.transcription_prompt_hash,
+                translation_prompt_hash: metadata.translation_prompt_hash,
+                low_confidence: options.low_confidence,
+            })
+        }
+    }
+}
+
+/// Parse subtitle `content` written in `format` into entries.
+pub fn parse_subtitles(content: &str, format: OutputFormat) -> Result<Vec<SubtitleEntry>> {
+    let content = content.trim_start_matches('\u{feff}');
+    let entries = match format {
+        OutputFormat::Srt => srt::parse(content)?,
+        OutputFormat::Vtt => vtt::parse(content)?,
+        OutputFormat::Json => json::parse(content)?,
+    };
+    if entries.is_empty() {
+        return Err(AutosubError::SubtitleParse("no cues found".to_string()));
+    }
+    Ok(entries)
+}
+
+/// Read a subtitle file, detecting its format from the extension.
+pub fn read_subtitles(path: &Path) -> Result<(Vec<SubtitleEntry>, OutputFormat)> {
+    let format = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .and_then(|ext| ext.parse::<OutputFormat>().ok())
+        .ok_or_else(|| {
+            AutosubError::SubtitleParse(format!(
+                "{}: unsupported extension, expected .srt, .vtt or .json",
+                path.display()
+            ))
+        })?;
+    let content = std::fs::read_to_string(path)?;
+    let entries = parse_subtitles(&content, format).map_err(|e| match e {
+        AutosubError::SubtitleParse(message) => {
+            AutosubError::SubtitleParse(format!("{}: {}", path.display(), message))
+        }
+        other => other,
+    })?;
+    Ok((entries, format))
+}
+
+/// Split text subtitle content into blocks of non-blank lines.
+pub(crate) fn text_blocks(content: &str) -> Vec<Vec<&str>> {
+    let mut blocks = Vec::new();
+    let mut block = Vec::new();
+    for line in content.lines() {
+        let line = line.trim_end_matches('\r');
+        if line.trim().is_empty() {
+            if !block.is_empty() {
+                blocks.push(std::mem::take(&mut block));
+            }
+        } else {
+            block.push(line);
+        }
+    }
+    if !block.is_empty() {
+        blocks.push(block);
+    }
+    blocks
+}
+
+/// Parse a cue timing line (`start --> end`, optionally followed by cue settings).
+pub(crate) fn parse_timing(line: &str) -> Option<(Duration, Duration)> {
+    let (start, end) = line.split_once("-->")?;
+    let end = end.split_whitespace().next()?;
+    Some((parse_timestamp(start.trim())?, parse_timestamp(end)?))
+}
+
+/// Parse `HH:MM:SS,mmm`, `HH:MM:SS.mmm` or `MM:SS.mmm`.
+fn parse_timestamp(s: &str) -> Option<Duration> {
+    let (clock, millis) = s.split_once([',', '.'])?;
+    if millis.len() != 3 {
+        return None;
+    }
+    let millis: u64 = millis.parse().ok()?;
+    let parts = clock
+        .split(':')
+        .map(|part| part.parse::<u64>().ok())
+        .collect::<Option<Vec<_>>>()?;
+    let secs = match parts[..] {
+        [h, m, s] if m < 60 && s < 60 => h.checked_mul(3600)?.checked_add(m * 60 + s)?,
+        [m, s] if m < 60 && s < 60 => m * 60 + s,
+        _ => return None,
+    };
+    Some(Duration::from_millis(
+        secs.checked_mul(1000)?.checked_add(millis)?,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_timing() {
+        assert_eq!(
+            parse_timing("00:01:02,500 --> 01:00:00,000"),
+            Some((Duration::from_millis(62_500), Duration::from_secs(3600)))
+        );
+        assert_eq!(
+            parse_timing("01:02.500 --> 01:04.000 align:start position:10%"),
+            Some((Duration::from_millis(62_500), Duration::from_secs(64)))
+        );
+        assert_eq!(parse_timing("00:00:01 --> 00:00:02"), None);
+        assert_eq!(parse_timing("00:61:00,000 --> 00:62:00,000"), None);
+        assert_eq!(parse_timing("Hello"), None);
+        assert_eq!(
+            parse_timing("99999999999999999:00:00,000 --> 00:00:01,000"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_parse_subtitles_round_trip() {
+        let entries = vec![
+            SubtitleEntry {
+                index: 3,
+                start: Duration::from_millis(1500),
+                end: Duration::from_millis(4000),
+                text: "Hello, <world>!\nSecond line".to_string(),
+                speaker: Some("Alice".to_string()),
+                confidence: None,
+                kind: SegmentKind::Speech,
+                original_text: None,
+            },
+            SubtitleEntry {
+                index: 7,
+                start: Duration::from_secs(3661),
+                end: Duration::from_millis(3_663_250),
+                text: "Bye.".to_string(),
+                speaker: None,
+                confidence: None,
+                kind: SegmentKind::Speech,
+                original_text: None,
+            },
+        ];
+        let options = FormatOptions::for_speaker_style(SpeakerStyle::Voice);
+
+        for format in [OutputFormat::Srt, OutputFormat::Vtt, OutputFormat::Json] {
+            let content = create_formatter_with_options(format, &options).format(&entries);
+            let parsed = parse_subtitles(&content, format).unwrap();
+            assert_eq!(parsed.len(), 2, "{}", format);
+            for (parsed, entry) in parsed.iter().zip(&entries) {
+                assert_eq!(parsed.start, entry.start, "{}", format);
+                assert_eq!(parsed.end, entry.end, "{}", format);
+            }
+            assert_eq!(parsed[1].text, "Bye.", "{}", format);
+        }
+
+        // SRT and JSON carry cue numbers, WebVTT renumbers; only SRT lacks speakers
+        let srt = parse_subtitles(
+            &create_formatter(OutputFormat::Srt).format(&entries),
+            OutputFormat::Srt,
+        )
+        .unwrap();
+        assert_eq!((srt[0].index, srt[1].index), (3, 7));
+        assert_eq!(srt[0].text, "Hello, <world>!\nSecond line");
+        let vtt = create_formatter_with_options(OutputFormat::Vtt, &options).format(&entries);
+        let vtt = parse_subtitles(&vtt, OutputFormat::Vtt).unwrap();
+        assert_eq!((vtt[0].index, vtt[1].index), (1, 2));
+        assert_eq!(vtt[0].speaker.as_deref(), Some("Alice"));
+        assert_eq!(vtt[0].text, "Hello, <world>!\nSecond line");
+        let json = parse_subtitles(
+            &create_formatter(OutputFormat::Json).format(&entries),
+            OutputFormat::Json,
+        )
+        .unwrap();
+        assert_eq!(json[0].index, 3);
+        assert_eq!(json[0].speaker.as_deref(), Some("Alice"));
+    }
+
+    #[test]
+    fn test_parse_subtitles_errors() {
+        assert!(parse_subtitles("", OutputFormat::Srt).is_err());
+        assert!(parse_subtitles("1\nnot a timing\nHello\n", OutputFormat::Srt).is_err());
+        assert!(parse_subtitles("00:00:01.000 --> 00:00:02.000\nHi\n", OutputFormat::Vtt).is_err());
+        assert!(parse_subtitles("{\"subtitles\": 3}", OutputFormat::Json).is_err());
+        assert!(read_subtitles(Path::new("movie.ass")).is_err());
     }
 }
@@ -1,4 +1,5 @@
 use super::SubtitleEntry;
+use crate::transcribe::SegmentKind;
 use std::time::Duration;
 
 /// Configuration for post-processing subtitles.
@@ -58,6 +59,45 @@ pub fn post_process(entries: Vec<SubtitleEntry>, config: &PostProcessConfig) ->
     result
 }
 
+/// How long each entry stays on screen once post-processed.
+///
+/// Entries merged into one cue share its time by text length. Splitting long lines
+/// only shares a cue's time out over its pieces, and filler removal only shortens
+/// text, so neither is applied here.
+pub fn shown_durations(entries: Vec<SubtitleEntry>, config: &PostProcessConfig) -> Vec<Duration> {
+    let count = entries.len();
+    let lengths: Vec<usize> = entries.iter().map(|e| e.text.chars().count()).collect();
+    // Merging keeps the first entry of each cue, so its index marks where the cue starts
+    let entries = entries
+        .into_iter()
+        .enumerate()
+        .map(|(i, mut entry)| {
+            entry.index = i;
+            entry
+        })
+        .collect();
+    let cues = adjust_timing(
+        merge_close_segments(entries, config.merge_threshold),
+        config,
+    );
+
+    let mut durations = vec![Duration::ZERO; count];
+    for (i, cue) in cues.iter().enumerate() {
+        let parts = cue.index..cues.get(i + 1).map_or(count, |next| next.index);
+        let shown = cue.end.saturating_sub(cue.start);
+        let total_chars: usize = lengths[parts.clone()].iter().sum();
+        let part_count = parts.len();
+        for part in parts {
+            let share = match total_chars {
+                0 => 1.0 / part_count as f64,
+                total => lengths[part] as f64 / total as f64,
+            };
+            durations[part] = shown.mul_f64(share);
+        }
+    }
+    durations
+}
+
 /// Merge segments that are closer than the threshold.
 fn merge_close_segments(entries: Vec<SubtitleEntry>, threshold: Duration) -> Vec<SubtitleEntry> {
     if entries.is_empty() {
@@ -68,14 +108,22 @@ fn merge_close_segments(entries: Vec<SubtitleEntry>, threshold: Duration) -> Vec
 
     for entry in entries {
         if let Some(last) = result.last_mut() {
-            // Check if same speaker and close enough to merge
+            // Check if same speaker and close enough to merge; sound events,
+            // music and lyrics always keep their own cue.
             let same_speaker = last.speaker == entry.speaker;
+            let same_kind = last.kind == entry.kind
+                && matches!(entry.kind, SegmentKind::Speech | SegmentKind::OffScreen);
             let gap = entry.start.saturating_sub(last.end);
 
-            if same_speaker && gap < threshold {
+            if same_speaker && same_kind && gap < threshold {
                 // Merge: extend the last entry
                 last.end = entry.end;
                 last.text = format!("{} {}", last.text.trim(), entry.text.trim());
+                last.confidence = min_confidence(last.confidence, entry.confidence);
+                last.original_text = match (last.original_text.take(), entry.original_text) {
+                    (Some(a), Some(b)) => Some(format!("{} {}", a.trim(), b.trim())),
+                    (a, b) => a.or(b),
+                };
             } else {
                 result.push(entry);
             }
@@ -87,6 +135,14 @@ fn merge_close_segments(entries: Vec<SubtitleEntry>, threshold: Duration) -> Vec
     result
 }
 
+/// The lower of two confidences; a merged cue is only as reliable as its weakest part.
+fn min_confidence(a: Option<f64>, b: Option<f64>) -> Option<f64> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
+    }
+}
+
 /// Split text that exceeds max line length at sentence boundaries when possible.
 fn split_long_lines(entries: Vec<SubtitleEntry>, max_length: usize) -> Vec<SubtitleEntry> {
     let mut result = Vec::new();
@@ -109,6 +165,13 @@ fn split_long_lines(entries: Vec<SubtitleEntry>, max_length: usize) -> Vec<Subti
             let total_chars: usize = split_text.iter().map(|s| s.len()).sum();
             let num_splits = split_text.len();
             let mut current_start = entry.start;
+            // The original of a translated cue is shared out over the same pieces
+            let mut originals = entry
+                .original_text
+                .as_deref()
+                .map(|original| split_into_parts(original, num_splits))
+                .unwrap_or_default()
+                .into_iter();
 
             for (i, text) in split_text.into_iter().enumerate() {
                 let proportion = text.len() as f64 / total_chars as f64;
@@ -126,6 +189,9 @@ fn split_long_lines(entries: Vec<SubtitleEntry>, max_length: usize) -> Vec<Subti
                     end: segment_end,
                     text,
                     speaker: entry.speaker.clone(),
+                    confidence: entry.confidence,
+                    kind: entry.kind,
+                    original_text: originals.next().filter(|original| !original.is_empty()),
                 });
 
                 current_start = segment_end;
@@ -186,6 +252,35 @@ fn smart_split(text: &str, max_length: usize) -> Vec<String> {
     result.into_iter().filter(|s| !s.is_empty()).collect()
 }
 
+/// Divide `text` into `parts` pieces of similar length, between words when it has spaces.
+fn split_into_parts(text: &str, parts: usize) -> Vec<String> {
+    let spaced = text.contains(' ');
+    let units: Vec<String> = if spaced {
+        text.split_whitespace().map(str::to_string).collect()
+    } else {
+        text.chars().map(String::from).collect()
+    };
+    let total: usize = units
+        .iter()
+        .map(|u| u.chars().count())
+        .sum::<usize>()
+        .max(1);
+
+    let mut pieces: Vec<Vec<String>> = vec![Vec::new(); parts.max(1)];
+    let mut consumed = 0;
+    for unit in units {
+        let piece = (consumed * pieces.len() / total).min(pieces.len() - 1);
+        consumed += unit.chars().count();
+        pieces[piece].push(unit);
+    }
+
+    let separator = if spaced { " " } else { "" };
+    pieces
+        .into_iter()
+        .map(|piece| piece.join(separator))
+        .collect()
+}
+
 /// Find the best position to split text.
 fn find_best_split(text: &str) -> Option<usize> {
     // First try sentence endings
@@ -328,6 +423,9 @@ mod tests {
             end: Duration::from_millis(end_ms),
             text: text.to_string(),
             speaker: None,
+            confidence: None,
+            kind: SegmentKind::Speech,
+            original_text: None,
         }
     }
 
@@ -358,6 +456,22 @@ mod tests {
         assert_eq!(result.len(), 2);
     }
 
+    #[test]
+    fn test_merge_keeps_sound_events_separate() {
+        let mut entries = vec![
+            entry(1, 0, 1000, "Hello"),
+            entry(2, 1200, 2000, "[door slams]"),
+            entry(3, 2200, 3000, "[dog barks]"),
+            entry(4, 3200, 4000, "What was that?"),
+        ];
+        entries[1].kind = SegmentKind::Sound;
+        entries[2].kind = SegmentKind::Sound;
+
+        let result = merge_close_segments(entries, Duration::from_secs(1));
+
+        assert_eq!(result.len(), 4);
+    }
+
     #[test]
     fn test_smart_split_short_text() {
         let result = smart_split("Hello world", 42);
@@ -373,6 +487,33 @@ mod tests {
         assert!(result[0].ends_with('.'));
     }
 
+    #[test]
+    fn test_original_text_follows_merges_and_splits() {
+        let mut entries = vec![entry(1, 0, 1000, "Hola"), entry(2, 1200, 2000, "mundo")];
+        entries[0].original_text = Some("Hello".to_string());
+        entries[1].original_text = Some("world".to_string());
+        let merged = merge_close_segments(entries, Duration::from_secs(1));
+        assert_eq!(merged[0].original_text.as_deref(), Some("Hello world"));
+
+        let mut long = entry(1, 0, 4000, "Esta es una frase. Y esta es otra frase.");
+        long.original_text = Some("This is a sentence. And this is another one.".to_string());
+        let split = split_long_lines(vec![long], 25);
+        assert_eq!(split.len(), 2);
+        assert_eq!(
+            split[0].original_text.as_deref(),
+            Some("This is a sentence. And")
+        );
+        assert_eq!(
+            split[1].original_text.as_deref(),
+            Some("this is another one.")
+        );
+
+        assert_eq!(
+            split_into_parts("こんにちは世界", 2),
+            ["こんにち", "は世界"]
+        );
+    }
+
     #[test]
     fn test_remove_filler_words() {
         let entries = vec![entry(1, 0, 1000, "So um I was like thinking")];
@@ -425,6 +566,29 @@ mod tests {
         assert_eq!(result[0].end, Duration::from_secs(7));
     }
 
+    #[test]
+    fn test_shown_durations() {
+        let entries = vec![
+            entry(1, 0, 300, "Hi"),
+            entry(2, 6000, 7000, "Hello"),
+            entry(3, 7500, 8000, "world"),
+            entry(4, 10000, 20000, "A very long speech"),
+        ];
+
+        let durations = shown_durations(entries, &PostProcessConfig::default());
+
+        // Short cues are stretched, merged ones share their cue, long ones are capped
+        assert_eq!(
+            durations,
+            vec![
+                Duration::from_secs(1),
+                Duration::from_secs(1),
+                Duration::from_secs(1),
+                Duration::from_secs(7),
+            ]
+        );
+    }
+
     #[test]
     fn test_post_process_integration() {
         let entries = vec![
@@ -0,0 +1,174 @@
+// Speaker naming and styling for subtitle output
+use crate::error::{AutosubError, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// How speaker identities are rendered in subtitle text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SpeakerStyle {
+    /// Prefix every cue with the speaker name: `[Alice] Hello`.
+    #[default]
+    Prefix,
+    /// Start a cue with a dash whenever the speaker changes: `- Hello`.
+    Dash,
+    /// WebVTT voice tags: `<v Alice>Hello`. Other formats omit the name.
+    Voice,
+    /// Omit speaker names from the text entirely.
+    None,
+}
+
+impl std::fmt::Display for SpeakerStyle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SpeakerStyle::Prefix => write!(f, "prefix"),
+            SpeakerStyle::Dash => write!(f, "dash"),
+            SpeakerStyle::Voice => write!(f, "voice"),
+            SpeakerStyle::None => write!(f, "none"),
+        }
+    }
+}
+
+impl std::str::FromStr for SpeakerStyle {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "prefix" => Ok(SpeakerStyle::Prefix),
+            "dash" => Ok(SpeakerStyle::Dash),
+            "voice" => Ok(SpeakerStyle::Voice),
+            "none" => Ok(SpeakerStyle::None),
+            _ => Err(format!(
+                "Unknown speaker style: {}. Use 'prefix', 'dash', 'voice', or 'none'",
+                s
+            )),
+        }
+    }
+}
+
+/// Maps speaker labels (e.g. "Speaker 1") to real names.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SpeakerMap {
+    names: HashMap<String, String>,
+}
+
+impl SpeakerMap {
+    /// Create an empty speaker map.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a mapping from a speaker label to a display name.
+    pub fn insert(&mut self, label: &str, name: &str) {
+        self.names
+            .insert(label.trim().to_lowercase(), name.trim().to_string());
+    }
+
+    /// Add a mapping from an assignment like `Speaker 1=Alice`.
+    pub fn add_assignment(&mut self, assignment: &str) -> Result<()> {
+        let (label, name) = assignment.split_once('=').ok_or_else(|| {
+            AutosubError::Config(format!(
+                "Invalid speaker mapping '{}'. Use \"Speaker 1=Alice\"",
+                assignment
+            ))
+        })?;
+        if label.trim().is_empty() || name.trim().is_empty() {
+            return Err(AutosubError::Config(format!(
+                "Invalid speaker mapping '{}': label and name must not be empty",
+                assignment
+            )));
+        }
+        self.insert(label, name);
+        Ok(())
+    }
+
+    /// Load mappings from a file with one `Speaker N=Name` per line.
+    ///
+    /// Blank lines and lines starting with `#` are ignored.
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)?;
+        let mut map = Self::new();
+        for line in contents.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            map.add_assignment(line)?;
+        }
+        Ok(map)
+    }
+
+    /// Resolve a speaker label to its display name.
+    pub fn resolve<'a>(&'a self, label: &'a str) -> &'a str {
+        self.names
+            .get(&label.trim().to_lowercase())
+            .map(|s| s.as_str())
+            .unwrap_or(label)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+}
+
+/// Speaker naming and styling applied when converting segments to subtitles.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SpeakerFormat {
+    pub names: SpeakerMap,
+    pub style: SpeakerStyle,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_speaker_style_parsing() {
+        assert_eq!(
+            "prefix".parse::<SpeakerStyle>().unwrap(),
+            SpeakerStyle::Prefix
+        );
+        assert_eq!("DASH".parse::<SpeakerStyle>().unwrap(), SpeakerStyle::Dash);
+        assert_eq!(
+            "voice".parse::<SpeakerStyle>().unwrap(),
+            SpeakerStyle::Voice
+        );
+        assert_eq!("none".parse::<SpeakerStyle>().unwrap(), SpeakerStyle::None);
+        assert!("bold".parse::<SpeakerStyle>().is_err());
+    }
+
+    #[test]
+    fn test_speaker_map_resolve() {
+        let mut map = SpeakerMap::new();
+        map.add_assignment("Speaker 1=Alice").unwrap();
+        map.add_assignment(" speaker 2 = Bob ").unwrap();
+
+        assert_eq!(map.resolve("Speaker 1"), "Alice");
+        assert_eq!(map.resolve("Speaker 2"), "Bob");
+        assert_eq!(map.resolve("Speaker 3"), "Speaker 3");
+    }
+
+    #[test]
+    fn test_speaker_map_invalid_assignment() {
+        let mut map = SpeakerMap::new();
+        assert!(map.add_assignment("Speaker 1").is_err());
+        assert!(map.add_assignment("=Alice").is_err());
+    }
+
+    #[test]
+    fn test_speaker_map_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("speakers.txt");
+        std::fs::write(&path, "# Cast\nSpeaker 1=Alice\n\nSpeaker 2=Bob\n").unwrap();
+
+        let map = SpeakerMap::load(&path).unwrap();
+
+        assert_eq!(map.len(), 2);
+        assert_eq!(map.resolve("Speaker 2"), "Bob");
+    }
+}
@@ -1,21 +1,19 @@
 // SRT subtitle format
-use super::{SubtitleEntry, SubtitleFormatter};
+use super::{parse_timing, text_blocks, OriginalStyle, SubtitleEntry, SubtitleFormatter};
+use crate::error::{AutosubError, Result};
+use crate::transcribe::SegmentKind;
 
-pub struct SrtFormatter;
+#[derive(Default)]
+pub struct SrtFormatter {
+    /// Stack the original text under translated cues, styled this way.
+    pub dual: Option<OriginalStyle>,
+}
 
 impl SubtitleFormatter for SrtFormatter {
     fn format(&self, entries: &[SubtitleEntry]) -> String {
         entries
             .iter()
-            .map(|entry| {
-                format!(
-                    "{}\n{} --> {}\n{}\n",
-                    entry.index,
-                    format_timestamp(entry.start),
-                    format_timestamp(entry.end),
-                    entry.text
-                )
-            })
+            .map(|entry| self.format_cue(entry))
             .collect::<Vec<_>>()
             .join("\n")
     }
@@ -25,6 +23,62 @@ impl SubtitleFormatter for SrtFormatter {
     }
 }
 
+impl SrtFormatter {
+    /// Format a single cue, without the blank line separating it from the next.
+    pub(crate) fn format_cue(&self, entry: &SubtitleEntry) -> String {
+        format!(
+            "{}\n{} --> {}\n{}\n",
+            entry.index,
+            format_timestamp(entry.start),
+            format_timestamp(entry.end),
+            self.cue_text(entry)
+        )
+    }
+
+    fn cue_text(&self, entry: &SubtitleEntry) -> String {
+        match (&self.dual, &entry.original_text) {
+            (Some(style), Some(original)) => {
+                let original = match style {
+                    OriginalStyle::Plain => original.clone(),
+                    OriginalStyle::Italic => format!("<i>{}</i>", original),
+                    OriginalStyle::Color(color) => {
+                        format!("<font color=\"{}\">{}</font>", color, original)
+                    }
+                };
+                format!("{}\n{}", entry.text, original)
+            }
+            _ => entry.text.clone(),
+        }
+    }
+}
+
+/// Parse SRT content, keeping each cue's number.
+pub fn parse(content: &str) -> Result<Vec<SubtitleEntry>> {
+    let mut entries: Vec<SubtitleEntry> = Vec::new();
+    for block in text_blocks(content) {
+        let previous = entries.last().map_or(0, |e| e.index);
+        // The cue number is optional in practice; number such cues after the previous one
+        let (index, rest) = match block[0].trim().parse::<usize>() {
+            Ok(index) if block.len() > 1 => (index, &block[1..]),
+            _ => (previous + 1, &block[..]),
+        };
+        let (start, end) = parse_timing(rest[0]).ok_or_else(|| {
+            AutosubError::SubtitleParse(format!("cue {}: invalid timing \"{}\"", index, rest[0]))
+        })?;
+        entries.push(SubtitleEntry {
+            index,
+            start,
+            end,
+            text: rest[1..].join("\n"),
+            speaker: None,
+            confidence: None,
+            kind: SegmentKind::Speech,
+            original_text: None,
+        });
+    }
+    Ok(entries)
+}
+
 fn format_timestamp(d: std::time::Duration) -> String {
     let total_secs = d.as_secs();
     let hours = total_secs / 3600;
@@ -60,6 +114,9 @@ mod tests {
                 end: Duration::from_millis(4000),
                 text: "Hello, world!".to_string(),
                 speaker: None,
+                confidence: None,
+                kind: SegmentKind::Speech,
+                original_text: None,
             },
             SubtitleEntry {
                 index: 2,
@@ -67,13 +124,87 @@ mod tests {
                 end: Duration::from_millis(7000),
                 text: "This is a test.".to_string(),
                 speaker: None,
+                confidence: None,
+                kind: SegmentKind::Speech,
+                original_text: None,
             },
         ];
 
-        let formatter = SrtFormatter;
+        let formatter = SrtFormatter::default();
         let output = formatter.format(&entries);
 
         assert!(output.contains("1\n00:00:01,500 --> 00:00:04,000\nHello, world!"));
         assert!(output.contains("2\n00:00:04,500 --> 00:00:07,000\nThis is a test."));
     }
+
+    #[test]
+    fn test_srt_parse() {
+        let content = "1\r\n00:00:01,500 --> 00:00:04,000\r\nHello,\r\nworld!\r\n\r\n\
+                       5\n00:00:04.500 --> 00:00:07,000 X1:10 X2:20\n<i>Quiet.</i>\n\n\n\
+                       00:00:08,000 --> 00:00:09,000\nNo number\n";
+        let entries = parse(content).unwrap();
+
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].index, 1);
+        assert_eq!(entries[0].start, Duration::from_millis(1500));
+        assert_eq!(entries[0].text, "Hello,\nworld!");
+        assert_eq!(entries[1].index, 5);
+        assert_eq!(entries[1].end, Duration::from_secs(7));
+        assert_eq!(entries[1].text, "<i>Quiet.</i>");
+        assert_eq!(entries[2].index, 6);
+        assert_eq!(entries[2].text, "No number");
+
+        let err = parse("2\n00:00:01,000 -> 00:00:02,000\nHi\n").unwrap_err();
+        assert!(err.to_string().contains("cue 2"));
+    }
+
+    #[test]
+    fn test_srt_dual() {
+        let entries = vec![
+            SubtitleEntry {
+                index: 1,
+                start: Duration::from_millis(0),
+                end: Duration::from_millis(2000),
+                text: "Hola".to_string(),
+                speaker: None,
+                confidence: None,
+                kind: SegmentKind::Speech,
+                original_text: Some("Hello".to_string()),
+            },
+            SubtitleEntry {
+                index: 2,
+                start: Duration::from_millis(2000),
+                end: Duration::from_millis(4000),
+                text: "[música]".to_string(),
+                speaker: None,
+                confidence: None,
+                kind: SegmentKind::Music,
+                original_text: None,
+            },
+        ];
+
+        let plain = SrtFormatter {
+            dual: Some(OriginalStyle::Plain),
+        };
+        let output = plain.format(&entries);
+        assert!(output.contains("00:00:02,000\nHola\nHello\n"));
+        assert!(output.contains("00:00:04,000\n[música]\n"));
+
+        let italic = SrtFormatter {
+            dual: Some("italic".parse().unwrap()),
+        };
+        assert!(italic.format(&entries).contains("Hola\n<i>Hello</i>\n"));
+
+        let color = SrtFormatter {
+            dual: Some("color:#FFCC00".parse().unwrap()),
+        };
+        assert!(color
+            .format(&entries)
+            .contains("Hola\n<font color=\"#ffcc00\">Hello</font>\n"));
+
+        // Without dual mode only the translation is shown
+        assert!(!SrtFormatter::default().format(&entries).contains("Hello"));
+        assert!("color:".parse::<OriginalStyle>().is_err());
+        assert!("color:red;}".parse::<OriginalStyle>().is_err());
+    }
 }
@@ -1,21 +1,35 @@
 // WebVTT subtitle format
-use super::{SubtitleEntry, SubtitleFormatter};
+use super::{parse_timing, text_blocks, OriginalStyle, SubtitleEntry, SubtitleFormatter};
+use crate::error::{AutosubError, Result};
+use crate::transcribe::SegmentKind;
 
-pub struct VttFormatter;
+/// File header preceding the first cue.
+pub(crate) const HEADER: &str = "WEBVTT\n\n";
+
+#[derive(Default)]
+pub struct VttFormatter {
+    /// Wrap cue text in `<v Speaker>` voice tags when the speaker is known.
+    pub voice_tags: bool,
+    /// Precede cues with a confidence below this threshold by a review NOTE.
+    pub low_confidence: Option<f64>,
+    /// Stack the original text under translated cues, styled this way.
+    pub dual: Option<OriginalStyle>,
+}
 
 impl SubtitleFormatter for VttFormatter {
     fn format(&self, entries: &[SubtitleEntry]) -> String {
-        let mut output = String::from("WEBVTT\n\n");
-
-        for entry in entries {
+        let mut output = String::from(HEADER);
+        if let Some(OriginalStyle::Color(color)) = &self.dual {
             output.push_str(&format!(
-                "{} --> {}\n{}\n\n",
-                format_timestamp(entry.start),
-                format_timestamp(entry.end),
-                entry.text
+                "STYLE\n::cue(.original) {{\n  color: {};\n}}\n\n",
+                color
             ));
         }
 
+        for entry in entries {
+            output.push_str(&self.format_cue(entry));
+        }
+
         output
     }
 
@@ -24,6 +38,115 @@ impl SubtitleFormatter for VttFormatter {
     }
 }
 
+impl VttFormatter {
+    /// Format a single cue, including its trailing blank line.
+    pub(crate) fn format_cue(&self, entry: &SubtitleEntry) -> String {
+        let note = match (self.low_confidence, entry.confidence) {
+            (Some(threshold), Some(confidence)) if entry.is_low_confidence(threshold) => {
+                format!("NOTE Low confidence ({:.2}), please review\n\n", confidence)
+            }
+            _ => String::new(),
+        };
+        let text = match entry.speaker {
+            Some(ref speaker) if self.voice_tags => {
+                format!("<v {}>{}", escape_cue_text(speaker), entry.text)
+            }
+            _ => entry.text.clone(),
+        };
+        let text = match (&self.dual, &entry.original_text) {
+            (Some(style), Some(original)) => {
+                let original = escape_cue_text(original);
+                match style {
+                    OriginalStyle::Plain => format!("{}\n{}", text, original),
+                    OriginalStyle::Italic => format!("{}\n<i>{}</i>", text, original),
+                    OriginalStyle::Color(_) => format!("{}\n<c.original>{}</c>", text, original),
+                }
+            }
+            _ => text,
+        };
+        format!(
+            "{}{} --> {}\n{}\n\n",
+            note,
+            format_timestamp(entry.start),
+            format_timestamp(entry.end),
+            text
+        )
+    }
+}
+
+/// Escape characters that have special meaning inside WebVTT cue text.
+fn escape_cue_text(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// Undo [`escape_cue_text`].
+fn unescape_cue_text(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&")
+}
+
+/// Parse WebVTT content. Cues are numbered in order since WebVTT has no cue numbers;
+/// a leading `<v Speaker>` voice tag becomes the entry's speaker.
+pub fn parse(content: &str) -> Result<Vec<SubtitleEntry>> {
+    let blocks = text_blocks(content);
+    if !blocks.first().is_some_and(|b| b[0].starts_with("WEBVTT")) {
+        return Err(AutosubError::SubtitleParse(
+            "missing WEBVTT header".to_string(),
+        ));
+    }
+
+    let mut entries = Vec::new();
+    for block in &blocks[1..] {
+        if ["NOTE", "STYLE", "REGION"]
+            .iter()
+            .any(|kind| block[0] == *kind || block[0].starts_with(&format!("{} ", kind)))
+        {
+            continue;
+        }
+        // Skip the optional cue identifier
+        let rest = if block[0].contains("-->") {
+            &block[..]
+        } else {
+            &block[1..]
+        };
+        let index = entries.len() + 1;
+        let (start, end) = rest
+            .first()
+            .and_then(|line| parse_timing(line))
+            .ok_or_else(|| {
+                AutosubError::SubtitleParse(format!(
+                    "cue {}: invalid timing \"{}\"",
+                    index,
+                    block.join(" ")
+                ))
+            })?;
+        let mut text = rest[1..].join("\n");
+        let mut speaker = None;
+        if let Some(tag) = text.strip_prefix("<v") {
+            if let Some((name, after)) = tag.split_once('>') {
+                // `<v.class Name>` carries classes before the name
+                let name = name.split_once(' ').map_or("", |(_, name)| name.trim());
+                speaker = (!name.is_empty()).then(|| unescape_cue_text(name));
+                text = after.replacen("</v>", "", 1);
+            }
+        }
+        entries.push(SubtitleEntry {
+            index,
+            start,
+            end,
+            text: unescape_cue_text(&text),
+            speaker,
+            confidence: None,
+            kind: SegmentKind::Speech,
+            original_text: None,
+        });
+    }
+    Ok(entries)
+}
+
 fn format_timestamp(d: std::time::Duration) -> String {
     let total_secs = d.as_secs();
     let hours = total_secs / 3600;
@@ -54,12 +177,123 @@ mod tests {
             end: Duration::from_millis(4000),
             text: "Hello, world!".to_string(),
             speaker: None,
+            confidence: None,
+            kind: SegmentKind::Speech,
+            original_text: None,
         }];
 
-        let formatter = VttFormatter;
+        let formatter = VttFormatter::default();
         let output = formatter.format(&entries);
 
         assert!(output.starts_with("WEBVTT\n\n"));
         assert!(output.contains("00:00:01.500 --> 00:00:04.000"));
     }
+
+    #[test]
+    fn test_vtt_voice_tags() {
+        let entries = vec![SubtitleEntry {
+            index: 1,
+            start: Duration::from_millis(0),
+            end: Duration::from_millis(2000),
+            text: "Hello".to_string(),
+            speaker: Some("Alice".to_string()),
+            confidence: None,
+            kind: SegmentKind::Speech,
+            original_text: None,
+        }];
+
+        let output = VttFormatter {
+            voice_tags: true,
+            ..Default::default()
+        }
+        .format(&entries);
+        assert!(output.contains("<v Alice>Hello"));
+
+        let output = VttFormatter::default().format(&entries);
+        assert!(!output.contains("<v "));
+    }
+
+    #[test]
+    fn test_vtt_low_confidence_note() {
+        let entry = |text: &str, confidence| SubtitleEntry {
+            index: 1,
+            start: Duration::from_millis(0),
+            end: Duration::from_millis(2000),
+            text: text.to_string(),
+            speaker: None,
+            confidence,
+            kind: SegmentKind::Speech,
+            original_text: None,
+        };
+        let entries = vec![
+            entry("Sure", Some(0.9)),
+            entry("Mumbled", Some(0.3)),
+            entry("Unknown", None),
+        ];
+
+        let formatter = VttFormatter {
+            low_confidence: Some(0.5),
+            ..Default::default()
+        };
+        let output = formatter.format(&entries);
+        assert_eq!(output.matches("NOTE").count(), 1);
+        assert!(output.contains(
+            "NOTE Low confidence (0.30), please review\n\n00:00:00.000 --> 00:00:02.000\nMumbled"
+        ));
+
+        assert!(!VttFormatter::default().format(&entries).contains("NOTE"));
+    }
+
+    #[test]
+    fn test_vtt_parse() {
+        let content = "\u{feff}WEBVTT - Episode 1\n\n\
+                       NOTE Low confidence (0.30), please review\n\n\
+                       STYLE\n::cue { color: white; }\n\n\
+                       intro\n00:01.000 --> 00:02.500 line:0\n<v.loud Bob Smith>Fish &amp; chips</v>\n\n\
+                       00:00:03.000 --> 00:00:04.000\nTwo\nlines\n";
+        let entries = parse(content.trim_start_matches('\u{feff}')).unwrap();
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].index, 1);
+        assert_eq!(entries[0].start, Duration::from_secs(1));
+        assert_eq!(entries[0].end, Duration::from_millis(2500));
+        assert_eq!(entries[0].speaker.as_deref(), Some("Bob Smith"));
+        assert_eq!(entries[0].text, "Fish & chips");
+        assert_eq!(entries[1].index, 2);
+        assert_eq!(entries[1].speaker, None);
+        assert_eq!(entries[1].text, "Two\nlines");
+    }
+
+    #[test]
+    fn test_vtt_dual() {
+        let entries = vec![SubtitleEntry {
+            index: 1,
+            start: Duration::from_millis(0),
+            end: Duration::from_millis(2000),
+            text: "Hola".to_string(),
+            speaker: Some("Alice".to_string()),
+            confidence: None,
+            kind: SegmentKind::Speech,
+            original_text: Some("Hello <there>".to_string()),
+        }];
+
+        let output = VttFormatter {
+            voice_tags: true,
+            dual: Some(OriginalStyle::Italic),
+            ..Default::default()
+        }
+        .format(&entries);
+        assert!(output.contains("<v Alice>Hola\n<i>Hello &lt;there&gt;</i>\n\n"));
+        assert!(!output.contains("STYLE"));
+
+        let output = VttFormatter {
+            dual: Some(OriginalStyle::Color("yellow".to_string())),
+            ..Default::default()
+        }
+        .format(&entries);
+        assert!(output.starts_with(
+            "WEBVTT\n\nSTYLE\n::cue(.original) {\n  color: yellow;\n}\n\n00:00:00.000"
+        ));
+        assert!(output.contains("Hola\n<c.original>Hello &lt;there&gt;</c>\n\n"));
+    }
 }
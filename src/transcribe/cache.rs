@@ -0,0 +1,192 @@
+//! On-disk, content-addressed cache of chunk transcripts.
+//!
+//! Keys are a SHA-256 hash of the chunk audio together with the transcriber
+//! fingerprint (provider, model, prompt and language), so re-running a file
+//! with only output or post-processing changes never re-sends audio.
+//! Transcripts are stored with chunk-relative timestamps, so identical audio
+//! at a different offset is still a hit.
+
+use crate::config::Config;
+use crate::error::{AutosubError, Result};
+use crate::transcribe::Transcript;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tracing::{debug, warn};
+
+/// Size and entry count of the cache.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    pub entries: usize,
+    pub bytes: u64,
+}
+
+/// Content-addressed store of chunk transcripts.
+#[derive(Debug, Clone)]
+pub struct TranscriptCache {
+    dir: PathBuf,
+}
+
+impl TranscriptCache {
+    /// Open a cache rooted at the given directory.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Open the cache in the default location under the config directory.
+    pub fn open_default() -> Result<Self> {
+        Config::config_dir()
+            .map(|dir| Self::new(dir.join("cache").join("transcripts")))
+            .ok_or_else(|| {
+                AutosubError::Config("Could not determine config directory for cache".to_string())
+            })
+    }
+
+    /// Directory holding cached transcripts.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Compute the cache key for chunk audio transcribed with the given fingerprint.
+    pub fn key(audio: &[u8], fingerprint: &str) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(fingerprint.as_bytes());
+        hasher.update([0u8]);
+        hasher.update(audio);
+        hasher
+            .finalize()
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect()
+    }
+
+    fn entry_path(&self, key: &str) -> PathBuf {
+        self.dir.join(&key[..2]).join(format!("{}.json", key))
+    }
+
+    /// Look up a transcript, placing its timestamps at `offset` in the file.
+    pub fn get(&self, key: &str, offset: Duration) -> Option<Transcript> {
+        let path = self.entry_path(key);
+        let contents = fs::read_to_string(&path).ok()?;
+        match serde_json::from_str::<Transcript>(&contents) {
+            Ok(transcript) => {
+                debug!("Cache hit: {}", key);
+                Some(transcript.shifted_forward(offset))
+            }
+            Err(e) => {
+                warn!("Ignoring corrupt cache entry {:?}: {}", path, e);
+                None
+            }
+        }
+    }
+
+    /// Store a transcript whose timestamps start at `offset` in the file.
+    pub fn put(&self, key: &str, transcript: &Transcript, offset: Duration) -> Result<()> {
+        let path = self.entry_path(key);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let relative = transcript.clone().shifted_back(offset);
+        // Write to a temp file first so concurrent readers never see partial entries
+        let tmp = path.with_extension("json.tmp");
+        fs::write(&tmp, serde_json::to_vec(&relative)?)?;
+        fs::rename(&tmp, &path)?;
+        Ok(())
+    }
+
+    /// Count entries and their total size.
+    pub fn stats(&self) -> Result<CacheStats> {
+        let mut stats = CacheStats::default();
+        if !self.dir.exists() {
+            return Ok(stats);
+        }
+        for shard in fs::read_dir(&self.dir)? {
+            let shard = shard?;
+            if !shard.file_type()?.is_dir() {
+                continue;
+            }
+            for entry in fs::read_dir(shard.path())? {
+                let entry = entry?;
+                if entry.path().extension().and_then(|e| e.to_str()) == Some("json") {
+                    stats.entries += 1;
+                    stats.bytes += entry.metadata()?.len();
+                }
+            }
+        }
+        Ok(stats)
+    }
+
+    /// Remove every cached transcript, returning what was removed.
+    pub fn clear(&self) -> Result<CacheStats> {
+        let stats = self.stats()?;
+        if self.dir.exists() {
+            fs::remove_dir_all(&self.dir)?;
+        }
+        Ok(stats)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transcribe::{SegmentKind, TranscriptSegment};
+
+    fn transcript() -> Transcript {
+        Transcript::single(TranscriptSegment {
+            text: "Cached words".to_string(),
+            start: Duration::from_secs(32),
+            end: Duration::from_secs(35),
+            words: None,
+            confidence: None,
+            speaker: Some("Speaker 1".to_string()),
+            kind: SegmentKind::Speech,
+            original_text: None,
+        })
+    }
+
+    #[test]
+    fn test_key_depends_on_audio_and_fingerprint() {
+        let key = TranscriptCache::key(b"audio", "gemini|model|en");
+        assert_eq!(key.len(), 64);
+        assert_eq!(key, TranscriptCache::key(b"audio", "gemini|model|en"));
+        assert_ne!(key, TranscriptCache::key(b"audio2", "gemini|model|en"));
+        assert_ne!(key, TranscriptCache::key(b"audio", "gemini|model|ja"));
+    }
+
+    #[test]
+    fn test_put_get_relocates_timestamps() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = TranscriptCache::new(dir.path());
+        let key = TranscriptCache::key(b"audio", "fp");
+
+        assert!(cache.get(&key, Duration::ZERO).is_none());
+        cache
+            .put(&key, &transcript(), Duration::from_secs(30))
+            .unwrap();
+
+        let hit = cache.get(&key, Duration::from_secs(90)).unwrap();
+        assert_eq!(hit.segments[0].text, "Cached words");
+        assert_eq!(hit.segments[0].start, Duration::from_secs(92));
+        assert_eq!(hit.segments[0].speaker.as_deref(), Some("Speaker 1"));
+    }
+
+    #[test]
+    fn test_stats_and_clear() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = TranscriptCache::new(dir.path().join("cache"));
+        assert_eq!(cache.stats().unwrap(), CacheStats::default());
+
+        for audio in [&b"one"[..], &b"two"[..]] {
+            let key = TranscriptCache::key(audio, "fp");
+            cache.put(&key, &transcript(), Duration::ZERO).unwrap();
+        }
+
+        let stats = cache.stats().unwrap();
+        assert_eq!(stats.entries, 2);
+        assert!(stats.bytes > 0);
+
+        assert_eq!(cache.clear().unwrap().entries, 2);
+        assert_eq!(cache.stats().unwrap().entries, 0);
+    }
+}
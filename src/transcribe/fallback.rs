@@ -0,0 +1,234 @@
+//! Provider fallback for transcription.
+//!
+//! Wraps an ordered chain of transcribers. Each chunk is sent to the first
+//! provider; if it fails with a provider-level error (API outage, quota,
+//! network failure, blocked response), the chunk moves on to the next provider.
+//! Local errors such as unreadable audio, and requests the provider refuses
+//! (bad request, invalid key), fail immediately.
+
+use crate::audio::AudioChunk;
+use crate::error::{AutosubError, Result};
+use crate::transcribe::{Transcriber, Transcript};
+use crate::usage::ModelUsage;
+use async_trait::async_trait;
+use tracing::warn;
+
+/// Transcriber that falls back through an ordered list of providers.
+pub struct FallbackTranscriber {
+    providers: Vec<Box<dyn Transcriber>>,
+}
+
+impl FallbackTranscriber {
+    /// Create a fallback chain. The first provider is tried first.
+    pub fn new(providers: Vec<Box<dyn Transcriber>>) -> Result<Self> {
+        if providers.is_empty() {
+            return Err(AutosubError::Config(
+                "Fallback chain needs at least one transcription provider".to_string(),
+            ));
+        }
+        Ok(Self { providers })
+    }
+
+    /// Number of providers in the chain.
+    pub fn len(&self) -> usize {
+        self.providers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.providers.is_empty()
+    }
+}
+
+#[async_trait]
+impl Transcriber for FallbackTranscriber {
+    async fn transcribe(&self, chunk: &AudioChunk) -> Result<Transcript> {
+        let mut last_error = None;
+
+        for (position, provider) in self.providers.iter().enumerate() {
+            match provider.transcribe(chunk).await {
+                Ok(mut transcript) => {
+                    if transcript.provider.is_none() {
+                        transcript.provider = Some(provider.name().to_string());
+                    }
+                    return Ok(transcript);
+                }
+                Err(e) if e.is_provider_error() && position + 1 < self.providers.len() => {
+                    warn!(
+                        "{} failed for chunk {} ({}), falling back to {}",
+                        provider.name(),
+                        chunk.index,
+                        e,
+                        self.providers[position + 1].name()
+                    );
+                    last_error = Some(e);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        Err(last_error
+            .unwrap_or_else(|| AutosubError::Transcription("No providers available".to_string())))
+    }
+
+    fn name(&self) -> &'static str {
+        self.providers[0].name()
+    }
+
+    fn fingerprint(&self) -> String {
+        self.providers
+            .iter()
+            .map(|p| p.fingerprint())
+            .collect::<Vec<_>>()
+            .join(" > ")
+    }
+
+    fn usage(&self) -> Vec<ModelUsage> {
+        self.providers.iter().flat_map(|p| p.usage()).collect()
+    }
+
+    fn max_file_size(&self) -> usize {
+        self.providers
+            .iter()
+            .map(|p| p.max_file_size())
+            .min()
+            .unwrap_or(0)
+    }
+
+    fn supported_formats(&self) -> &[&str] {
+        self.providers[0].supported_formats()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::SpeechRegion;
+    use crate::transcribe::{SegmentKind, TranscriptSegment};
+    use std::path::PathBuf;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    struct StubTranscriber {
+        name: &'static str,
+        error: Option<fn() -> AutosubError>,
+        calls: Arc<AtomicUsize>,
+    }
+
+    impl StubTranscriber {
+        fn boxed(
+            name: &'static str,
+            error: Option<fn() -> AutosubError>,
+        ) -> (Box<dyn Transcriber>, Arc<AtomicUsize>) {
+            let calls = Arc::new(AtomicUsize::new(0));
+            (
+                Box::new(Self {
+                    name,
+                    error,
+                    calls: calls.clone(),
+                }),
+                calls,
+            )
+        }
+    }
+
+    #[async_trait]
+    impl Transcriber for StubTranscriber {
+        async fn transcribe(&self, chunk: &AudioChunk) -> Result<Transcript> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            if let Some(error) = self.error {
+                return Err(error());
+            }
+            Ok(Transcript::single(TranscriptSegment {
+                text: format!("from {}", self.name),
+                start: chunk.region.start,
+                end: chunk.region.end,
+                words: None,
+                confidence: None,
+                speaker: None,
+                kind: SegmentKind::Speech,
+                original_text: None,
+            }))
+        }
+
+        fn name(&self) -> &'static str {
+            self.name
+        }
+
+        fn max_file_size(&self) -> usize {
+            1024
+        }
+
+        fn supported_formats(&self) -> &[&str] {
+            &["wav"]
+        }
+    }
+
+    fn chunk() -> AudioChunk {
+        AudioChunk {
+            region: SpeechRegion {
+                start: Duration::ZERO,
+                end: Duration::from_secs(30),
+            },
+            path: PathBuf::from("/tmp/chunk.wav"),
+            index: 0,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_falls_back_on_provider_error() {
+        let (primary, primary_calls) =
+            StubTranscriber::boxed("primary", Some(|| AutosubError::Api("503".to_string())));
+        let (secondary, secondary_calls) = StubTranscriber::boxed("secondary", None);
+        let chain = FallbackTranscriber::new(vec![primary, secondary]).unwrap();
+
+        let transcript = chain.transcribe(&chunk()).await.unwrap();
+
+        assert_eq!(transcript.segments[0].text, "from secondary");
+        assert_eq!(transcript.provider.as_deref(), Some("secondary"));
+        assert_eq!(primary_calls.load(Ordering::SeqCst), 1);
+        assert_eq!(secondary_calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_local_error_does_not_fall_back() {
+        let (primary, _) = StubTranscriber::boxed(
+            "primary",
+            Some(|| AutosubError::FileNotFound("chunk.wav".to_string())),
+        );
+        let (secondary, secondary_calls) = StubTranscriber::boxed("secondary", None);
+        let chain = FallbackTranscriber::new(vec![primary, secondary]).unwrap();
+
+        assert!(chain.transcribe(&chunk()).await.is_err());
+        assert_eq!(secondary_calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn test_rejected_request_does_not_fall_back() {
+        let (primary, _) = StubTranscriber::boxed(
+            "primary",
+            Some(|| AutosubError::ApiRejected("400 Bad Request".to_string())),
+        );
+        let (secondary, secondary_calls) = StubTranscriber::boxed("secondary", None);
+        let chain = FallbackTranscriber::new(vec![primary, secondary]).unwrap();
+
+        let err = chain.transcribe(&chunk()).await.unwrap_err();
+        assert!(matches!(err, AutosubError::ApiRejected(_)));
+        assert_eq!(secondary_calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn test_all_providers_fail() {
+        let (primary, _) =
+            StubTranscriber::boxed("primary", Some(|| AutosubError::Api("down".to_string())));
+        let (secondary, _) = StubTranscriber::boxed(
+            "secondary",
+            Some(|| AutosubError::Api("also down".to_string())),
+        );
+        let chain = FallbackTranscriber::new(vec![primary, secondary]).unwrap();
+
+        let err = chain.transcribe(&chunk()).await.unwrap_err();
+        assert!(err.to_string().contains("also down"));
+        assert!(FallbackTranscriber::new(Vec::new()).is_err());
+    }
+}
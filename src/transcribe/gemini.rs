@@ -1,21 +1,32 @@
 use crate::audio::AudioChunk;
 use crate::error::{AutosubError, Result};
-use crate::transcribe::{Transcriber, Transcript, TranscriptSegment};
+use crate::keys::{is_quota_error, ApiKey, ApiKeyPool, KeyResponse};
+use crate::prompt::PromptTemplate;
+use crate::transcribe::{SegmentKind, SpeakerProfile, Transcriber, Transcript, TranscriptSegment};
+use crate::usage::{GeminiUsageMetadata, ModelUsage, UsageTracker};
 use async_trait::async_trait;
 use base64::Engine;
 use regex::Regex;
+use reqwest::StatusCode;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::ops::Range;
 use std::path::Path;
 use std::time::Duration;
 use tokio::fs;
 use tracing::{debug, warn};
 
-/// Gemini API endpoint for content generation.
-const GENERATE_CONTENT_URL: &str =
-    "https://generativelanguage.googleapis.com/v1beta/models/gemini-2.5-flash:generateContent";
+/// Gemini API base URL.
+const API_BASE_URL: &str = "https://generativelanguage.googleapis.com";
 
-/// Gemini Files API endpoint for uploading large files.
-const FILES_UPLOAD_URL: &str = "https://generativelanguage.googleapis.com/upload/v1beta/files";
+/// Path of the model endpoints, relative to the base URL.
+const MODELS_PATH: &str = "v1beta/models";
+
+/// Default model used for transcription.
+pub const DEFAULT_MODEL: &str = "gemini-2.5-flash";
+
+/// Path of the Files API upload endpoint, relative to the base URL.
+const FILES_UPLOAD_PATH: &str = "upload/v1beta/files";
 
 /// Threshold for using Files API vs inline data (20 MB).
 const INLINE_SIZE_THRESHOLD: usize = 20 * 1024 * 1024;
@@ -29,25 +40,83 @@ const MAX_RETRIES: u32 = 3;
 /// Base delay for exponential backoff (milliseconds).
 const BASE_DELAY_MS: u64 = 1000;
 
+/// Marker that starts the speaker description block in diarized responses.
+const SPEAKERS_MARKER: &str = "[SPEAKERS]";
+
+/// Marker the model appends to lines it is unsure about.
+const UNCERTAIN_MARKER: &str = "[?]";
+
+/// Confidence given to lines the model marked as uncertain.
+const MARKED_UNCERTAIN_CONFIDENCE: f64 = 0.3;
+
+/// Line prefixes the model uses for non-speech content in SDH mode.
+const SDH_PREFIXES: &[(&str, SegmentKind)] = &[
+    ("SOUND", SegmentKind::Sound),
+    ("MUSIC", SegmentKind::Music),
+    ("LYRICS", SegmentKind::Lyrics),
+    ("OFFSCREEN", SegmentKind::OffScreen),
+];
+
 /// Google Gemini Audio API client.
 pub struct GeminiClient {
     client: reqwest::Client,
-    api_key: String,
+    keys: ApiKeyPool,
+    base_url: String,
+    model: String,
     language: Option<String>,
     enable_diarization: bool,
+    logprobs: bool,
+    sdh: bool,
+    prompt_template: Option<PromptTemplate>,
+    usage: UsageTracker,
 }
 
 impl GeminiClient {
-    /// Create a new Gemini client with the given API key.
+    /// Create a new Gemini client with the given API key, or several comma-separated keys.
     pub fn new(api_key: String) -> Self {
         Self {
-            client: reqwest::Client::new(),
-            api_key,
+            client: crate::http::default_client(),
+            keys: ApiKeyPool::parse(&api_key),
+            base_url: API_BASE_URL.to_string(),
+            model: DEFAULT_MODEL.to_string(),
             language: None,
             enable_diarization: false,
+            logprobs: false,
+            sdh: false,
+            prompt_template: None,
+            usage: UsageTracker::new(),
         }
     }
 
+    /// Set a different model (e.g., "gemini-2.0-flash").
+    pub fn with_model(mut self, model: impl Into<String>) -> Self {
+        self.model = model.into();
+        self
+    }
+
+    /// Send requests through a preconfigured HTTP client (timeouts, proxy, CA).
+    pub fn with_http_client(mut self, client: reqwest::Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// Rotate over a shared pool of API keys instead of the client's own.
+    pub fn with_key_pool(mut self, keys: ApiKeyPool) -> Self {
+        self.keys = keys;
+        self
+    }
+
+    /// Send requests to a different endpoint, e.g. a proxy or a test server.
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Provider identifier recorded on transcripts, e.g. `gemini/gemini-2.5-flash`.
+    pub fn provider_id(&self) -> String {
+        format!("gemini/{}", self.model)
+    }
+
     /// Set the source language for transcription.
     pub fn with_language(mut self, language: String) -> Self {
         self.language = Some(language);
@@ -60,6 +129,27 @@ impl GeminiClient {
         self
     }
 
+    /// Request token log probabilities and derive segment confidence from them.
+    ///
+    /// Not every model supports this; without it, confidence comes only from
+    /// lines the model marks as uncertain.
+    pub fn with_logprobs(mut self, enable: bool) -> Self {
+        self.logprobs = enable;
+        self
+    }
+
+    /// Also transcribe sound events, music, lyrics and off-screen speech (SDH).
+    pub fn with_sdh(mut self, enable: bool) -> Self {
+        self.sdh = enable;
+        self
+    }
+
+    /// Replace the built-in prompt with a user-defined transcription template.
+    pub fn with_prompt_template(mut self, template: PromptTemplate) -> Self {
+        self.prompt_template = Some(template);
+        self
+    }
+
     /// Get MIME type for audio file.
     fn get_mime_type(path: &Path) -> &'static str {
         match path.extension().and_then(|e| e.to_str()) {
@@ -81,16 +171,20 @@ impl GeminiClient {
         prompt.push_str("Transcribe this audio with precise timestamps.\n\n");
         prompt.push_str("Format each line as:\n");
         prompt.push_str("[MM:SS] Text of what was said\n\n");
+        prompt.push_str(&format!(
+            "If you are unsure of any words in a line (unclear audio, overlapping speech), end that line with {}\n\n",
+            UNCERTAIN_MARKER
+        ));
 
         if let Some(ref lang) = self.language {
             prompt.push_str(&format!("The audio is in {} language.\n", lang));
         }
 
-        if self.enable_diarization {
-            prompt.push_str(
-                "Identify different speakers and label them as Speaker 1, Speaker 2, etc.\n",
-            );
-            prompt.push_str("Format: [MM:SS] Speaker N: Text\n");
+        prompt.push_str(&self.speaker_instructions());
+
+        if self.sdh {
+            prompt.push('\n');
+            prompt.push_str(&self.sdh_instructions());
         }
 
         prompt.push_str("\nProvide accurate timestamps for each segment of speech.");
@@ -98,6 +192,72 @@ impl GeminiClient {
         prompt
     }
 
+    /// Instructions for labelling speakers, empty without diarization.
+    fn speaker_instructions(&self) -> String {
+        if !self.enable_diarization {
+            return String::new();
+        }
+        let mut instructions = String::new();
+        instructions
+            .push_str("Identify different speakers and label them as Speaker 1, Speaker 2, etc.\n");
+        instructions.push_str("Format: [MM:SS] Speaker N: Text\n");
+        instructions.push_str(&format!(
+            "\nAfter the transcript, add a line with {} followed by one line per speaker:\n",
+            SPEAKERS_MARKER
+        ));
+        instructions.push_str(
+            "Speaker N: name=<name if mentioned, else unknown>; gender=<female/male/unknown>; voice=<pitch, pace, accent>; role=<e.g. host, guest, narrator>\n",
+        );
+        instructions
+    }
+
+    /// Instructions for annotating non-speech audio, empty outside SDH mode.
+    fn sdh_instructions(&self) -> String {
+        if !self.sdh {
+            return String::new();
+        }
+        let mut instructions = String::new();
+        instructions.push_str(
+            "These subtitles are for deaf and hard of hearing viewers. Also transcribe meaningful non-speech audio on its own line:\n",
+        );
+        instructions.push_str(
+            "[MM:SS] SOUND: short description of a sound effect, e.g. door slams, laughter\n",
+        );
+        instructions.push_str("[MM:SS] MUSIC: short description of music, e.g. tense music\n");
+        instructions.push_str("[MM:SS] LYRICS: words being sung\n");
+        instructions.push_str(
+            "Prefix speech from someone not on screen (narration, voice-over, phone) with OFFSCREEN, e.g. [MM:SS] OFFSCREEN: Text\n",
+        );
+        if self.enable_diarization {
+            instructions.push_str("or [MM:SS] OFFSCREEN Speaker N: Text\n");
+        }
+        instructions
+    }
+
+    /// The prompt sent for a chunk: the user template if one is set, else the built-in prompt.
+    fn prompt_for(&self, chunk: &AudioChunk) -> String {
+        let Some(ref template) = self.prompt_template else {
+            return self.build_prompt();
+        };
+
+        let offset = chunk.region.start.as_secs();
+        let values = HashMap::from([
+            ("language", self.language.clone().unwrap_or_default()),
+            (
+                "offset",
+                format!(
+                    "{:02}:{:02}:{:02}",
+                    offset / 3600,
+                    (offset % 3600) / 60,
+                    offset % 60
+                ),
+            ),
+            ("speakers", self.speaker_instructions()),
+            ("sdh", self.sdh_instructions()),
+        ]);
+        template.render(&values)
+    }
+
     /// Transcribe using inline audio data (for files < 20MB).
     async fn transcribe_inline(&self, chunk: &AudioChunk) -> Result<Transcript> {
         let audio_bytes = fs::read(&chunk.path).await?;
@@ -109,7 +269,7 @@ impl GeminiClient {
                 role: "user".to_string(),
                 parts: vec![
                     Part::Text {
-                        text: self.build_prompt(),
+                        text: self.prompt_for(chunk),
                     },
                     Part::InlineData {
                         inline_data: InlineData {
@@ -122,14 +282,17 @@ impl GeminiClient {
             generation_config: Some(GenerationConfig {
                 temperature: Some(0.0),
                 max_output_tokens: Some(8192),
+                response_logprobs: self.logprobs.then_some(true),
             }),
         };
 
-        self.call_generate_content(request, chunk).await
+        self.call_generate_content(request, chunk, None).await
     }
 
     /// Upload a file using the Files API (for files >= 20MB).
-    async fn upload_file(&self, path: &Path) -> Result<String> {
+    ///
+    /// Uploaded files belong to the key's project, so the same key must be used to reference them.
+    async fn upload_file(&self, path: &Path, key: &ApiKey) -> Result<String> {
         let file_bytes = fs::read(path).await?;
         let mime_type = Self::get_mime_type(path);
         let file_name = path
@@ -137,40 +300,45 @@ impl GeminiClient {
             .and_then(|n| n.to_str())
             .unwrap_or("audio.wav");
 
-        let url = format!("{}?key={}", FILES_UPLOAD_URL, self.api_key);
-
         // Upload with resumable upload protocol
         let response = self
-            .client
-            .post(&url)
-            .header("X-Goog-Upload-Protocol", "raw")
-            .header("X-Goog-Upload-Command", "upload, finalize")
-            .header("Content-Type", mime_type)
-            .header("X-Goog-Upload-File-Name", file_name)
-            .body(file_bytes)
-            .send()
+            .keys
+            .send_with_key(key, |key| {
+                self.client
+                    .post(format!(
+                        "{}/{}?key={}",
+                        self.base_url, FILES_UPLOAD_PATH, key
+                    ))
+                    .header("X-Goog-Upload-Protocol", "raw")
+                    .header("X-Goog-Upload-Command", "upload, finalize")
+                    .header("Content-Type", mime_type)
+                    .header("X-Goog-Upload-File-Name", file_name)
+                    .body(file_bytes.clone())
+            })
             .await?;
 
-        if !response.status().is_success() {
-            let error_text = response.text().await.unwrap_or_default();
-            return Err(AutosubError::Api(format!(
-                "Gemini file upload failed: {}",
-                error_text
-            )));
+        if !response.status.is_success() {
+            let message = format!("Gemini file upload failed: {}", response.body);
+            return Err(response_error(response.status, &response.body, message));
         }
 
-        let upload_response: FileUploadResponse = response.json().await?;
+        let upload_response: FileUploadResponse = serde_json::from_str(&response.body)?;
         Ok(upload_response.file.uri)
     }
 
     /// Transcribe using uploaded file reference.
-    async fn transcribe_file(&self, file_uri: &str, chunk: &AudioChunk) -> Result<Transcript> {
+    async fn transcribe_file(
+        &self,
+        file_uri: &str,
+        chunk: &AudioChunk,
+        key: &ApiKey,
+    ) -> Result<Transcript> {
         let request = GenerateContentRequest {
             contents: vec![Content {
                 role: "user".to_string(),
                 parts: vec![
                     Part::Text {
-                        text: self.build_prompt(),
+                        text: self.prompt_for(chunk),
                     },
                     Part::FileData {
                         file_data: FileData {
@@ -183,19 +351,31 @@ impl GeminiClient {
             generation_config: Some(GenerationConfig {
                 temperature: Some(0.0),
                 max_output_tokens: Some(8192),
+                response_logprobs: self.logprobs.then_some(true),
             }),
         };
 
-        self.call_generate_content(request, chunk).await
+        self.call_generate_content(request, chunk, Some(key)).await
     }
 
     /// Call the generateContent API endpoint.
+    ///
+    /// Without a `key`, requests rotate over the pool and move to another key on quota errors.
     async fn call_generate_content(
         &self,
         request: GenerateContentRequest,
         chunk: &AudioChunk,
+        key: Option<&ApiKey>,
     ) -> Result<Transcript> {
-        let url = format!("{}?key={}", GENERATE_CONTENT_URL, self.api_key);
+        let build = |key: &str| {
+            self.client
+                .post(format!(
+                    "{}/{}/{}:generateContent?key={}",
+                    self.base_url, MODELS_PATH, self.model, key
+                ))
+                .header("Content-Type", "application/json")
+                .json(&request)
+        };
 
         let mut last_error = None;
 
@@ -206,45 +386,43 @@ impl GeminiClient {
                 tokio::time::sleep(Duration::from_millis(delay)).await;
             }
 
-            let response = self
-                .client
-                .post(&url)
-                .header("Content-Type", "application/json")
-                .json(&request)
-                .send()
-                .await;
+            let response = match key {
+                Some(key) => self.keys.send_with_key(key, build).await,
+                None => self.keys.send(build).await,
+            };
 
             match response {
-                Ok(resp) => {
-                    let status = resp.status();
+                Ok(KeyResponse { key, status, body }) => {
                     debug!("Gemini API response status: {}", status);
 
                     if status.is_success() {
-                        let body = resp.text().await?;
                         debug!("Gemini API response: {}", &body[..body.len().min(500)]);
                         let parsed: GenerateContentResponse = serde_json::from_str(&body)?;
-                        return Ok(self.parse_response(parsed, chunk));
+                        // Truncated and blocked responses are billed too
+                        if let Some(ref usage) = parsed.usage_metadata {
+                            self.usage.record(usage.into());
+                            self.keys.record_tokens(&key, usage.into());
+                        }
+                        return self.parse_response(parsed, chunk);
                     }
 
-                    let error_body = resp.text().await.unwrap_or_default();
-
                     // Don't retry on client errors
-                    if status.as_u16() >= 400 && status.as_u16() < 500 {
-                        return Err(AutosubError::Api(format!(
-                            "Gemini API error ({}): {}",
-                            status, error_body
-                        )));
+                    if status.is_client_error() {
+                        let message = format!("Gemini API error ({}): {}", status, body);
+                        return Err(response_error(status, &body, message));
                     }
 
-                    warn!("Gemini API server error ({}): {}", status, error_body);
+                    warn!("Gemini API server error ({}): {}", status, body);
                     last_error = Some(AutosubError::Api(format!(
                         "Gemini API server error: {}",
                         status
                     )));
                 }
+                // No key configured
+                Err(e @ AutosubError::Config(_)) => return Err(e),
                 Err(e) => {
                     warn!("Gemini API request failed: {}", e);
-                    last_error = Some(e.into());
+                    last_error = Some(e);
                 }
             }
         }
@@ -253,30 +431,114 @@ impl GeminiClient {
     }
 
     /// Parse the Gemini response and extract transcript segments.
-    fn parse_response(&self, response: GenerateContentResponse, chunk: &AudioChunk) -> Transcript {
-        let text = response
-            .candidates
-            .first()
-            .and_then(|c| c.content.parts.first())
+    ///
+    /// Blocked responses become [`AutosubError::ContentBlocked`] and responses
+    /// cut off at the token limit become [`AutosubError::OutputTruncated`], so
+    /// neither is mistaken for a short or empty transcript.
+    fn parse_response(
+        &self,
+        response: GenerateContentResponse,
+        chunk: &AudioChunk,
+    ) -> Result<Transcript> {
+        let range = chunk.region.to_string();
+
+        if let Some(reason) = response.prompt_feedback.and_then(|f| f.block_reason) {
+            return Err(AutosubError::ContentBlocked { reason, range });
+        }
+
+        let candidate = response.candidates.into_iter().next().ok_or_else(|| {
+            AutosubError::Api(format!("Gemini returned no candidates for audio {}", range))
+        })?;
+
+        match candidate.finish_reason.as_deref() {
+            None | Some("STOP") => {}
+            Some("MAX_TOKENS") => return Err(AutosubError::OutputTruncated(range)),
+            Some(
+                reason @ ("SAFETY" | "RECITATION" | "BLOCKLIST" | "PROHIBITED_CONTENT" | "SPII"),
+            ) => {
+                return Err(AutosubError::ContentBlocked {
+                    reason: reason.to_string(),
+                    range,
+                })
+            }
+            Some(other) => warn!(
+                "Gemini finished chunk {} with reason {}, using partial output",
+                chunk.index, other
+            ),
+        }
+
+        let logprobs = candidate.logprobs_result;
+        let text: String = candidate
+            .content
+            .map(|c| c.parts)
+            .unwrap_or_default()
+            .into_iter()
             .map(|p| match p {
-                ResponsePart::Text { text } => text.as_str(),
+                ResponsePart::Text { text } => text,
             })
-            .unwrap_or("");
+            .collect();
 
         debug!("Gemini raw response text: {}", text);
 
-        let segments = self.parse_timestamped_text(text, chunk);
+        // The transcript is a prefix of the full text, so token offsets still line up
+        let token_spans = logprobs.and_then(|l| l.token_spans(&text));
+        let (body, speakers) = Self::split_speaker_block(&text);
+        let segments = self
+            .parse_segments(body, chunk)
+            .into_iter()
+            .map(|(mut segment, span)| {
+                let scored = token_spans
+                    .as_deref()
+                    .and_then(|tokens| span_confidence(tokens, &span));
+                segment.confidence = match (segment.confidence, scored) {
+                    (Some(marked), Some(scored)) => Some(marked.min(scored)),
+                    (marked, scored) => marked.or(scored),
+                };
+                segment
+            })
+            .collect();
 
-        Transcript {
+        Ok(Transcript {
             segments,
             language: self.language.clone(),
             duration: Some(chunk.duration()),
+            speakers,
+            provider: Some(self.provider_id()),
+        })
+    }
+
+    /// Split off the trailing speaker description block, if present.
+    fn split_speaker_block(text: &str) -> (&str, Vec<SpeakerProfile>) {
+        match text.find(SPEAKERS_MARKER) {
+            Some(pos) => {
+                let speakers = text[pos + SPEAKERS_MARKER.len()..]
+                    .lines()
+                    .filter_map(SpeakerProfile::parse)
+                    .collect();
+                (&text[..pos], speakers)
+            }
+            None => (text, Vec::new()),
         }
     }
 
     /// Parse timestamped text like "[00:15] Hello world" into segments.
+    #[cfg(test)]
     fn parse_timestamped_text(&self, text: &str, chunk: &AudioChunk) -> Vec<TranscriptSegment> {
-        let mut segments: Vec<TranscriptSegment> = Vec::new();
+        self.parse_segments(text, chunk)
+            .into_iter()
+            .map(|(segment, _)| segment)
+            .collect()
+    }
+
+    /// Parse timestamped text into segments, each with the byte range of its text.
+    ///
+    /// Lines ending in the uncertainty marker get a low confidence.
+    fn parse_segments(
+        &self,
+        text: &str,
+        chunk: &AudioChunk,
+    ) -> Vec<(TranscriptSegment, Range<usize>)> {
+        let mut segments: Vec<(TranscriptSegment, Range<usize>)> = Vec::new();
 
         // Regex to match [MM:SS] or [HH:MM:SS] timestamps at the start of lines or after newlines
         let timestamp_re =
@@ -299,7 +561,16 @@ impl GeminiClient {
             let timestamp_secs = hours * 3600 + mins * 60 + secs;
             let start = chunk.region.start + Duration::from_secs(timestamp_secs);
 
-            let raw_text = cap.get(4).map(|m| m.as_str().trim()).unwrap_or("");
+            let Some(body) = cap.get(4) else {
+                continue;
+            };
+            let raw_text = body.as_str().trim();
+            let uncertain = text[body.end()..].starts_with(UNCERTAIN_MARKER);
+            let (kind, raw_text) = if self.sdh {
+                split_kind_prefix(raw_text)
+            } else {
+                (SegmentKind::Speech, raw_text)
+            };
 
             // Parse speaker label if present (e.g., "Speaker 1: Hello")
             let (speaker, clean_text) = if raw_text.contains(':') {
@@ -318,18 +589,23 @@ impl GeminiClient {
 
             if !clean_text.is_empty() {
                 // Update end time of previous segment
-                if let Some(prev) = segments.last_mut() {
+                if let Some((prev, _)) = segments.last_mut() {
                     prev.end = start;
                 }
 
-                segments.push(TranscriptSegment {
-                    text: clean_text,
-                    start,
-                    end: chunk.region.end, // Will be updated by next segment or left as chunk end
-                    words: None,
-                    confidence: None,
-                    speaker,
-                });
+                segments.push((
+                    TranscriptSegment {
+                        text: clean_text,
+                        start,
+                        end: chunk.region.end, // Will be updated by next segment or left as chunk end
+                        words: None,
+                        confidence: uncertain.then_some(MARKED_UNCERTAIN_CONFIDENCE),
+                        speaker,
+                        kind,
+                        original_text: None,
+                    },
+                    body.range(),
+                ));
             }
         }
 
@@ -343,20 +619,57 @@ impl GeminiClient {
                 .collect::<Vec<_>>()
                 .join(" ");
 
-            segments.push(TranscriptSegment {
-                text: clean_text,
-                start: chunk.region.start,
-                end: chunk.region.end,
-                words: None,
-                confidence: None,
-                speaker: None,
-            });
+            segments.push((
+                TranscriptSegment {
+                    text: clean_text,
+                    start: chunk.region.start,
+                    end: chunk.region.end,
+                    words: None,
+                    confidence: None,
+                    speaker: None,
+                    kind: SegmentKind::Speech,
+                    original_text: None,
+                },
+                0..text.len(),
+            ));
         }
 
         segments
     }
 }
 
+/// Error for a failed response. Quota and server errors may clear up on another
+/// provider; any other client error means the request itself was refused.
+fn response_error(status: StatusCode, body: &str, message: String) -> AutosubError {
+    if status.is_client_error() && !is_quota_error(status, body) {
+        AutosubError::ApiRejected(message)
+    } else {
+        AutosubError::Api(message)
+    }
+}
+
+/// Split an SDH kind prefix such as `SOUND:` or `OFFSCREEN Speaker 1:` off a line.
+fn split_kind_prefix(text: &str) -> (SegmentKind, &str) {
+    for &(prefix, kind) in SDH_PREFIXES {
+        let Some(head) = text.get(..prefix.len()) else {
+            continue;
+        };
+        if !head.eq_ignore_ascii_case(prefix) {
+            continue;
+        }
+        let rest = &text[prefix.len()..];
+        // Off-screen lines may carry a speaker label after the prefix.
+        if kind == SegmentKind::OffScreen && rest.starts_with(char::is_whitespace) {
+            let rest = rest.trim_start();
+            return (kind, rest.strip_prefix(':').unwrap_or(rest).trim_start());
+        }
+        if let Some(rest) = rest.strip_prefix(':') {
+            return (kind, rest.trim_start());
+        }
+    }
+    (SegmentKind::Speech, text)
+}
+
 #[async_trait]
 impl Transcriber for GeminiClient {
     async fn transcribe(&self, chunk: &AudioChunk) -> Result<Transcript> {
@@ -380,9 +693,10 @@ impl Transcriber for GeminiClient {
             self.transcribe_inline(chunk).await?
         } else {
             debug!("Uploading file to Files API ({} bytes)", file_size);
-            let file_uri = self.upload_file(&chunk.path).await?;
+            let key = self.keys.acquire()?;
+            let file_uri = self.upload_file(&chunk.path, &key).await?;
             debug!("File uploaded: {}", file_uri);
-            let result = self.transcribe_file(&file_uri, chunk).await?;
+            let result = self.transcribe_file(&file_uri, chunk, &key).await?;
             // Note: In production, we should delete the uploaded file after use
             result
         };
@@ -400,6 +714,30 @@ impl Transcriber for GeminiClient {
         "Google Gemini"
     }
 
+    fn fingerprint(&self) -> String {
+        // Templates render per chunk, so they are identified by their hash and mode
+        let prompt = match self.prompt_template {
+            Some(ref template) => format!(
+                "template:{}|{}{}",
+                template.hash(),
+                self.speaker_instructions(),
+                self.sdh_instructions()
+            ),
+            None => self.build_prompt(),
+        };
+        format!(
+            "gemini|{}|{}|{}{}",
+            self.model,
+            self.language.as_deref().unwrap_or(""),
+            prompt,
+            if self.logprobs { "|logprobs" } else { "" }
+        )
+    }
+
+    fn usage(&self) -> Vec<ModelUsage> {
+        self.usage.model_usage(&self.model)
+    }
+
     fn max_file_size(&self) -> usize {
         MAX_FILE_SIZE
     }
@@ -450,20 +788,84 @@ struct GenerationConfig {
     temperature: Option<f32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     max_output_tokens: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    response_logprobs: Option<bool>,
 }
 
 #[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
 struct GenerateContentResponse {
+    #[serde(default)]
     candidates: Vec<Candidate>,
+    prompt_feedback: Option<PromptFeedback>,
+    usage_metadata: Option<GeminiUsageMetadata>,
 }
 
 #[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PromptFeedback {
+    block_reason: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
 struct Candidate {
-    content: CandidateContent,
+    content: Option<CandidateContent>,
+    finish_reason: Option<String>,
+    logprobs_result: Option<LogprobsResult>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LogprobsResult {
+    #[serde(default)]
+    chosen_candidates: Vec<LogprobToken>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LogprobToken {
+    #[serde(default)]
+    token: String,
+    #[serde(default)]
+    log_probability: f64,
+}
+
+impl LogprobsResult {
+    /// Byte range of each chosen token within `text`, with its log probability.
+    ///
+    /// Returns `None` if the tokens do not add up to the text.
+    fn token_spans(&self, text: &str) -> Option<Vec<(Range<usize>, f64)>> {
+        let mut offset = 0;
+        let spans: Vec<_> = self
+            .chosen_candidates
+            .iter()
+            .map(|t| {
+                let span = offset..offset + t.token.len();
+                offset = span.end;
+                (span, t.log_probability)
+            })
+            .collect();
+        (offset == text.len() && !spans.is_empty()).then_some(spans)
+    }
+}
+
+/// Confidence of a span of text: the geometric mean probability of its tokens.
+fn span_confidence(tokens: &[(Range<usize>, f64)], span: &Range<usize>) -> Option<f64> {
+    let logprobs: Vec<f64> = tokens
+        .iter()
+        .filter(|(t, _)| t.start < span.end && t.end > span.start)
+        .map(|(_, lp)| *lp)
+        .collect();
+    if logprobs.is_empty() {
+        return None;
+    }
+    Some((logprobs.iter().sum::<f64>() / logprobs.len() as f64).exp())
 }
 
 #[derive(Deserialize)]
 struct CandidateContent {
+    #[serde(default)]
     parts: Vec<ResponsePart>,
 }
 
@@ -530,6 +932,42 @@ mod tests {
         assert_eq!(segments[1].text, "Hi there!");
     }
 
+    #[test]
+    fn test_parse_sdh_kinds() {
+        let client = GeminiClient::new("test-key".to_string()).with_sdh(true);
+        let chunk = create_test_chunk();
+
+        let text = "[00:00] SOUND: door slams\n[00:01] MUSIC: tense music\n[00:02] LYRICS: Hold on\n[00:04] OFFSCREEN Speaker 2: Who's there?\n[00:06] OFFSCREEN: Previously...\n[00:08] Speaker 1: Nobody.";
+        let segments = client.parse_timestamped_text(text, &chunk);
+
+        let kinds: Vec<SegmentKind> = segments.iter().map(|s| s.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                SegmentKind::Sound,
+                SegmentKind::Music,
+                SegmentKind::Lyrics,
+                SegmentKind::OffScreen,
+                SegmentKind::OffScreen,
+                SegmentKind::Speech,
+            ]
+        );
+        assert_eq!(segments[0].text, "door slams");
+        assert_eq!(segments[3].speaker, Some("Speaker 2".to_string()));
+        assert_eq!(segments[3].text, "Who's there?");
+        assert_eq!(segments[4].speaker, None);
+        assert_eq!(segments[4].text, "Previously...");
+
+        // Without SDH the prefixes are ordinary text.
+        let plain = GeminiClient::new("test-key".to_string()).parse_timestamped_text(text, &chunk);
+        assert_eq!(plain[0].kind, SegmentKind::Speech);
+        assert_eq!(plain[0].text, "SOUND: door slams");
+        assert_ne!(
+            client.fingerprint(),
+            GeminiClient::new("test-key".to_string()).fingerprint()
+        );
+    }
+
     #[test]
     fn test_parse_no_timestamps() {
         let client = GeminiClient::new("test-key".to_string());
@@ -560,6 +998,151 @@ mod tests {
         let prompt = client.build_prompt();
         assert!(prompt.contains("Speaker 1"));
         assert!(prompt.contains("Speaker 2"));
+        assert!(prompt.contains(SPEAKERS_MARKER));
+    }
+
+    #[test]
+    fn test_prompt_template() {
+        let template = PromptTemplate::parse(
+            "Chunk at {offset}, language {language}.\n{speakers}Reply with [MM:SS] text lines.",
+            crate::prompt::PromptKind::Transcription,
+        )
+        .unwrap();
+        let client = GeminiClient::new("test-key".to_string())
+            .with_language("ja".to_string())
+            .with_prompt_template(template);
+
+        let mut chunk = create_test_chunk();
+        chunk.region.start = Duration::from_secs(3725);
+        assert_eq!(
+            client.prompt_for(&chunk),
+            "Chunk at 01:02:05, language ja.\nReply with [MM:SS] text lines."
+        );
+        assert_ne!(
+            client.fingerprint(),
+            GeminiClient::new("test-key".to_string())
+                .with_language("ja".to_string())
+                .fingerprint()
+        );
+
+        let diarized = GeminiClient::new("test-key".to_string())
+            .with_diarization(true)
+            .with_prompt_template(client.prompt_template.clone().unwrap());
+        assert!(diarized.prompt_for(&chunk).contains(SPEAKERS_MARKER));
+    }
+
+    #[test]
+    fn test_split_speaker_block() {
+        let text = "[00:00] Speaker 1: Hello.\n[00:03] Speaker 2: Hi!\n[SPEAKERS]\nSpeaker 1: name=Ann; gender=female; voice=bright\nSpeaker 2: name=unknown; gender=male; voice=low";
+        let (transcript, speakers) = GeminiClient::split_speaker_block(text);
+
+        assert!(!transcript.contains(SPEAKERS_MARKER));
+        assert_eq!(speakers.len(), 2);
+        assert_eq!(speakers[0].name, Some("Ann".to_string()));
+        assert_eq!(speakers[1].gender, Some("male".to_string()));
+
+        let client = GeminiClient::new("test-key".to_string());
+        let segments = client.parse_timestamped_text(transcript, &create_test_chunk());
+        assert_eq!(segments.len(), 2);
+        assert_eq!(segments[1].text, "Hi!");
+    }
+
+    #[test]
+    fn test_fingerprint_tracks_settings() {
+        let base = GeminiClient::new("test-key".to_string());
+        let other_model = GeminiClient::new("test-key".to_string()).with_model("gemini-2.0-flash");
+        let other_lang = GeminiClient::new("test-key".to_string()).with_language("ja".to_string());
+        let diarized = GeminiClient::new("test-key".to_string()).with_diarization(true);
+
+        assert!(base.fingerprint().contains(DEFAULT_MODEL));
+        assert_ne!(base.fingerprint(), other_model.fingerprint());
+        assert_ne!(base.fingerprint(), other_lang.fingerprint());
+        assert_ne!(base.fingerprint(), diarized.fingerprint());
+    }
+
+    fn parse(client: &GeminiClient, body: &str) -> Result<Transcript> {
+        let response: GenerateContentResponse = serde_json::from_str(body).unwrap();
+        client.parse_response(response, &create_test_chunk())
+    }
+
+    #[test]
+    fn test_parse_response_stop() {
+        let client = GeminiClient::new("test-key".to_string());
+        let body = r#"{"candidates":[{"content":{"parts":[{"text":"[00:00] Hello"},{"text":"\n[00:04] world"}]},"finishReason":"STOP"}]}"#;
+
+        let transcript = parse(&client, body).unwrap();
+
+        assert_eq!(transcript.segments.len(), 2);
+        assert_eq!(transcript.segments[1].text, "world");
+    }
+
+    #[test]
+    fn test_uncertain_marker_sets_confidence() {
+        let client = GeminiClient::new("test-key".to_string());
+        let text = "[00:00] Clear line.\n[00:04] Mumbled words [?]\n[00:08] Another clear line.";
+        let segments = client.parse_timestamped_text(text, &create_test_chunk());
+
+        assert_eq!(segments.len(), 3);
+        assert_eq!(segments[1].text, "Mumbled words");
+        assert_eq!(segments[1].confidence, Some(MARKED_UNCERTAIN_CONFIDENCE));
+        assert_eq!(segments[0].confidence, None);
+        assert_eq!(segments[2].text, "Another clear line.");
+    }
+
+    #[test]
+    fn test_parse_response_logprobs_confidence() {
+        let client = GeminiClient::new("test-key".to_string()).with_logprobs(true);
+        let body = r#"{"candidates":[{"content":{"parts":[{"text":"[00:00] Hello\n[00:04] world"}]},"finishReason":"STOP",
+            "logprobsResult":{"chosenCandidates":[
+                {"token":"[00:00] ","logProbability":0.0},
+                {"token":"Hello","logProbability":-0.01},
+                {"token":"\n[00:04] ","logProbability":0.0},
+                {"token":"world","logProbability":-1.5}]}}]}"#;
+
+        let transcript = parse(&client, body).unwrap();
+
+        let first = transcript.segments[0].confidence.unwrap();
+        let second = transcript.segments[1].confidence.unwrap();
+        assert!(first > 0.98, "{}", first);
+        assert!((second - (-1.5f64).exp()).abs() < 1e-9, "{}", second);
+    }
+
+    #[test]
+    fn test_parse_response_max_tokens() {
+        let client = GeminiClient::new("test-key".to_string());
+        let body = r#"{"candidates":[{"content":{"parts":[{"text":"[00:00] Hello"}]},"finishReason":"MAX_TOKENS"}]}"#;
+
+        match parse(&client, body) {
+            Err(AutosubError::OutputTruncated(range)) => assert_eq!(range, "00:00:10-00:00:30"),
+            other => panic!(
+                "expected truncation, got {:?}",
+                other.map(|t| t.segments.len())
+            ),
+        }
+    }
+
+    #[test]
+    fn test_parse_response_blocked() {
+        let client = GeminiClient::new("test-key".to_string());
+
+        let safety = r#"{"candidates":[{"finishReason":"SAFETY"}]}"#;
+        let err = parse(&client, safety).unwrap_err();
+        assert!(
+            matches!(err, AutosubError::ContentBlocked { ref reason, .. } if reason == "SAFETY")
+        );
+        assert!(err.to_string().contains("00:00:10-00:00:30"));
+
+        let prompt = r#"{"promptFeedback":{"blockReason":"OTHER"}}"#;
+        assert!(matches!(
+            parse(&client, prompt),
+            Err(AutosubError::ContentBlocked { ref reason, .. }) if reason == "OTHER"
+        ));
+
+        let recitation = r#"{"candidates":[{"content":{"parts":[]},"finishReason":"RECITATION"}]}"#;
+        assert!(matches!(
+            parse(&client, recitation),
+            Err(AutosubError::ContentBlocked { .. })
+        ));
     }
 
     #[test]
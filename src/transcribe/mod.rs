@@ -1,25 +1,94 @@
+pub mod cache;
+pub mod fallback;
 pub mod gemini;
 pub mod orchestrator;
+pub mod record;
+pub mod speakers;
+pub mod validate;
 
+pub use cache::{CacheStats, TranscriptCache};
+pub use fallback::FallbackTranscriber;
 pub use gemini::GeminiClient;
-pub use orchestrator::TranscriptionOrchestrator;
+pub use orchestrator::{OrderedChunk, TranscriptionOrchestrator};
+pub use record::{RecordingTranscriber, ReplayTranscriber};
+pub use speakers::{reconcile_speakers, SpeakerProfile, SpeakerRegistry};
+pub use validate::{
+    validate_transcript, FlaggedChunk, ValidationConfig, ValidationCounts, ValidationIssue,
+};
 
 use crate::audio::AudioChunk;
-use crate::config::Config;
-use crate::error::Result;
+use crate::config::{Config, ProviderConfig};
+use crate::error::{AutosubError, Result};
+use crate::keys::ApiKeyPool;
+use crate::prompt::PromptTemplate;
+use crate::usage::ModelUsage;
 use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
 use std::time::Duration;
 
 /// A word with its timestamp information.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WordTimestamp {
     pub word: String,
     pub start: Duration,
     pub end: Duration,
 }
 
+/// What a segment contains. Only SDH transcription produces kinds other than speech.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SegmentKind {
+    #[default]
+    Speech,
+    /// Speech from a speaker who is not on screen (narration, voice-over, phone).
+    OffScreen,
+    /// A non-speech sound, e.g. "door slams".
+    Sound,
+    /// A description of music, e.g. "tense orchestral music".
+    Music,
+    /// Words being sung.
+    Lyrics,
+}
+
+impl SegmentKind {
+    /// Whether the segment carries spoken or sung words rather than a description.
+    pub fn is_spoken(&self) -> bool {
+        matches!(
+            self,
+            SegmentKind::Speech | SegmentKind::OffScreen | SegmentKind::Lyrics
+        )
+    }
+}
+
+impl std::fmt::Display for SegmentKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SegmentKind::Speech => write!(f, "speech"),
+            SegmentKind::OffScreen => write!(f, "off_screen"),
+            SegmentKind::Sound => write!(f, "sound"),
+            SegmentKind::Music => write!(f, "music"),
+            SegmentKind::Lyrics => write!(f, "lyrics"),
+        }
+    }
+}
+
+impl std::str::FromStr for SegmentKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "speech" => Ok(SegmentKind::Speech),
+            "off_screen" | "offscreen" => Ok(SegmentKind::OffScreen),
+            "sound" => Ok(SegmentKind::Sound),
+            "music" => Ok(SegmentKind::Music),
+            "lyrics" => Ok(SegmentKind::Lyrics),
+            _ => Err(format!("Unknown segment kind: {}", s)),
+        }
+    }
+}
+
 /// A single segment of transcribed audio.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TranscriptSegment {
     pub text: String,
     pub start: Duration,
@@ -27,14 +96,24 @@ pub struct TranscriptSegment {
     pub words: Option<Vec<WordTimestamp>>,
     pub confidence: Option<f64>,
     pub speaker: Option<String>,
+    #[serde(default)]
+    pub kind: SegmentKind,
+    /// Source-language text, kept when `text` has been translated.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub original_text: Option<String>,
 }
 
 /// Complete transcription result from processing an audio chunk.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Transcript {
     pub segments: Vec<TranscriptSegment>,
     pub language: Option<String>,
     pub duration: Option<Duration>,
+    /// Speakers described by the transcriber when diarization is enabled.
+    pub speakers: Vec<SpeakerProfile>,
+    /// Provider that produced the segments, e.g. `gemini/gemini-2.5-flash`.
+    #[serde(default)]
+    pub provider: Option<String>,
 }
 
 impl Transcript {
@@ -44,7 +123,35 @@ impl Transcript {
             segments: Vec::new(),
             language: None,
             duration: None,
+            speakers: Vec::new(),
+            provider: None,
+        }
+    }
+
+    /// Shift all timestamps earlier by `offset`, e.g. to make them chunk-relative.
+    pub fn shifted_back(mut self, offset: Duration) -> Self {
+        for segment in &mut self.segments {
+            segment.start = segment.start.saturating_sub(offset);
+            segment.end = segment.end.saturating_sub(offset);
+            for word in segment.words.iter_mut().flatten() {
+                word.start = word.start.saturating_sub(offset);
+                word.end = word.end.saturating_sub(offset);
+            }
+        }
+        self
+    }
+
+    /// Shift all timestamps later by `offset`, e.g. to place them in file time.
+    pub fn shifted_forward(mut self, offset: Duration) -> Self {
+        for segment in &mut self.segments {
+            segment.start += offset;
+            segment.end += offset;
+            for word in segment.words.iter_mut().flatten() {
+                word.start += offset;
+                word.end += offset;
+            }
         }
+        self
     }
 
     /// Create a transcript with a single segment.
@@ -53,6 +160,8 @@ impl Transcript {
             segments: vec![segment],
             language: None,
             duration: None,
+            speakers: Vec::new(),
+            provider: None,
         }
     }
 }
@@ -65,6 +174,13 @@ pub struct TranscriptionResult {
     pub duration: Duration,
 }
 
+/// Number of chunks a provider transcribed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProviderShare {
+    pub provider: String,
+    pub chunks: usize,
+}
+
 /// Trait for transcription providers.
 #[async_trait]
 pub trait Transcriber: Send + Sync {
@@ -74,6 +190,17 @@ pub trait Transcriber: Send + Sync {
     /// Get the provider name for display.
     fn name(&self) -> &'static str;
 
+    /// Identifies everything besides the audio that affects the output
+    /// (provider, model, prompt, language). Used as part of cache keys.
+    fn fingerprint(&self) -> String {
+        self.name().to_string()
+    }
+
+    /// Tokens used so far, per model. Providers without token billing report nothing.
+    fn usage(&self) -> Vec<ModelUsage> {
+        Vec::new()
+    }
+
     /// Maximum file size supported by this provider (in bytes).
     fn max_file_size(&self) -> usize;
 
@@ -81,14 +208,101 @@ pub trait Transcriber: Send + Sync {
     fn supported_formats(&self) -> &[&str];
 }
 
+/// Per-run settings applied to every transcription provider.
+#[derive(Debug, Clone, Default)]
+pub struct TranscriberOptions {
+    /// Source language code.
+    pub language: Option<String>,
+    /// Identify and label speakers.
+    pub diarize: bool,
+    /// Annotate sound events, music, lyrics and off-screen speech.
+    pub sdh: bool,
+    /// User-defined prompt replacing the built-in one.
+    pub prompt_template: Option<PromptTemplate>,
+    /// Keys shared with other providers of the run; defaults to the configured keys.
+    pub key_pool: Option<ApiKeyPool>,
+}
+
 /// Factory function to create a transcriber.
 pub fn create_transcriber(config: &Config) -> Result<Box<dyn Transcriber>> {
-    let api_key = config.gemini_api_key.as_ref().ok_or_else(|| {
-        crate::error::AutosubError::Config(
-            "Gemini API key not set. Set GEMINI_API_KEY environment variable.".to_string(),
-        )
-    })?;
-    Ok(Box::new(GeminiClient::new(api_key.clone())))
+    create_transcriber_with(config, &TranscriberOptions::default())
+}
+
+/// Create the configured transcriber with per-run options.
+///
+/// Several `transcription_providers` entries are wrapped in a [`FallbackTranscriber`];
+/// without any, Gemini with the default model is used.
+pub fn create_transcriber_with(
+    config: &Config,
+    options: &TranscriberOptions,
+) -> Result<Box<dyn Transcriber>> {
+    // One client and key pool for every provider so they share connections, settings and quotas
+    let client = config.http.build_client()?;
+    let keys = options.key_pool.clone().or_else(|| config.key_pool());
+
+    if config.transcription_providers.is_empty() {
+        return build_provider(&ProviderConfig::gemini(), options, &client, keys.as_ref());
+    }
+
+    let mut providers = config
+        .transcription_providers
+        .iter()
+        .map(|entry| build_provider(entry, options, &client, keys.as_ref()))
+        .collect::<Result<Vec<_>>>()?;
+
+    if providers.len() == 1 {
+        return Ok(providers.remove(0));
+    }
+    Ok(Box::new(FallbackTranscriber::new(providers)?))
+}
+
+/// Build a single provider from its config entry.
+///
+/// Entries with their own `api_key` get their own pool; the rest share `keys`.
+fn build_provider(
+    entry: &ProviderConfig,
+    options: &TranscriberOptions,
+    client: &reqwest::Client,
+    keys: Option<&ApiKeyPool>,
+) -> Result<Box<dyn Transcriber>> {
+    match entry.provider.as_str() {
+        "gemini" => {
+            let keys = match (&entry.api_key, keys) {
+                (Some(api_key), _) => ApiKeyPool::parse(api_key),
+                (None, Some(keys)) => keys.clone(),
+                (None, None) => {
+                    return Err(AutosubError::Config(
+                        "Gemini API key not set. Set GEMINI_API_KEY environment variable."
+                            .to_string(),
+                    ))
+                }
+            };
+
+            let mut client = GeminiClient::new(String::new())
+                .with_key_pool(keys)
+                .with_http_client(client.clone())
+                .with_diarization(options.diarize)
+                .with_sdh(options.sdh)
+                .with_logprobs(entry.logprobs);
+            if let Some(model) = &entry.model {
+                client = client.with_model(model.clone());
+            }
+            if let Some(base_url) = &entry.base_url {
+                client = client.with_base_url(base_url.clone());
+            }
+            if let Some(language) = &options.language {
+                client = client.with_language(language.clone());
+            }
+            if let Some(template) = &options.prompt_template {
+                client = client.with_prompt_template(template.clone());
+            }
+            Ok(Box::new(client))
+        }
+        other => Err(AutosubError::Config(format!(
+            "Unknown transcription provider: {}. Supported: gemini",
+            other
+        ))),
+    }
 }
 
 #[cfg(test)]
@@ -111,9 +325,66 @@ mod tests {
             words: None,
             confidence: Some(0.95),
             speaker: None,
+            kind: SegmentKind::Speech,
+            original_text: None,
         };
         let t = Transcript::single(segment.clone());
         assert_eq!(t.segments.len(), 1);
         assert_eq!(t.segments[0].text, "Hello world");
     }
+
+    #[test]
+    fn test_transcript_shift_round_trip() {
+        let segment = TranscriptSegment {
+            text: "Hello".to_string(),
+            start: Duration::from_secs(35),
+            end: Duration::from_secs(38),
+            words: None,
+            confidence: None,
+            speaker: None,
+            kind: SegmentKind::Speech,
+            original_text: None,
+        };
+        let relative = Transcript::single(segment).shifted_back(Duration::from_secs(30));
+        assert_eq!(relative.segments[0].start, Duration::from_secs(5));
+
+        let absolute = relative.shifted_forward(Duration::from_secs(30));
+        assert_eq!(absolute.segments[0].end, Duration::from_secs(38));
+    }
+
+    #[test]
+    fn test_create_transcriber_fallback_chain() {
+        let config: Config = toml::from_str(
+            r#"
+            gemini_api_key = "primary-key"
+            default_format = "srt"
+            concurrency = 4
+
+            [[transcription_providers]]
+            provider = "gemini"
+
+            [[transcription_providers]]
+            provider = "gemini"
+            model = "gemini-2.0-flash"
+            api_key = "backup-key"
+            "#,
+        )
+        .unwrap();
+
+        let transcriber = create_transcriber_with(
+            &config,
+            &TranscriberOptions {
+                language: Some("en".to_string()),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let fingerprint = transcriber.fingerprint();
+        assert!(fingerprint.contains("gemini-2.5-flash"));
+        assert!(fingerprint.contains("gemini-2.0-flash"));
+
+        let mut unknown = config.clone();
+        unknown.transcription_providers[1].provider = "whisper".to_string();
+        assert!(create_transcriber(&unknown).is_err());
+    }
 }
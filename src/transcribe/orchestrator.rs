@@ -1,13 +1,28 @@
-use crate::audio::AudioChunk;
+use crate::audio::{cleanup_chunks, extract_subchunk, split_chunk, AudioChunk, SpeechRegion};
 use crate::error::{AutosubError, Result};
-use crate::transcribe::{Transcriber, Transcript, TranscriptSegment, TranscriptionResult};
+use crate::job::Job;
+use crate::transcribe::validate::{
+    validate_transcript, FlaggedChunk, ValidationConfig, ValidationCounts, ValidationIssue,
+};
+use crate::transcribe::{
+    ProviderShare, SpeakerRegistry, Transcriber, Transcript, TranscriptCache, TranscriptSegment,
+    TranscriptionResult,
+};
+use crate::usage::ModelUsage;
 use futures::stream::{FuturesUnordered, StreamExt};
 use indicatif::{ProgressBar, ProgressStyle};
+use std::collections::{BTreeMap, HashMap, VecDeque};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
-use tokio::sync::Semaphore;
+use tokio::sync::{mpsc, Semaphore};
 use tracing::{debug, info, warn};
 
+/// Truncated chunks are not split into halves shorter than this.
+const MIN_SPLIT_HALF: Duration = Duration::from_secs(5);
+
+/// Audio kept on each side of a low-confidence segment when re-transcribing it.
+const REVIEW_PADDING: Duration = Duration::from_millis(250);
+
 /// Result of processing a single chunk.
 #[derive(Debug)]
 pub struct ChunkResult {
@@ -15,6 +30,26 @@ pub struct ChunkResult {
     pub transcript: Option<Transcript>,
     pub error: Option<String>,
     pub duration_ms: u64,
+    /// Validation issues remaining after any retries.
+    pub issues: Vec<ValidationIssue>,
+    /// Number of times the chunk was re-transcribed because it was flagged.
+    pub retries: u32,
+    /// Whether the transcript came from the cache.
+    pub cached: bool,
+    /// Whether the transcript was checkpointed by an earlier run of the job.
+    pub resumed: bool,
+    /// Number of times the chunk was split because the output was truncated.
+    pub splits: u32,
+    /// Low-confidence segments replaced by a more confident focused re-transcription.
+    pub refined: usize,
+}
+
+/// A finished chunk, released in chunk order by [`TranscriptionOrchestrator::process_chunks_streaming`].
+#[derive(Debug, Clone)]
+pub struct OrderedChunk {
+    pub index: usize,
+    /// Transcript with speakers reconciled against earlier chunks, or `None` if the chunk failed.
+    pub transcript: Option<Transcript>,
 }
 
 /// Statistics from the transcription process.
@@ -25,6 +60,22 @@ pub struct TranscriptionStats {
     pub failed_chunks: usize,
     pub total_time: Duration,
     pub avg_chunk_time: Duration,
+    /// Chunks still flagged by validation after retries, in chunk order.
+    pub flagged_chunks: Vec<FlaggedChunk>,
+    /// Chunks re-transcribed at least once because validation flagged them.
+    pub retried_chunks: usize,
+    /// Issues found in the final transcripts, by kind.
+    pub validation: ValidationCounts,
+    /// Chunks served from the transcript cache.
+    pub cache_hits: usize,
+    /// Chunks restored from job checkpoints.
+    pub resumed_chunks: usize,
+    /// Chunks split into smaller pieces because the output was truncated.
+    pub split_chunks: usize,
+    /// Chunks transcribed by each provider, in order of first use.
+    pub providers: Vec<ProviderShare>,
+    /// Low-confidence segments improved by focused re-transcription.
+    pub refined_segments: usize,
 }
 
 /// Orchestrates concurrent transcription of audio chunks.
@@ -32,6 +83,10 @@ pub struct TranscriptionOrchestrator {
     transcriber: Arc<dyn Transcriber>,
     concurrency: usize,
     show_progress: bool,
+    validation: Option<Arc<ValidationConfig>>,
+    cache: Option<TranscriptCache>,
+    job: Option<Arc<Job>>,
+    review_threshold: Option<f64>,
 }
 
 impl TranscriptionOrchestrator {
@@ -41,6 +96,10 @@ impl TranscriptionOrchestrator {
             transcriber: Arc::from(transcriber),
             concurrency,
             show_progress: true,
+            validation: None,
+            cache: None,
+            job: None,
+            review_threshold: None,
         }
     }
 
@@ -50,10 +109,59 @@ impl TranscriptionOrchestrator {
         self
     }
 
+    /// Validate each chunk transcript and retry chunks that look hallucinated.
+    pub fn with_validation(mut self, config: ValidationConfig) -> Self {
+        self.validation = Some(Arc::new(config));
+        self
+    }
+
+    /// Reuse cached transcripts for chunks that were transcribed before.
+    pub fn with_cache(mut self, cache: TranscriptCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Checkpoint finished chunks to a job and skip chunks it already holds.
+    pub fn with_job(mut self, job: Arc<Job>) -> Self {
+        self.job = Some(job);
+        self
+    }
+
+    /// Re-transcribe segments with a confidence below `threshold` from a clip of just that segment.
+    pub fn with_confidence_review(mut self, threshold: f64) -> Self {
+        self.review_threshold = Some(threshold);
+        self
+    }
+
+    /// Tokens used by the transcriber so far.
+    pub fn usage(&self) -> Vec<ModelUsage> {
+        self.transcriber.usage()
+    }
+
     /// Process all chunks concurrently and return the combined transcript.
     pub async fn process_chunks(
         &self,
         chunks: Vec<AudioChunk>,
+    ) -> Result<(TranscriptionResult, TranscriptionStats)> {
+        self.run_chunks(chunks, None).await
+    }
+
+    /// Like [`process_chunks`](Self::process_chunks), but also sends each chunk to
+    /// `stream` as soon as it and every chunk before it have finished.
+    ///
+    /// The sender is dropped when processing ends, which closes the stream.
+    pub async fn process_chunks_streaming(
+        &self,
+        chunks: Vec<AudioChunk>,
+        stream: mpsc::UnboundedSender<OrderedChunk>,
+    ) -> Result<(TranscriptionResult, TranscriptionStats)> {
+        self.run_chunks(chunks, Some(stream)).await
+    }
+
+    async fn run_chunks(
+        &self,
+        chunks: Vec<AudioChunk>,
+        stream: Option<mpsc::UnboundedSender<OrderedChunk>>,
     ) -> Result<(TranscriptionResult, TranscriptionStats)> {
         if chunks.is_empty() {
             return Ok((
@@ -68,6 +176,14 @@ impl TranscriptionOrchestrator {
                     failed_chunks: 0,
                     total_time: Duration::ZERO,
                     avg_chunk_time: Duration::ZERO,
+                    flagged_chunks: Vec::new(),
+                    retried_chunks: 0,
+                    validation: ValidationCounts::default(),
+                    cache_hits: 0,
+                    resumed_chunks: 0,
+                    split_chunks: 0,
+                    providers: Vec::new(),
+                    refined_segments: 0,
                 },
             ));
         }
@@ -99,12 +215,25 @@ impl TranscriptionOrchestrator {
         // Use semaphore to limit concurrency
         let semaphore = Arc::new(Semaphore::new(self.concurrency));
 
+        // Kept so flagged chunks can be reported by their audio range
+        let chunk_regions: HashMap<usize, SpeechRegion> =
+            chunks.iter().map(|c| (c.index, c.region.clone())).collect();
+
+        // Chunks are released in this order, whatever order they finish in
+        let mut release_order: Vec<usize> = chunks.iter().map(|c| c.index).collect();
+        release_order.sort_unstable();
+        let mut release_order = VecDeque::from(release_order);
+
         // Create futures for all chunks
         let mut futures = FuturesUnordered::new();
 
         for chunk in chunks {
             let sem = semaphore.clone();
             let transcriber = self.transcriber.clone();
+            let validation = self.validation.clone();
+            let cache = self.cache.clone();
+            let job = self.job.clone();
+            let review_threshold = self.review_threshold;
             let pb = progress_bar.clone();
 
             let future = async move {
@@ -116,7 +245,39 @@ impl TranscriptionOrchestrator {
 
                 debug!("Starting transcription of chunk {}", index);
 
-                let result = transcriber.transcribe(&chunk).await;
+                // Chunks finished by an earlier run of the same job are not redone
+                let checkpoint = job.as_ref().and_then(|job| job.load_chunk(index));
+                let resumed = checkpoint.is_some();
+
+                let (mut result, issues, retries, from_cache, splits) = match checkpoint {
+                    Some(transcript) => (Ok(transcript), Vec::new(), 0, false, 0),
+                    None => {
+                        transcribe_splitting(
+                            &*transcriber,
+                            &chunk,
+                            validation.as_deref(),
+                            cache.as_ref(),
+                        )
+                        .await
+                    }
+                };
+
+                // Checkpoints were refined before they were saved, and cache hits are
+                // reused as they are rather than paying for the clips again
+                let mut refined = 0;
+                if let (Some(threshold), Ok(transcript), false) =
+                    (review_threshold, &mut result, resumed || from_cache)
+                {
+                    refined =
+                        refine_low_confidence(&*transcriber, &chunk, transcript, threshold).await;
+                }
+
+                if let (Some(job), Ok(transcript), false) = (&job, &result, resumed) {
+                    if let Err(e) = job.save_chunk(index, transcript) {
+                        warn!("Failed to checkpoint chunk {}: {}", index, e);
+                    }
+                }
+
                 let duration_ms = chunk_start.elapsed().as_millis() as u64;
 
                 if let Some(ref pb) = pb {
@@ -126,11 +287,20 @@ impl TranscriptionOrchestrator {
                 match result {
                     Ok(transcript) => {
                         debug!("Chunk {} completed in {}ms", index, duration_ms);
+                        if !issues.is_empty() {
+                            warn!("Chunk {} still flagged after {} retries", index, retries);
+                        }
                         ChunkResult {
                             index,
                             transcript: Some(transcript),
                             error: None,
                             duration_ms,
+                            issues,
+                            retries,
+                            cached: from_cache,
+                            resumed,
+                            splits,
+                            refined,
                         }
                     }
                     Err(e) => {
@@ -140,6 +310,12 @@ impl TranscriptionOrchestrator {
                             transcript: None,
                             error: Some(e.to_string()),
                             duration_ms,
+                            issues,
+                            retries,
+                            cached: false,
+                            resumed: false,
+                            splits,
+                            refined,
                         }
                     }
                 }
@@ -148,19 +324,46 @@ impl TranscriptionOrchestrator {
             futures.push(future);
         }
 
-        // Collect results
+        // Collect results, releasing each one once all earlier chunks are done
         let mut results: Vec<ChunkResult> = Vec::with_capacity(total_chunks);
+        let mut pending: BTreeMap<usize, ChunkResult> = BTreeMap::new();
+        // Give diarized speakers stable identities across chunks
+        let mut speaker_registry = SpeakerRegistry::new();
+
         while let Some(result) = futures.next().await {
-            results.push(result);
+            pending.insert(result.index, result);
+
+            while let Some(mut result) = release_order
+                .front()
+                .and_then(|index| pending.remove(index))
+            {
+                release_order.pop_front();
+                if let Some(ref mut transcript) = result.transcript {
+                    speaker_registry.reconcile(transcript);
+                }
+                if let Some(ref stream) = stream {
+                    // A closed receiver only means nobody is watching
+                    let _ = stream.send(OrderedChunk {
+                        index: result.index,
+                        transcript: result.transcript.clone(),
+                    });
+                }
+                results.push(result);
+            }
         }
+        drop(stream);
 
         // Finish progress bar
         if let Some(pb) = progress_bar {
             pb.finish_with_message("Transcription complete");
         }
 
-        // Sort results by chunk index to maintain order
-        results.sort_by_key(|r| r.index);
+        if !speaker_registry.speakers().is_empty() {
+            info!(
+                "Identified {} distinct speakers across chunks",
+                speaker_registry.speakers().len()
+            );
+        }
 
         // Aggregate results
         let mut all_segments: Vec<TranscriptSegment> = Vec::new();
@@ -168,14 +371,59 @@ impl TranscriptionOrchestrator {
         let mut successful_count = 0;
         let mut failed_count = 0;
         let mut total_chunk_time_ms: u64 = 0;
+        let mut flagged_chunks = Vec::new();
+        let mut retried_chunks = 0;
+        let mut validation = ValidationCounts::default();
+        let mut cache_hits = 0;
+        let mut resumed_chunks = 0;
+        let mut split_chunks = 0;
+        let mut providers: Vec<ProviderShare> = Vec::new();
+        let mut refined_segments = 0;
 
         for result in &results {
+            refined_segments += result.refined;
             total_chunk_time_ms += result.duration_ms;
 
+            if result.cached {
+                cache_hits += 1;
+            }
+            if result.resumed {
+                resumed_chunks += 1;
+            }
+            if result.splits > 0 {
+                split_chunks += 1;
+            }
+
+            if result.retries > 0 {
+                retried_chunks += 1;
+            }
+            if !result.issues.is_empty() {
+                for issue in &result.issues {
+                    validation.add(*issue);
+                }
+                if let Some(region) = chunk_regions.get(&result.index) {
+                    flagged_chunks.push(FlaggedChunk {
+                        index: result.index,
+                        region: region.clone(),
+                        issues: result.issues.clone(),
+                    });
+                }
+            }
+
             if let Some(ref transcript) = result.transcript {
                 successful_count += 1;
                 all_segments.extend(transcript.segments.clone());
 
+                if let Some(ref provider) = transcript.provider {
+                    match providers.iter_mut().find(|p| &p.provider == provider) {
+                        Some(share) => share.chunks += 1,
+                        None => providers.push(ProviderShare {
+                            provider: provider.clone(),
+                            chunks: 1,
+                        }),
+                    }
+                }
+
                 // Use first detected language
                 if detected_language.is_none() {
                     detected_language = transcript.language.clone();
@@ -198,8 +446,31 @@ impl TranscriptionOrchestrator {
             failed_chunks: failed_count,
             total_time,
             avg_chunk_time,
+            flagged_chunks,
+            retried_chunks,
+            validation,
+            cache_hits,
+            resumed_chunks,
+            split_chunks,
+            providers,
+            refined_segments,
         };
 
+        if cache_hits > 0 {
+            info!(
+                "{} of {} chunks served from cache",
+                cache_hits, total_chunks
+            );
+        }
+        if split_chunks > 0 {
+            info!("{} chunks were split after truncated output", split_chunks);
+        }
+        if resumed_chunks > 0 {
+            info!(
+                "{} of {} chunks restored from checkpoints",
+                resumed_chunks, total_chunks
+            );
+        }
         info!(
             "Transcription complete: {}/{} chunks successful in {:.2}s (avg {:.2}s/chunk)",
             successful_count,
@@ -243,6 +514,7 @@ impl TranscriptionOrchestrator {
         let mut remaining_chunks = chunks;
         let mut all_segments: Vec<TranscriptSegment> = Vec::new();
         let mut detected_language = None;
+        let mut providers = Vec::new();
         let mut total_successful = 0;
         let total_failed = 0;
         let start_time = Instant::now();
@@ -262,7 +534,8 @@ impl TranscriptionOrchestrator {
                 tokio::time::sleep(Duration::from_secs(2u64.pow(attempt - 1))).await;
             }
 
-            let (result, _stats) = self.process_chunks(remaining_chunks).await?;
+            let (result, stats) = self.process_chunks(remaining_chunks).await?;
+            providers.extend(stats.providers);
 
             // Collect successful results
             all_segments.extend(result.segments);
@@ -289,10 +562,18 @@ impl TranscriptionOrchestrator {
             } else {
                 Duration::ZERO
             },
+            flagged_chunks: Vec::new(),
+            retried_chunks: 0,
+            validation: ValidationCounts::default(),
+            cache_hits: 0,
+            resumed_chunks: 0,
+            split_chunks: 0,
+            providers,
+            refined_segments: 0,
         };
 
         // Sort segments by start time
-        all_segments.sort_by(|a, b| a.start.cmp(&b.start));
+        all_segments.sort_by_key(|s| s.start);
 
         let total_duration = all_segments
             .iter()
@@ -311,18 +592,290 @@ impl TranscriptionOrchestrator {
     }
 }
 
+/// Transcribe a chunk, splitting it in half whenever the output is truncated.
+///
+/// Halves are transcribed in order and merged back into one transcript, with
+/// speakers reconciled across them. Returns the combined result, its issues,
+/// the total number of validation retries, whether every piece was a cache hit
+/// and how many times the chunk was split.
+async fn transcribe_splitting(
+    transcriber: &dyn Transcriber,
+    chunk: &AudioChunk,
+    validation: Option<&ValidationConfig>,
+    cache: Option<&TranscriptCache>,
+) -> (Result<Transcript>, Vec<ValidationIssue>, u32, bool, u32) {
+    let mut pending = VecDeque::from([chunk.clone()]);
+    let mut pieces = Vec::new();
+    let mut split_files = Vec::new();
+    let mut all_issues = Vec::new();
+    let mut total_retries = 0;
+    let mut all_cached = true;
+    let mut splits = 0;
+
+    let failure = loop {
+        let Some(piece) = pending.pop_front() else {
+            break None;
+        };
+        let (result, issues, retries, cached) =
+            transcribe_cached(transcriber, &piece, validation, cache).await;
+        total_retries += retries;
+
+        match result {
+            Ok(transcript) => {
+                pieces.push(transcript);
+                all_issues.extend(issues);
+                all_cached &= cached;
+            }
+            Err(AutosubError::OutputTruncated(range)) => {
+                match split_chunk(&piece, MIN_SPLIT_HALF).await {
+                    Ok(Some((first, second))) => {
+                        warn!(
+                            "Output truncated for chunk {} ({}), splitting in half",
+                            chunk.index, range
+                        );
+                        split_files.push(first.clone());
+                        split_files.push(second.clone());
+                        pending.push_front(second);
+                        pending.push_front(first);
+                        splits += 1;
+                    }
+                    Ok(None) => break Some(AutosubError::OutputTruncated(range)),
+                    Err(e) => break Some(e),
+                }
+            }
+            Err(e) => break Some(e),
+        }
+    };
+
+    let _ = cleanup_chunks(&split_files);
+
+    if let Some(e) = failure {
+        return (Err(e), all_issues, total_retries, false, splits);
+    }
+
+    all_issues.sort_by_key(|issue| *issue as u8);
+    all_issues.dedup();
+
+    let transcript = if pieces.len() == 1 {
+        pieces.remove(0)
+    } else {
+        merge_pieces(pieces, chunk)
+    };
+    (
+        Ok(transcript),
+        all_issues,
+        total_retries,
+        all_cached,
+        splits,
+    )
+}
+
+/// Re-transcribe segments with a confidence below `threshold` from clips of just
+/// those segments, keeping each new text only if it is more confident.
+///
+/// Returns the number of segments replaced.
+async fn refine_low_confidence(
+    transcriber: &dyn Transcriber,
+    chunk: &AudioChunk,
+    transcript: &mut Transcript,
+    threshold: f64,
+) -> usize {
+    let mut refined = 0;
+    let mut clips = Vec::new();
+
+    for (i, segment) in transcript.segments.iter_mut().enumerate() {
+        if !segment.confidence.is_some_and(|c| c < threshold) {
+            continue;
+        }
+
+        let region = SpeechRegion {
+            start: segment
+                .start
+                .saturating_sub(REVIEW_PADDING)
+                .max(chunk.region.start),
+            end: (segment.end + REVIEW_PADDING).min(chunk.region.end),
+        };
+        let clip = match extract_subchunk(chunk, region, &format!("r{}", i)).await {
+            Ok(clip) => clip,
+            Err(e) => {
+                warn!("Cannot cut review clip from chunk {}: {}", chunk.index, e);
+                break;
+            }
+        };
+        clips.push(clip.clone());
+
+        match transcriber.transcribe(&clip).await {
+            Ok(focused) => {
+                if let Some(better) = focused_replacement(segment, &focused) {
+                    debug!(
+                        "Refined segment at {:?} in chunk {}: {:?} -> {:?}",
+                        segment.start, chunk.index, segment.text, better.text
+                    );
+                    *segment = better;
+                    refined += 1;
+                }
+            }
+            Err(e) => debug!(
+                "Review of chunk {} segment {} failed: {}",
+                chunk.index, i, e
+            ),
+        }
+    }
+
+    let _ = cleanup_chunks(&clips);
+    if refined > 0 {
+        info!(
+            "Chunk {}: refined {} low-confidence segments",
+            chunk.index, refined
+        );
+    }
+    refined
+}
+
+/// The text of a focused re-transcription as a replacement for `segment`,
+/// if it is more confident. Segments without a confidence count as certain.
+fn focused_replacement(
+    segment: &TranscriptSegment,
+    focused: &Transcript,
+) -> Option<TranscriptSegment> {
+    let text = focused
+        .segments
+        .iter()
+        .map(|s| s.text.trim())
+        .filter(|t| !t.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+    if text.is_empty() {
+        return None;
+    }
+
+    let confidence = focused
+        .segments
+        .iter()
+        .filter_map(|s| s.confidence)
+        .reduce(f64::min);
+    if confidence.unwrap_or(1.0) <= segment.confidence.unwrap_or(1.0) {
+        return None;
+    }
+
+    Some(TranscriptSegment {
+        text,
+        confidence,
+        words: None,
+        ..segment.clone()
+    })
+}
+
+/// Merge the transcripts of consecutive pieces of one chunk.
+fn merge_pieces(mut pieces: Vec<Transcript>, chunk: &AudioChunk) -> Transcript {
+    let mut registry = SpeakerRegistry::new();
+    for piece in &mut pieces {
+        registry.reconcile(piece);
+    }
+
+    Transcript {
+        language: pieces.iter().find_map(|p| p.language.clone()),
+        provider: pieces.iter().find_map(|p| p.provider.clone()),
+        segments: pieces.into_iter().flat_map(|p| p.segments).collect(),
+        duration: Some(chunk.duration()),
+        speakers: registry.speakers().to_vec(),
+    }
+}
+
+/// Transcribe a chunk, consulting the cache first and storing clean results in it.
+///
+/// Returns the result, its remaining issues, the number of retries and whether it was a cache hit.
+async fn transcribe_cached(
+    transcriber: &dyn Transcriber,
+    chunk: &AudioChunk,
+    validation: Option<&ValidationConfig>,
+    cache: Option<&TranscriptCache>,
+) -> (Result<Transcript>, Vec<ValidationIssue>, u32, bool) {
+    let Some(cache) = cache else {
+        let (result, issues, retries) = transcribe_validated(transcriber, chunk, validation).await;
+        return (result, issues, retries, false);
+    };
+
+    let key = match tokio::fs::read(&chunk.path).await {
+        Ok(audio) => Some(TranscriptCache::key(&audio, &transcriber.fingerprint())),
+        Err(e) => {
+            debug!("Cannot read chunk {} for cache lookup: {}", chunk.index, e);
+            None
+        }
+    };
+    if let Some(transcript) = key
+        .as_ref()
+        .and_then(|key| cache.get(key, chunk.region.start))
+    {
+        return (Ok(transcript), Vec::new(), 0, true);
+    }
+
+    let (result, issues, retries) = transcribe_validated(transcriber, chunk, validation).await;
+
+    // Only clean transcripts are worth reusing
+    if let (Some(key), Ok(transcript)) = (&key, &result) {
+        if issues.is_empty() {
+            if let Err(e) = cache.put(key, transcript, chunk.region.start) {
+                warn!("Failed to cache chunk {}: {}", chunk.index, e);
+            }
+        }
+    }
+
+    (result, issues, retries, false)
+}
+
+/// Transcribe a chunk, re-transcribing while validation flags the result.
+///
+/// Returns the final result, its remaining issues and the number of retries.
+async fn transcribe_validated(
+    transcriber: &dyn Transcriber,
+    chunk: &AudioChunk,
+    validation: Option<&ValidationConfig>,
+) -> (Result<Transcript>, Vec<ValidationIssue>, u32) {
+    let max_retries = validation.map_or(0, |c| c.max_retries);
+    let mut retries = 0;
+
+    loop {
+        let result = transcriber.transcribe(chunk).await;
+        let issues = match (&result, validation) {
+            (Ok(transcript), Some(config)) => validate_transcript(transcript, chunk, config),
+            _ => Vec::new(),
+        };
+        if issues.is_empty() || retries >= max_retries {
+            return (result, issues, retries);
+        }
+        retries += 1;
+        warn!(
+            "Chunk {} flagged ({}), retrying ({}/{})",
+            chunk.index,
+            issues
+                .iter()
+                .map(|i| i.to_string())
+                .collect::<Vec<_>>()
+                .join(", "),
+            retries,
+            max_retries
+        );
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::audio::SpeechRegion;
+    use crate::transcribe::{SegmentKind, SpeakerProfile};
     use async_trait::async_trait;
-    use std::path::PathBuf;
+    use std::path::{Path, PathBuf};
     use std::sync::atomic::{AtomicUsize, Ordering};
 
     /// Mock transcriber for testing.
     struct MockTranscriber {
         call_count: AtomicUsize,
         fail_on_index: Option<usize>,
+        /// Number of calls that return a looping transcript before a clean one.
+        garbage_calls: usize,
+        /// Report every response as cut off at the token limit.
+        truncate: bool,
     }
 
     impl MockTranscriber {
@@ -330,6 +883,8 @@ mod tests {
             Self {
                 call_count: AtomicUsize::new(0),
                 fail_on_index: None,
+                garbage_calls: 0,
+                truncate: false,
             }
         }
 
@@ -337,6 +892,17 @@ mod tests {
             Self {
                 call_count: AtomicUsize::new(0),
                 fail_on_index: Some(index),
+                garbage_calls: 0,
+                truncate: false,
+            }
+        }
+
+        fn looping_for(calls: usize) -> Self {
+            Self {
+                call_count: AtomicUsize::new(0),
+                fail_on_index: None,
+                garbage_calls: calls,
+                truncate: false,
             }
         }
     }
@@ -344,7 +910,7 @@ mod tests {
     #[async_trait]
     impl Transcriber for MockTranscriber {
         async fn transcribe(&self, chunk: &AudioChunk) -> Result<Transcript> {
-            self.call_count.fetch_add(1, Ordering::SeqCst);
+            let call = self.call_count.fetch_add(1, Ordering::SeqCst);
 
             // Simulate some processing time
             tokio::time::sleep(Duration::from_millis(10)).await;
@@ -352,18 +918,31 @@ mod tests {
             if self.fail_on_index == Some(chunk.index) {
                 return Err(AutosubError::Transcription("Mock error".to_string()));
             }
+            if self.truncate {
+                return Err(AutosubError::OutputTruncated(chunk.region.to_string()));
+            }
+
+            let text = if call < self.garbage_calls {
+                "Thank you. ".repeat(40)
+            } else {
+                format!("Transcript for chunk {}", chunk.index)
+            };
 
             Ok(Transcript {
                 segments: vec![TranscriptSegment {
-                    text: format!("Transcript for chunk {}", chunk.index),
+                    text,
                     start: chunk.region.start,
                     end: chunk.region.end,
                     words: None,
                     confidence: Some(0.95),
                     speaker: None,
+                    kind: SegmentKind::Speech,
+                    original_text: None,
                 }],
                 language: Some("en".to_string()),
                 duration: Some(chunk.duration()),
+                speakers: Vec::new(),
+                provider: Some("mock".to_string()),
             })
         }
 
@@ -459,4 +1038,246 @@ mod tests {
         assert_eq!(stats.successful_chunks, 4);
         assert_eq!(stats.failed_chunks, 1);
     }
+
+    #[tokio::test]
+    async fn test_streams_chunks_in_order() {
+        let transcriber = Box::new(MockTranscriber::failing_on(2));
+        let orchestrator = TranscriptionOrchestrator::new(transcriber, 4).with_progress(false);
+        let (tx, mut rx) = mpsc::unbounded_channel();
+
+        let mut chunks = create_test_chunks(6);
+        chunks.reverse();
+        orchestrator
+            .process_chunks_streaming(chunks, tx)
+            .await
+            .unwrap();
+
+        let mut streamed = Vec::new();
+        while let Some(chunk) = rx.recv().await {
+            streamed.push((chunk.index, chunk.transcript.is_some()));
+        }
+        assert_eq!(
+            streamed,
+            vec![
+                (0, true),
+                (1, true),
+                (2, false),
+                (3, true),
+                (4, true),
+                (5, true)
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_retries_flagged_chunk() {
+        let transcriber = Box::new(MockTranscriber::looping_for(1));
+        let orchestrator = TranscriptionOrchestrator::new(transcriber, 1)
+            .with_progress(false)
+            .with_validation(ValidationConfig::default());
+
+        let (result, stats) = orchestrator
+            .process_chunks(create_test_chunks(1))
+            .await
+            .unwrap();
+
+        assert_eq!(result.segments[0].text, "Transcript for chunk 0");
+        assert_eq!(stats.retried_chunks, 1);
+        assert!(stats.flagged_chunks.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_counts_chunks_still_flagged() {
+        let transcriber = Box::new(MockTranscriber::looping_for(usize::MAX));
+        let orchestrator = TranscriptionOrchestrator::new(transcriber, 1)
+            .with_progress(false)
+            .with_validation(ValidationConfig::default());
+
+        let (result, stats) = orchestrator
+            .process_chunks(create_test_chunks(2))
+            .await
+            .unwrap();
+
+        // Flagged transcripts are kept, but counted
+        assert_eq!(result.segments.len(), 2);
+        assert_eq!(stats.flagged_chunks.len(), 2);
+        assert_eq!(stats.flagged_chunks[1].index, 1);
+        assert_eq!(
+            stats.flagged_chunks[1].region.start,
+            Duration::from_secs(10)
+        );
+        assert!(stats.flagged_chunks[1]
+            .issues
+            .contains(&ValidationIssue::RepetitionLoop));
+        assert_eq!(stats.validation.repetition_loops, 2);
+    }
+
+    #[tokio::test]
+    async fn test_cache_skips_transcriber() {
+        let dir = tempfile::tempdir().unwrap();
+        let chunk_path = dir.path().join("chunk_0000.wav");
+        std::fs::write(&chunk_path, b"fake audio").unwrap();
+        let chunks = vec![AudioChunk {
+            path: chunk_path,
+            ..create_test_chunks(1).remove(0)
+        }];
+        let cache = TranscriptCache::new(dir.path().join("cache"));
+
+        let first = TranscriptionOrchestrator::new(Box::new(MockTranscriber::new()), 1)
+            .with_progress(false)
+            .with_cache(cache.clone());
+        let (_, stats) = first.process_chunks(chunks.clone()).await.unwrap();
+        assert_eq!(stats.cache_hits, 0);
+
+        let transcriber = Arc::new(MockTranscriber::new());
+        let second = TranscriptionOrchestrator {
+            transcriber: transcriber.clone(),
+            concurrency: 1,
+            show_progress: false,
+            validation: None,
+            cache: Some(cache),
+            job: None,
+            review_threshold: None,
+        };
+        let (result, stats) = second.process_chunks(chunks).await.unwrap();
+
+        assert_eq!(stats.cache_hits, 1);
+        assert_eq!(transcriber.call_count.load(Ordering::SeqCst), 0);
+        assert_eq!(result.segments[0].text, "Transcript for chunk 0");
+    }
+
+    #[tokio::test]
+    async fn test_resumes_from_job_checkpoints() {
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("video.mp4");
+        std::fs::write(&input, b"video").unwrap();
+        let config = crate::pipeline::PipelineConfig::default();
+        let id = Job::id_for(&input, &config).unwrap();
+        let job = Arc::new(
+            Job::open_or_create_in(
+                dir.path(),
+                id,
+                &input,
+                Path::new("out.srt"),
+                &config,
+                &crate::config::TranslatorConfig::named("gemini"),
+                &Default::default(),
+            )
+            .unwrap(),
+        );
+
+        // First run: chunk 1 fails, chunk 0 is checkpointed
+        let first = TranscriptionOrchestrator::new(Box::new(MockTranscriber::failing_on(1)), 2)
+            .with_progress(false)
+            .with_job(job.clone());
+        let (_, stats) = first.process_chunks(create_test_chunks(2)).await.unwrap();
+        assert_eq!(stats.failed_chunks, 1);
+        assert!(job.has_chunk(0));
+        assert!(!job.has_chunk(1));
+
+        // Second run only transcribes the unfinished chunk
+        let transcriber = Arc::new(MockTranscriber::new());
+        let second = TranscriptionOrchestrator {
+            transcriber: transcriber.clone(),
+            concurrency: 2,
+            show_progress: false,
+            validation: None,
+            cache: None,
+            job: Some(job),
+            review_threshold: None,
+        };
+        let (result, stats) = second.process_chunks(create_test_chunks(2)).await.unwrap();
+
+        assert_eq!(transcriber.call_count.load(Ordering::SeqCst), 1);
+        assert_eq!(stats.resumed_chunks, 1);
+        assert_eq!(stats.successful_chunks, 2);
+        assert_eq!(result.segments.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_truncated_short_chunk_fails_with_range() {
+        let transcriber = MockTranscriber {
+            truncate: true,
+            ..MockTranscriber::new()
+        };
+        let chunks = vec![AudioChunk {
+            region: SpeechRegion {
+                start: Duration::from_secs(60),
+                end: Duration::from_secs(66),
+            },
+            path: PathBuf::from("/tmp/chunk_0.wav"),
+            index: 0,
+        }];
+        let orchestrator =
+            TranscriptionOrchestrator::new(Box::new(transcriber), 1).with_progress(false);
+
+        // Too short to split, so the truncation surfaces as the chunk error
+        let result = orchestrator.process_chunks(chunks).await;
+        let err = result.unwrap_err().to_string();
+        assert!(err.contains("00:01:00-00:01:06"), "{}", err);
+    }
+
+    #[test]
+    fn test_focused_replacement_keeps_more_confident_text() {
+        let segment = |text: &str, confidence| TranscriptSegment {
+            text: text.to_string(),
+            start: Duration::from_secs(12),
+            end: Duration::from_secs(15),
+            words: None,
+            confidence,
+            speaker: Some("Speaker 2".to_string()),
+            kind: SegmentKind::Speech,
+            original_text: None,
+        };
+        let original = segment("the quarterly bird", Some(0.3));
+
+        let clearer = Transcript::single(segment("the quarterly board", Some(0.85)));
+        let replaced = focused_replacement(&original, &clearer).unwrap();
+        assert_eq!(replaced.text, "the quarterly board");
+        assert_eq!(replaced.confidence, Some(0.85));
+        assert_eq!(replaced.start, original.start);
+        assert_eq!(replaced.speaker.as_deref(), Some("Speaker 2"));
+
+        // Unmarked lines are treated as certain
+        let unmarked = Transcript::single(segment("the quarterly board", None));
+        assert!(focused_replacement(&original, &unmarked).is_some());
+
+        let worse = Transcript::single(segment("the quarter leeboard", Some(0.2)));
+        assert!(focused_replacement(&original, &worse).is_none());
+        assert!(focused_replacement(&original, &Transcript::empty()).is_none());
+    }
+
+    #[test]
+    fn test_merge_pieces_reconciles_speakers() {
+        let piece = |start: u64, label: &str, profile: &str| Transcript {
+            segments: vec![TranscriptSegment {
+                text: format!("at {}", start),
+                start: Duration::from_secs(start),
+                end: Duration::from_secs(start + 5),
+                words: None,
+                confidence: None,
+                speaker: Some(label.to_string()),
+                kind: SegmentKind::Speech,
+                original_text: None,
+            }],
+            language: Some("en".to_string()),
+            duration: None,
+            speakers: SpeakerProfile::parse(profile).into_iter().collect(),
+            provider: None,
+        };
+        let chunk = create_test_chunks(1).remove(0);
+
+        let merged = merge_pieces(
+            vec![
+                piece(0, "Speaker 1", "Speaker 1: name=Ann; gender=female"),
+                piece(5, "Speaker 2", "Speaker 2: name=Ann; gender=female"),
+            ],
+            &chunk,
+        );
+
+        assert_eq!(merged.segments.len(), 2);
+        assert_eq!(merged.speakers.len(), 1);
+        assert_eq!(merged.segments[1].speaker.as_deref(), Some("Speaker 1"));
+        assert_eq!(merged.duration, Some(chunk.duration()));
+    }
 }
@@ -0,0 +1,231 @@
+//! Recording and replay wrappers for transcription providers.
+//!
+//! Records are keyed by a hash of the chunk audio and store chunk-relative
+//! timestamps, so a replayed run matches chunks regardless of their offset.
+
+use crate::audio::AudioChunk;
+use crate::error::Result;
+use crate::record::{hash_parts, RecordStore};
+use crate::transcribe::{Transcriber, Transcript};
+use crate::usage::ModelUsage;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tokio::fs;
+use tracing::debug;
+
+/// Directory under the recording root holding transcription records.
+const KIND: &str = "transcription";
+
+/// A recorded transcription call.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranscriptionRecord {
+    pub request: TranscriptionRequest,
+    /// The provider's transcript, with timestamps relative to the chunk start.
+    pub response: Transcript,
+}
+
+/// What was sent to the provider.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranscriptionRequest {
+    pub provider: String,
+    /// Provider settings, including the prompt.
+    pub fingerprint: String,
+    pub chunk_index: usize,
+    pub start: Duration,
+    pub end: Duration,
+    /// SHA-256 of the chunk audio, the key records are matched by.
+    pub audio_sha256: String,
+}
+
+/// Hash identifying a chunk's audio.
+async fn chunk_hash(chunk: &AudioChunk) -> Result<String> {
+    let audio = fs::read(&chunk.path).await?;
+    Ok(hash_parts([audio.as_slice()]))
+}
+
+/// Transcriber that saves every successful call of the wrapped provider.
+pub struct RecordingTranscriber {
+    inner: Box<dyn Transcriber>,
+    store: RecordStore,
+}
+
+impl RecordingTranscriber {
+    pub fn new(inner: Box<dyn Transcriber>, store: RecordStore) -> Self {
+        Self { inner, store }
+    }
+}
+
+#[async_trait]
+impl Transcriber for RecordingTranscriber {
+    async fn transcribe(&self, chunk: &AudioChunk) -> Result<Transcript> {
+        let transcript = self.inner.transcribe(chunk).await?;
+
+        let audio_sha256 = chunk_hash(chunk).await?;
+        let record = TranscriptionRecord {
+            request: TranscriptionRequest {
+                provider: self.inner.name().to_string(),
+                fingerprint: self.inner.fingerprint(),
+                chunk_index: chunk.index,
+                start: chunk.region.start,
+                end: chunk.region.end,
+                audio_sha256: audio_sha256.clone(),
+            },
+            response: transcript.clone().shifted_back(chunk.region.start),
+        };
+        self.store.save(KIND, &audio_sha256, &record)?;
+        debug!(
+            "Recorded transcription of chunk {} as {}",
+            chunk.index, audio_sha256
+        );
+
+        Ok(transcript)
+    }
+
+    fn name(&self) -> &'static str {
+        self.inner.name()
+    }
+
+    fn fingerprint(&self) -> String {
+        self.inner.fingerprint()
+    }
+
+    fn usage(&self) -> Vec<ModelUsage> {
+        self.inner.usage()
+    }
+
+    fn max_file_size(&self) -> usize {
+        self.inner.max_file_size()
+    }
+
+    fn supported_formats(&self) -> &[&str] {
+        self.inner.supported_formats()
+    }
+}
+
+/// Transcriber that serves recorded responses without calling any provider.
+pub struct ReplayTranscriber {
+    store: RecordStore,
+}
+
+impl ReplayTranscriber {
+    pub fn new(store: RecordStore) -> Self {
+        Self { store }
+    }
+}
+
+#[async_trait]
+impl Transcriber for ReplayTranscriber {
+    async fn transcribe(&self, chunk: &AudioChunk) -> Result<Transcript> {
+        let audio_sha256 = chunk_hash(chunk).await?;
+        let record: TranscriptionRecord = self.store.load(KIND, &audio_sha256)?;
+        debug!(
+            "Replaying transcription of chunk {} from {}",
+            chunk.index, audio_sha256
+        );
+        Ok(record.response.shifted_forward(chunk.region.start))
+    }
+
+    fn name(&self) -> &'static str {
+        "replay"
+    }
+
+    fn fingerprint(&self) -> String {
+        format!("replay|{}", self.store.dir().display())
+    }
+
+    fn max_file_size(&self) -> usize {
+        usize::MAX
+    }
+
+    fn supported_formats(&self) -> &[&str] {
+        &["wav", "mp3", "aiff", "aac", "ogg", "flac"]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::SpeechRegion;
+    use crate::error::AutosubError;
+    use crate::transcribe::{SegmentKind, TranscriptSegment};
+
+    struct StubTranscriber;
+
+    #[async_trait]
+    impl Transcriber for StubTranscriber {
+        async fn transcribe(&self, chunk: &AudioChunk) -> Result<Transcript> {
+            if chunk.index > 0 {
+                return Err(AutosubError::Api("unavailable".to_string()));
+            }
+            let mut transcript = Transcript::single(TranscriptSegment {
+                text: "Recorded line".to_string(),
+                start: chunk.region.start + Duration::from_secs(1),
+                end: chunk.region.start + Duration::from_secs(3),
+                words: None,
+                confidence: Some(0.8),
+                speaker: None,
+                kind: SegmentKind::Speech,
+                original_text: None,
+            });
+            transcript.provider = Some("gemini/stub".to_string());
+            Ok(transcript)
+        }
+
+        fn name(&self) -> &'static str {
+            "stub"
+        }
+
+        fn max_file_size(&self) -> usize {
+            1024
+        }
+
+        fn supported_formats(&self) -> &[&str] {
+            &["wav"]
+        }
+    }
+
+    fn chunk(
+        dir: &std::path::Path,
+        name: &str,
+        audio: &[u8],
+        start: u64,
+        index: usize,
+    ) -> AudioChunk {
+        let path = dir.join(name);
+        std::fs::write(&path, audio).unwrap();
+        AudioChunk {
+            region: SpeechRegion {
+                start: Duration::from_secs(start),
+                end: Duration::from_secs(start + 30),
+            },
+            path,
+            index,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_replay_serves_recorded_transcript() {
+        let audio_dir = tempfile::tempdir().unwrap();
+        let record_dir = tempfile::tempdir().unwrap();
+        let store = RecordStore::new(record_dir.path());
+
+        let recorder = RecordingTranscriber::new(Box::new(StubTranscriber), store.clone());
+        let recorded = chunk(audio_dir.path(), "a.wav", b"audio-a", 60, 0);
+        recorder.transcribe(&recorded).await.unwrap();
+        // Failed calls are not recorded
+        let failed = chunk(audio_dir.path(), "b.wav", b"audio-b", 90, 1);
+        assert!(recorder.transcribe(&failed).await.is_err());
+
+        // Same audio at another offset replays with shifted timestamps
+        let replayer = ReplayTranscriber::new(store);
+        let moved = chunk(audio_dir.path(), "c.wav", b"audio-a", 120, 3);
+        let transcript = replayer.transcribe(&moved).await.unwrap();
+
+        assert_eq!(transcript.segments[0].text, "Recorded line");
+        assert_eq!(transcript.segments[0].start, Duration::from_secs(121));
+        assert_eq!(transcript.segments[0].confidence, Some(0.8));
+        assert_eq!(transcript.provider.as_deref(), Some("gemini/stub"));
+        assert!(replayer.transcribe(&failed).await.is_err());
+    }
+}
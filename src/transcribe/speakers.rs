@@ -0,0 +1,381 @@
+//! Cross-chunk speaker reconciliation.
+//!
+//! Each audio chunk is diarized independently, so "Speaker 1" in one chunk is
+//! not necessarily "Speaker 1" in the next. The transcriber describes every
+//! speaker it labels; the registry here matches those descriptions against the
+//! speakers seen in earlier chunks and relabels segments with stable,
+//! file-wide identities.
+
+use crate::transcribe::Transcript;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use tracing::debug;
+
+/// Minimum similarity for a chunk-local speaker to be matched to a known one.
+const MATCH_THRESHOLD: f64 = 0.3;
+
+/// Words ignored when comparing voice descriptions.
+const STOPWORDS: &[&str] = &[
+    "a", "an", "the", "and", "or", "with", "of", "to", "in", "is", "voice", "speaker", "unknown",
+];
+
+/// A speaker as described by the transcriber within a single chunk.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpeakerProfile {
+    /// Chunk-local label, e.g. "Speaker 1".
+    pub label: String,
+    /// Name of the speaker, if it was mentioned in the audio.
+    pub name: Option<String>,
+    /// Perceived gender of the voice, if stated.
+    pub gender: Option<String>,
+    /// Free-form description of the voice and role.
+    pub description: String,
+}
+
+impl SpeakerProfile {
+    /// Parse a profile line like
+    /// `Speaker 1: name=Alice; gender=female; voice=calm, low pitch; role=host`.
+    pub fn parse(line: &str) -> Option<Self> {
+        let (label, rest) = line.split_once(':')?;
+        let label = label.trim();
+        if !label.to_lowercase().starts_with("speaker") {
+            return None;
+        }
+
+        let mut name = None;
+        let mut gender = None;
+        let mut description = Vec::new();
+
+        for field in rest.split(';') {
+            let Some((key, value)) = field.split_once('=') else {
+                if !field.trim().is_empty() {
+                    description.push(field.trim().to_string());
+                }
+                continue;
+            };
+            let value = value.trim();
+            if value.is_empty() || value.eq_ignore_ascii_case("unknown") {
+                continue;
+            }
+            match key.trim().to_lowercase().as_str() {
+                "name" => name = Some(value.to_string()),
+                "gender" => gender = Some(value.to_lowercase()),
+                _ => description.push(value.to_string()),
+            }
+        }
+
+        Some(Self {
+            label: label.to_string(),
+            name,
+            gender,
+            description: description.join(", "),
+        })
+    }
+
+    /// Similarity between two profiles in the range 0.0 to 1.0.
+    fn similarity(&self, other: &SpeakerProfile) -> f64 {
+        if let (Some(a), Some(b)) = (&self.name, &other.name) {
+            return if a.eq_ignore_ascii_case(b) { 1.0 } else { 0.0 };
+        }
+        if let (Some(a), Some(b)) = (&self.gender, &other.gender) {
+            if a != b {
+                return 0.0;
+            }
+        }
+
+        let a = description_tokens(&self.description);
+        let b = description_tokens(&other.description);
+        if a.is_empty() || b.is_empty() {
+            return 0.0;
+        }
+        let shared = a.intersection(&b).count() as f64;
+        let total = a.union(&b).count() as f64;
+        shared / total
+    }
+}
+
+/// Split a description into lowercase tokens without stopwords.
+fn description_tokens(description: &str) -> HashSet<String> {
+    description
+        .split(|c: char| !c.is_alphanumeric())
+        .map(|t| t.to_lowercase())
+        .filter(|t| !t.is_empty() && !STOPWORDS.contains(&t.as_str()))
+        .collect()
+}
+
+/// Keeps track of speakers across chunks and assigns stable labels.
+#[derive(Debug, Default)]
+pub struct SpeakerRegistry {
+    speakers: Vec<SpeakerProfile>,
+}
+
+impl SpeakerRegistry {
+    /// Create an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Speakers identified so far, labelled with their file-wide identity.
+    pub fn speakers(&self) -> &[SpeakerProfile] {
+        &self.speakers
+    }
+
+    /// Relabel the speakers of a transcript with file-wide identities.
+    ///
+    /// Transcripts must be reconciled in chunk order.
+    pub fn reconcile(&mut self, transcript: &mut Transcript) {
+        let mut profiles = transcript.speakers.clone();
+
+        // Labels used in segments but never described still need an identity.
+        for segment in &transcript.segments {
+            if let Some(ref label) = segment.speaker {
+                if !profiles.iter().any(|p| &p.label == label) {
+                    profiles.push(SpeakerProfile {
+                        label: label.clone(),
+                        name: None,
+                        gender: None,
+                        description: String::new(),
+                    });
+                }
+            }
+        }
+
+        if profiles.is_empty() {
+            return;
+        }
+
+        let mapping = self.assign(&profiles);
+        debug!("Speaker mapping for chunk: {:?}", mapping);
+
+        for segment in &mut transcript.segments {
+            if let Some(ref label) = segment.speaker {
+                if let Some(global) = mapping.get(label) {
+                    segment.speaker = Some(global.clone());
+                }
+            }
+        }
+        for profile in &mut transcript.speakers {
+            if let Some(global) = mapping.get(&profile.label) {
+                profile.label = global.clone();
+            }
+        }
+    }
+
+    /// Greedily match local profiles to known speakers, best matches first.
+    fn assign(&mut self, profiles: &[SpeakerProfile]) -> HashMap<String, String> {
+        let mut candidates: Vec<(f64, usize, usize)> = Vec::new();
+        for (local_idx, profile) in profiles.iter().enumerate() {
+            for (known_idx, known) in self.speakers.iter().enumerate() {
+                let score = profile.similarity(known);
+                if score >= MATCH_THRESHOLD {
+                    candidates.push((score, local_idx, known_idx));
+                }
+            }
+        }
+        candidates.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+        let mut mapping = HashMap::new();
+        let mut used_local = HashSet::new();
+        let mut used_known = HashSet::new();
+
+        for (_, local_idx, known_idx) in candidates {
+            if used_local.contains(&local_idx) || used_known.contains(&known_idx) {
+                continue;
+            }
+            used_local.insert(local_idx);
+            used_known.insert(known_idx);
+            self.merge_into(known_idx, &profiles[local_idx]);
+            mapping.insert(
+                profiles[local_idx].label.clone(),
+                self.speakers[known_idx].label.clone(),
+            );
+        }
+
+        // Undescribed labels fall back to the known speaker with the same label.
+        for (local_idx, profile) in profiles.iter().enumerate() {
+            if used_local.contains(&local_idx) || !profile_is_empty(profile) {
+                continue;
+            }
+            if let Some(known_idx) = self
+                .speakers
+                .iter()
+                .position(|s| s.label == profile.label)
+                .filter(|idx| !used_known.contains(idx))
+            {
+                used_local.insert(local_idx);
+                used_known.insert(known_idx);
+                mapping.insert(profile.label.clone(), profile.label.clone());
+            }
+        }
+
+        for (local_idx, profile) in profiles.iter().enumerate() {
+            if used_local.contains(&local_idx) {
+                continue;
+            }
+            let label = format!("Speaker {}", self.speakers.len() + 1);
+            self.speakers.push(SpeakerProfile {
+                label: label.clone(),
+                ..profile.clone()
+            });
+            mapping.insert(profile.label.clone(), label);
+        }
+
+        mapping
+    }
+
+    /// Fill in details about a known speaker from a newly matched profile.
+    fn merge_into(&mut self, known_idx: usize, profile: &SpeakerProfile) {
+        let known = &mut self.speakers[known_idx];
+        if known.name.is_none() {
+            known.name = profile.name.clone();
+        }
+        if known.gender.is_none() {
+            known.gender = profile.gender.clone();
+        }
+        if known.description.is_empty() {
+            known.description = profile.description.clone();
+        }
+    }
+}
+
+fn profile_is_empty(profile: &SpeakerProfile) -> bool {
+    profile.name.is_none() && profile.gender.is_none() && profile.description.is_empty()
+}
+
+/// Reconcile speaker labels across transcripts given in chunk order.
+pub fn reconcile_speakers(transcripts: &mut [Transcript]) -> Vec<SpeakerProfile> {
+    let mut registry = SpeakerRegistry::new();
+    for transcript in transcripts.iter_mut() {
+        registry.reconcile(transcript);
+    }
+    registry.speakers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transcribe::{SegmentKind, TranscriptSegment};
+    use std::time::Duration;
+
+    fn transcript(lines: &[(&str, &str)], profiles: &[&str]) -> Transcript {
+        Transcript {
+            segments: lines
+                .iter()
+                .enumerate()
+                .map(|(i, (speaker, text))| TranscriptSegment {
+                    text: text.to_string(),
+                    start: Duration::from_secs(i as u64),
+                    end: Duration::from_secs(i as u64 + 1),
+                    words: None,
+                    confidence: None,
+                    speaker: Some(speaker.to_string()),
+                    kind: SegmentKind::Speech,
+                    original_text: None,
+                })
+                .collect(),
+            language: None,
+            duration: None,
+            provider: None,
+            speakers: profiles
+                .iter()
+                .filter_map(|p| SpeakerProfile::parse(p))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_parse_profile() {
+        let profile = SpeakerProfile::parse(
+            "Speaker 2: name=Bob; gender=Male; voice=deep, raspy; role=guest",
+        )
+        .unwrap();
+        assert_eq!(profile.label, "Speaker 2");
+        assert_eq!(profile.name, Some("Bob".to_string()));
+        assert_eq!(profile.gender, Some("male".to_string()));
+        assert_eq!(profile.description, "deep, raspy, guest");
+    }
+
+    #[test]
+    fn test_parse_profile_unknown_name() {
+        let profile = SpeakerProfile::parse("Speaker 1: name=unknown; gender=female").unwrap();
+        assert!(profile.name.is_none());
+        assert!(SpeakerProfile::parse("Narrator notes").is_none());
+    }
+
+    #[test]
+    fn test_reconcile_swapped_labels() {
+        let mut transcripts = vec![
+            transcript(
+                &[("Speaker 1", "Welcome"), ("Speaker 2", "Thanks")],
+                &[
+                    "Speaker 1: name=Alice; gender=female; voice=calm, bright; role=host",
+                    "Speaker 2: gender=male; voice=deep, raspy; role=guest",
+                ],
+            ),
+            // The model numbered the speakers the other way round in this chunk.
+            transcript(
+                &[("Speaker 1", "So anyway"), ("Speaker 2", "Right")],
+                &[
+                    "Speaker 1: gender=male; voice=deep, raspy; role=guest",
+                    "Speaker 2: name=Alice; gender=female; voice=calm; role=host",
+                ],
+            ),
+        ];
+
+        let speakers = reconcile_speakers(&mut transcripts);
+
+        assert_eq!(speakers.len(), 2);
+        assert_eq!(
+            transcripts[1].segments[0].speaker.as_deref(),
+            Some("Speaker 2")
+        );
+        assert_eq!(
+            transcripts[1].segments[1].speaker.as_deref(),
+            Some("Speaker 1")
+        );
+    }
+
+    #[test]
+    fn test_reconcile_new_speaker() {
+        let mut transcripts = vec![
+            transcript(
+                &[("Speaker 1", "Hi")],
+                &["Speaker 1: gender=female; voice=soft"],
+            ),
+            transcript(
+                &[("Speaker 1", "Hello"), ("Speaker 2", "Hey")],
+                &[
+                    "Speaker 1: gender=male; voice=loud, fast",
+                    "Speaker 2: gender=female; voice=soft",
+                ],
+            ),
+        ];
+
+        let speakers = reconcile_speakers(&mut transcripts);
+
+        assert_eq!(speakers.len(), 2);
+        assert_eq!(
+            transcripts[1].segments[0].speaker.as_deref(),
+            Some("Speaker 2")
+        );
+        assert_eq!(
+            transcripts[1].segments[1].speaker.as_deref(),
+            Some("Speaker 1")
+        );
+    }
+
+    #[test]
+    fn test_reconcile_without_profiles_keeps_labels() {
+        let mut transcripts = vec![
+            transcript(&[("Speaker 1", "One")], &[]),
+            transcript(&[("Speaker 1", "Two")], &[]),
+        ];
+
+        reconcile_speakers(&mut transcripts);
+
+        assert_eq!(
+            transcripts[1].segments[0].speaker.as_deref(),
+            Some("Speaker 1")
+        );
+    }
+}
@@ -0,0 +1,324 @@
+//! Detection of hallucinated or garbage transcriber output.
+//!
+//! LLM transcribers occasionally loop the same sentence, invent speech over
+//! silence, or return timestamps past the end of the chunk. The checks here
+//! run on every chunk transcript so that suspicious chunks can be retried.
+
+use crate::audio::{AudioChunk, SpeechRegion};
+use crate::transcribe::Transcript;
+use std::collections::HashSet;
+use std::time::Duration;
+
+/// A problem found in a chunk transcript.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ValidationIssue {
+    /// The same sentence or phrase repeats over and over.
+    RepetitionLoop,
+    /// A segment is timestamped outside the chunk it came from.
+    TimestampOutOfRange,
+    /// More text than could plausibly be spoken in the chunk.
+    ImplausibleDensity,
+    /// Speech placed in a region where VAD detected silence.
+    SpeechInSilence,
+}
+
+impl std::fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ValidationIssue::RepetitionLoop => write!(f, "repetition loop"),
+            ValidationIssue::TimestampOutOfRange => write!(f, "timestamp outside chunk"),
+            ValidationIssue::ImplausibleDensity => write!(f, "implausible text density"),
+            ValidationIssue::SpeechInSilence => write!(f, "speech in silent region"),
+        }
+    }
+}
+
+/// Configuration for transcript validation.
+#[derive(Debug, Clone)]
+pub struct ValidationConfig {
+    /// Flag text repeated more than this many times in a row.
+    pub max_repeats: usize,
+    /// Flag chunks with more characters per second of audio than this.
+    pub max_chars_per_second: f64,
+    /// Allowed slack for timestamps past the end of the chunk.
+    pub timestamp_tolerance: Duration,
+    /// Speech regions from VAD, in file time. `None` disables the silence check.
+    pub speech_regions: Option<Vec<SpeechRegion>>,
+    /// Minimum segment duration for the silence check to apply.
+    pub min_silent_segment: Duration,
+    /// How many times a flagged chunk is re-transcribed.
+    pub max_retries: u32,
+}
+
+impl Default for ValidationConfig {
+    fn default() -> Self {
+        Self {
+            max_repeats: 3,
+            max_chars_per_second: 30.0,
+            timestamp_tolerance: Duration::from_secs(1),
+            speech_regions: None,
+            min_silent_segment: Duration::from_secs(1),
+            max_retries: 1,
+        }
+    }
+}
+
+/// Per-issue counts of flagged chunks.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ValidationCounts {
+    pub repetition_loops: usize,
+    pub timestamps_out_of_range: usize,
+    pub implausible_density: usize,
+    pub speech_in_silence: usize,
+}
+
+impl ValidationCounts {
+    /// Count one occurrence of an issue.
+    pub fn add(&mut self, issue: ValidationIssue) {
+        match issue {
+            ValidationIssue::RepetitionLoop => self.repetition_loops += 1,
+            ValidationIssue::TimestampOutOfRange => self.timestamps_out_of_range += 1,
+            ValidationIssue::ImplausibleDensity => self.implausible_density += 1,
+            ValidationIssue::SpeechInSilence => self.speech_in_silence += 1,
+        }
+    }
+
+    /// Total number of issues counted.
+    pub fn total(&self) -> usize {
+        self.repetition_loops
+            + self.timestamps_out_of_range
+            + self.implausible_density
+            + self.speech_in_silence
+    }
+}
+
+/// A chunk whose transcript is still flagged after retries, kept for review.
+#[derive(Debug, Clone)]
+pub struct FlaggedChunk {
+    pub index: usize,
+    /// Audio range of the chunk, in file time.
+    pub region: SpeechRegion,
+    pub issues: Vec<ValidationIssue>,
+}
+
+/// Validate a chunk transcript and return the distinct issues found.
+pub fn validate_transcript(
+    transcript: &Transcript,
+    chunk: &AudioChunk,
+    config: &ValidationConfig,
+) -> Vec<ValidationIssue> {
+    let mut issues = Vec::new();
+
+    if has_repetition_loop(transcript, config.max_repeats) {
+        issues.push(ValidationIssue::RepetitionLoop);
+    }
+
+    let latest = chunk.region.end + config.timestamp_tolerance;
+    if transcript
+        .segments
+        .iter()
+        .any(|s| s.start < chunk.region.start || s.start > latest || s.end > latest)
+    {
+        issues.push(ValidationIssue::TimestampOutOfRange);
+    }
+
+    let chunk_secs = chunk.duration().as_secs_f64();
+    if chunk_secs > 0.0 {
+        let chars: usize = transcript
+            .segments
+            .iter()
+            .map(|s| s.text.chars().count())
+            .sum();
+        if chars as f64 / chunk_secs > config.max_chars_per_second {
+            issues.push(ValidationIssue::ImplausibleDensity);
+        }
+    }
+
+    if let Some(ref regions) = config.speech_regions {
+        let in_silence = transcript.segments.iter().any(|s| {
+            s.end.saturating_sub(s.start) >= config.min_silent_segment
+                && !regions.iter().any(|r| r.start < s.end && s.start < r.end)
+        });
+        if in_silence {
+            issues.push(ValidationIssue::SpeechInSilence);
+        }
+    }
+
+    issues
+}
+
+/// Normalize text for repetition comparison.
+fn normalize(text: &str) -> String {
+    text.chars()
+        .filter(|c| c.is_alphanumeric() || c.is_whitespace())
+        .collect::<String>()
+        .to_lowercase()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Whether segments or phrases within a segment repeat more than `max_repeats` times in a row.
+fn has_repetition_loop(transcript: &Transcript, max_repeats: usize) -> bool {
+    // Consecutive identical segments
+    let mut run = 1;
+    let mut previous: Option<String> = None;
+    for segment in &transcript.segments {
+        let text = normalize(&segment.text);
+        if text.is_empty() {
+            continue;
+        }
+        if previous.as_ref() == Some(&text) {
+            run += 1;
+            if run > max_repeats {
+                return true;
+            }
+        } else {
+            run = 1;
+        }
+        previous = Some(text);
+    }
+
+    // Phrases looping inside a single segment
+    transcript.segments.iter().any(|segment| {
+        let normalized = normalize(&segment.text);
+        let words: Vec<&str> = normalized.split_whitespace().collect();
+        has_repeated_ngram(&words, max_repeats)
+    })
+}
+
+/// Whether any phrase of two or more words repeats back-to-back more than `max_repeats` times.
+fn has_repeated_ngram(words: &[&str], max_repeats: usize) -> bool {
+    for n in 2..=8 {
+        if words.len() < n * (max_repeats + 1) {
+            break;
+        }
+        for start in 0..n {
+            let mut run = 1;
+            let mut i = start + n;
+            while i + n <= words.len() {
+                if words[i..i + n] == words[i - n..i] {
+                    run += 1;
+                    if run > max_repeats {
+                        return true;
+                    }
+                } else {
+                    run = 1;
+                }
+                i += n;
+            }
+        }
+    }
+
+    // Catch single words stuck in a loop ("the the the the the ...").
+    let distinct: HashSet<&&str> = words.iter().collect();
+    words.len() >= 4 * (max_repeats + 1) && distinct.len() == 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transcribe::{SegmentKind, TranscriptSegment};
+    use std::path::PathBuf;
+
+    fn chunk() -> AudioChunk {
+        AudioChunk {
+            region: SpeechRegion {
+                start: Duration::from_secs(30),
+                end: Duration::from_secs(60),
+            },
+            path: PathBuf::from("/tmp/chunk.wav"),
+            index: 1,
+        }
+    }
+
+    fn transcript(segments: &[(u64, u64, &str)]) -> Transcript {
+        Transcript {
+            segments: segments
+                .iter()
+                .map(|(start, end, text)| TranscriptSegment {
+                    text: text.to_string(),
+                    start: Duration::from_secs(*start),
+                    end: Duration::from_secs(*end),
+                    words: None,
+                    confidence: None,
+                    speaker: None,
+                    kind: SegmentKind::Speech,
+                    original_text: None,
+                })
+                .collect(),
+            language: None,
+            duration: None,
+            provider: None,
+            speakers: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_clean_transcript() {
+        let t = transcript(&[(30, 35, "Hello there."), (36, 40, "How are you?")]);
+        assert!(validate_transcript(&t, &chunk(), &ValidationConfig::default()).is_empty());
+    }
+
+    #[test]
+    fn test_repeated_segments() {
+        let t = transcript(&[
+            (30, 32, "Thank you."),
+            (32, 34, "Thank you."),
+            (34, 36, "thank you"),
+            (36, 38, "Thank you!"),
+        ]);
+        let issues = validate_transcript(&t, &chunk(), &ValidationConfig::default());
+        assert_eq!(issues, vec![ValidationIssue::RepetitionLoop]);
+    }
+
+    #[test]
+    fn test_repeated_phrase_in_segment() {
+        let t = transcript(&[(
+            30,
+            50,
+            "I think that I think that I think that I think that I think that",
+        )]);
+        let issues = validate_transcript(&t, &chunk(), &ValidationConfig::default());
+        assert!(issues.contains(&ValidationIssue::RepetitionLoop));
+    }
+
+    #[test]
+    fn test_timestamp_out_of_range() {
+        let t = transcript(&[(30, 35, "Fine"), (75, 80, "Past the end")]);
+        let issues = validate_transcript(&t, &chunk(), &ValidationConfig::default());
+        assert_eq!(issues, vec![ValidationIssue::TimestampOutOfRange]);
+    }
+
+    #[test]
+    fn test_implausible_density() {
+        let text = "word ".repeat(250);
+        let t = transcript(&[(30, 60, text.as_str())]);
+        let issues = validate_transcript(&t, &chunk(), &ValidationConfig::default());
+        assert!(issues.contains(&ValidationIssue::ImplausibleDensity));
+    }
+
+    #[test]
+    fn test_speech_in_silence() {
+        let config = ValidationConfig {
+            speech_regions: Some(vec![SpeechRegion {
+                start: Duration::from_secs(30),
+                end: Duration::from_secs(40),
+            }]),
+            ..Default::default()
+        };
+        let t = transcript(&[(31, 35, "Real speech"), (45, 50, "Invented speech")]);
+        let issues = validate_transcript(&t, &chunk(), &config);
+        assert_eq!(issues, vec![ValidationIssue::SpeechInSilence]);
+    }
+
+    #[test]
+    fn test_validation_counts() {
+        let mut counts = ValidationCounts::default();
+        counts.add(ValidationIssue::RepetitionLoop);
+        counts.add(ValidationIssue::SpeechInSilence);
+        counts.add(ValidationIssue::RepetitionLoop);
+        assert_eq!(counts.repetition_loops, 2);
+        assert_eq!(counts.total(), 3);
+    }
+}
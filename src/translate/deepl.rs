@@ -0,0 +1,232 @@
+//! DeepL translation using its REST API.
+
+use crate::error::{AutosubError, Result};
+use crate::translate::{TranslationContext, Translator};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+/// Endpoint of DeepL API Pro keys.
+const PRO_BASE_URL: &str = "https://api.deepl.com";
+
+/// Endpoint of DeepL API Free keys, which end in `:fx`.
+const FREE_BASE_URL: &str = "https://api-free.deepl.com";
+
+/// Translator using the DeepL API.
+pub struct DeepLTranslator {
+    client: Client,
+    api_key: String,
+    base_url: String,
+    source_language: Option<String>,
+}
+
+impl DeepLTranslator {
+    /// Create a DeepL translator, using the Free or Pro endpoint that matches the key.
+    pub fn new(api_key: String) -> Self {
+        let base_url = if api_key.ends_with(":fx") {
+            FREE_BASE_URL
+        } else {
+            PRO_BASE_URL
+        };
+        Self {
+            client: crate::http::default_client(),
+            api_key,
+            base_url: base_url.to_string(),
+            source_language: None,
+        }
+    }
+
+    /// Send requests through a preconfigured HTTP client (timeouts, proxy, CA).
+    pub fn with_http_client(mut self, client: Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// Send requests to a different endpoint, e.g. a proxy or a test server.
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Language of the texts; DeepL detects it when not set.
+    pub fn with_source_language(mut self, language: impl Into<String>) -> Self {
+        self.source_language = Some(language.into());
+        self
+    }
+}
+
+#[derive(Serialize)]
+struct DeepLRequest<'a> {
+    text: &'a [&'a str],
+    target_lang: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    source_lang: Option<String>,
+    /// Text that influences the translation without being translated.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    context: Option<String>,
+    /// Keep line breaks and punctuation DeepL would otherwise normalize.
+    preserve_formatting: bool,
+}
+
+#[derive(Deserialize)]
+struct DeepLResponse {
+    translations: Vec<DeepLTranslation>,
+}
+
+#[derive(Deserialize)]
+struct DeepLTranslation {
+    text: String,
+}
+
+#[async_trait]
+impl Translator for DeepLTranslator {
+    async fn translate(&self, text: &str, target_lang: &str) -> Result<String> {
+        let results = self.translate_batch(&[text], target_lang).await?;
+        Ok(results.into_iter().next().unwrap_or_default())
+    }
+
+    async fn translate_batch(&self, texts: &[&str], target_lang: &str) -> Result<Vec<String>> {
+        self.translate_batch_with_context(texts, target_lang, &TranslationContext::default())
+            .await
+    }
+
+    async fn translate_batch_with_context(
+        &self,
+        texts: &[&str],
+        target_lang: &str,
+        context: &TranslationContext,
+    ) -> Result<Vec<String>> {
+        if texts.is_empty() {
+            return Ok(vec![]);
+        }
+
+        debug!(
+            "Translating {} text(s) to {} with DeepL",
+            texts.len(),
+            target_lang
+        );
+        let request = DeepLRequest {
+            text: texts,
+            target_lang: target_language(target_lang),
+            source_lang: self
+                .source_language
+                .as_deref()
+                .map(|language| primary_subtag(language).to_uppercase()),
+            context: context_text(context),
+            preserve_formatting: true,
+        };
+
+        let response = self
+            .client
+            .post(format!("{}/v2/translate", self.base_url))
+            .header("Authorization", format!("DeepL-Auth-Key {}", self.api_key))
+            .json(&request)
+            .send()
+            .await
+            .map_err(|e| AutosubError::Api(format!("Translation request failed: {}", e)))?;
+        let status = response.status();
+        let body = response.text().await?;
+        if !status.is_success() {
+            return Err(AutosubError::Api(format!(
+                "DeepL API error ({}): {}",
+                status, body
+            )));
+        }
+
+        let response: DeepLResponse = serde_json::from_str(&body)
+            .map_err(|e| AutosubError::Api(format!("Failed to parse DeepL response: {}", e)))?;
+        if response.translations.len() != texts.len() {
+            return Err(AutosubError::Api(format!(
+                "DeepL returned {} translations for {} texts",
+                response.translations.len(),
+                texts.len()
+            )));
+        }
+
+        Ok(response.translations.into_iter().map(|t| t.text).collect())
+    }
+
+    fn supported_languages(&self) -> &[&str] {
+        &SUPPORTED_LANGUAGES
+    }
+
+    fn name(&self) -> &'static str {
+        "deepl"
+    }
+}
+
+/// DeepL target code for a language code. English and Portuguese need a variant.
+fn target_language(code: &str) -> String {
+    match code.to_lowercase().as_str() {
+        "en" => "EN-US".to_string(),
+        "pt" => "PT-BR".to_string(),
+        other => other.to_uppercase(),
+    }
+}
+
+/// `pt` of `pt-BR`: DeepL source languages have no variants.
+fn primary_subtag(code: &str) -> &str {
+    code.split(['-', '_']).next().unwrap_or(code)
+}
+
+/// The summary and surrounding lines as DeepL's untranslated `context` text.
+fn context_text(context: &TranslationContext) -> Option<String> {
+    let parts: Vec<&str> = context
+        .summary
+        .iter()
+        .chain(&context.before)
+        .chain(&context.after)
+        .map(String::as_str)
+        .collect();
+    (!parts.is_empty()).then(|| parts.join("\n"))
+}
+
+/// Target languages offered by DeepL.
+const SUPPORTED_LANGUAGES: [&str; 30] = [
+    "ar", "bg", "cs", "da", "de", "el", "en", "es", "et", "fi", "fr", "hu", "id", "it", "ja", "ko",
+    "lt", "lv", "nb", "nl", "pl", "pt", "ro", "ru", "sk", "sl", "sv", "tr", "uk", "zh",
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_endpoint_follows_key() {
+        assert_eq!(
+            DeepLTranslator::new("abc:fx".to_string()).base_url,
+            FREE_BASE_URL
+        );
+        assert_eq!(
+            DeepLTranslator::new("abc".to_string()).base_url,
+            PRO_BASE_URL
+        );
+        let translator =
+            DeepLTranslator::new("abc".to_string()).with_base_url("http://localhost:8080/");
+        assert_eq!(translator.base_url, "http://localhost:8080");
+    }
+
+    #[test]
+    fn test_language_codes() {
+        assert_eq!(target_language("en"), "EN-US");
+        assert_eq!(target_language("en-gb"), "EN-GB");
+        assert_eq!(target_language("de"), "DE");
+        assert_eq!(primary_subtag("pt-BR"), "pt");
+    }
+
+    #[test]
+    fn test_context_text() {
+        assert_eq!(context_text(&TranslationContext::default()), None);
+        let context = TranslationContext {
+            before: vec!["Where is Anna?".to_string()],
+            after: vec!["She left.".to_string()],
+            summary: Some("Two sisters run a bakery.".to_string()),
+            budgets: vec![10],
+        };
+        assert_eq!(
+            context_text(&context).unwrap(),
+            "Two sisters run a bakery.\nWhere is Anna?\nShe left."
+        );
+    }
+}
@@ -1,26 +1,50 @@
 //! Gemini-based translation using the Generative AI API.
 
 use crate::error::{AutosubError, Result};
-use crate::translate::Translator;
+use crate::keys::ApiKeyPool;
+use crate::prompt::PromptTemplate;
+use crate::translate::{budget_len, Glossary, TranslationContext, Translator};
+use crate::usage::{GeminiUsageMetadata, ModelUsage, UsageTracker};
 use async_trait::async_trait;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use tracing::{debug, warn};
 
+/// Default model used for translation.
+pub const DEFAULT_MODEL: &str = "gemini-2.0-flash";
+
+/// Gemini API base URL.
+const API_BASE_URL: &str = "https://generativelanguage.googleapis.com";
+
+/// Times cues missing from a response are requested again before failing.
+const MAX_REREQUESTS: u32 = 2;
+
+/// Most characters of subtitle text sent to be summarized.
+const MAX_SUMMARY_INPUT_CHARS: usize = 60_000;
+
 /// Translator using Google Gemini API.
 pub struct GeminiTranslator {
     client: Client,
-    api_key: String,
+    keys: ApiKeyPool,
+    base_url: String,
     model: String,
+    prompt_template: Option<PromptTemplate>,
+    glossary: Option<Glossary>,
+    usage: UsageTracker,
 }
 
 impl GeminiTranslator {
-    /// Create a new Gemini translator with the given API key.
+    /// Create a new Gemini translator with the given API key, or several comma-separated keys.
     pub fn new(api_key: String) -> Self {
         Self {
-            client: Client::new(),
-            api_key,
-            model: "gemini-2.0-flash".to_string(),
+            client: crate::http::default_client(),
+            keys: ApiKeyPool::parse(&api_key),
+            base_url: API_BASE_URL.to_string(),
+            model: DEFAULT_MODEL.to_string(),
+            prompt_template: None,
+            glossary: None,
+            usage: UsageTracker::new(),
         }
     }
 
@@ -30,89 +54,133 @@ impl GeminiTranslator {
         self
     }
 
-    /// Build the translation prompt.
-    fn build_prompt(&self, texts: &[&str], target_lang: &str) -> String {
-        let lang_name = language_code_to_name(target_lang);
+    /// Send requests through a preconfigured HTTP client (timeouts, proxy, CA).
+    pub fn with_http_client(mut self, client: Client) -> Self {
+        self.client = client;
+        self
+    }
 
-        if texts.len() == 1 {
-            format!(
-                r#"Translate the following text to {lang_name}. 
-Return ONLY the translated text, nothing else. Preserve all formatting and line breaks.
+    /// Rotate over a shared pool of API keys instead of the translator's own.
+    pub fn with_key_pool(mut self, keys: ApiKeyPool) -> Self {
+        self.keys = keys;
+        self
+    }
 
-Text to translate:
-{}"#,
-                texts[0]
-            )
-        } else {
-            let numbered_texts: String = texts
-                .iter()
-                .enumerate()
-                .map(|(i, t)| format!("[{}] {}", i + 1, t))
-                .collect::<Vec<_>>()
-                .join("\n");
+    /// Send requests to a different endpoint, e.g. a proxy or a test server.
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
 
-            format!(
-                r#"Translate each of the following numbered texts to {lang_name}.
-Return ONLY the translations in the same numbered format. Preserve all formatting.
+    /// Replace the built-in prompt with a user-defined translation template.
+    pub fn with_prompt_template(mut self, template: PromptTemplate) -> Self {
+        self.prompt_template = Some(template);
+        self
+    }
 
-Texts to translate:
-{numbered_texts}"#
-            )
-        }
+    /// Require glossary terms to be translated as listed.
+    pub fn with_glossary(mut self, glossary: Glossary) -> Self {
+        self.glossary = Some(glossary);
+        self
+    }
+
+    /// Build the prompt translating `cues`, given as `(id, text)` pairs.
+    #[cfg(test)]
+    fn build_prompt(
+        &self,
+        cues: &[(usize, &str)],
+        target_lang: &str,
+        context: &TranslationContext,
+    ) -> String {
+        translation_prompt(
+            cues,
+            target_lang,
+            context,
+            self.prompt_template.as_ref(),
+            self.glossary.as_ref(),
+        )
     }
 
-    /// Parse batch translation response.
-    fn parse_batch_response(&self, response: &str, count: usize) -> Vec<String> {
-        let mut results = Vec::with_capacity(count);
-
-        // Try to parse numbered responses
-        for i in 1..=count {
-            let pattern = format!("[{}]", i);
-            let next_pattern = format!("[{}]", i + 1);
-
-            if let Some(start) = response.find(&pattern) {
-                let text_start = start + pattern.len();
-                let text_end = if i < count {
-                    response[text_start..]
-                        .find(&next_pattern)
-                        .map(|p| text_start + p)
-                        .unwrap_or(response.len())
-                } else {
-                    response.len()
-                };
-
-                let translated = response[text_start..text_end].trim().to_string();
-                results.push(translated);
-            }
+    /// Send a prompt and return the text of the reply, constrained to `schema` if given.
+    async fn request(&self, prompt: String, schema: Option<serde_json::Value>) -> Result<String> {
+        let request = GeminiRequest {
+            contents: vec![GeminiContent {
+                parts: vec![GeminiPart { text: prompt }],
+            }],
+            generation_config: schema.map(|schema| GeminiGenerationConfig {
+                response_mime_type: "application/json".to_string(),
+                response_schema: schema,
+            }),
+        };
+
+        let response = self
+            .keys
+            .send(|key| {
+                self.client
+                    .post(format!(
+                        "{}/v1beta/models/{}:generateContent?key={}",
+                        self.base_url, self.model, key
+                    ))
+                    .json(&request)
+            })
+            .await
+            .map_err(|e| match e {
+                AutosubError::Http(e) => {
+                    AutosubError::Api(format!("Translation request failed: {}", e))
+                }
+                other => other,
+            })?;
+        let (status, body) = (response.status, response.body);
+
+        if !status.is_success() {
+            return Err(AutosubError::Api(format!(
+                "Translation API error ({}): {}",
+                status, body
+            )));
         }
 
-        // If parsing failed, split by newlines as fallback
-        if results.len() != count {
-            warn!(
-                "Batch parse failed (got {} of {}), using line-based fallback",
-                results.len(),
-                count
-            );
-            results = response
-                .lines()
-                .filter(|l| !l.trim().is_empty())
-                .take(count)
-                .map(|l| l.trim().to_string())
-                .collect();
+        let gemini_response: GeminiResponse = serde_json::from_str(&body).map_err(|e| {
+            AutosubError::Api(format!("Failed to parse translation response: {}", e))
+        })?;
+
+        if let Some(ref usage) = gemini_response.usage_metadata {
+            self.usage.record(usage.into());
+            self.keys.record_tokens(&response.key, usage.into());
         }
 
-        // Pad with empty strings if still not enough
-        while results.len() < count {
-            results.push(String::new());
+        if let Some(error) = gemini_response.error {
+            return Err(AutosubError::Api(format!(
+                "Gemini error: {}",
+                error.message
+            )));
         }
 
-        results
+        let translated_text = gemini_response
+            .candidates
+            .and_then(|c| c.into_iter().next())
+            .and_then(|c| c.content)
+            .and_then(|c| c.parts)
+            .and_then(|p| p.into_iter().next())
+            .and_then(|p| p.text)
+            .unwrap_or_default();
+
+        Ok(translated_text)
     }
 }
 
 #[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
 struct GeminiRequest {
     contents: Vec<GeminiContent>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    generation_config: Option<GeminiGenerationConfig>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GeminiGenerationConfig {
+    response_mime_type: String,
+    response_schema: serde_json::Value,
 }
 
 #[derive(Serialize)]
@@ -126,9 +194,11 @@ struct GeminiPart {
 }
 
 #[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
 struct GeminiResponse {
     candidates: Option<Vec<GeminiCandidate>>,
     error: Option<GeminiError>,
+    usage_metadata: Option<GeminiUsageMetadata>,
 }
 
 #[derive(Deserialize, Debug)]
@@ -160,84 +230,445 @@ impl Translator for GeminiTranslator {
     }
 
     async fn translate_batch(&self, texts: &[&str], target_lang: &str) -> Result<Vec<String>> {
-        if texts.is_empty() {
-            return Ok(vec![]);
-        }
+        self.translate_batch_with_context(texts, target_lang, &TranslationContext::default())
+            .await
+    }
 
-        debug!("Translating {} text(s) to {}", texts.len(), target_lang);
+    async fn translate_batch_with_context(
+        &self,
+        texts: &[&str],
+        target_lang: &str,
+        context: &TranslationContext,
+    ) -> Result<Vec<String>> {
+        translate_cues(
+            self,
+            texts,
+            target_lang,
+            context,
+            self.prompt_template.as_ref(),
+            self.glossary.as_ref(),
+        )
+        .await
+    }
 
-        let prompt = self.build_prompt(texts, target_lang);
+    async fn condense(
+        &self,
+        sources: &[&str],
+        translations: &[&str],
+        budgets: &[usize],
+        target_lang: &str,
+    ) -> Result<Vec<String>> {
+        condense_cues(
+            self,
+            sources,
+            translations,
+            budgets,
+            target_lang,
+            self.glossary.as_ref(),
+        )
+        .await
+    }
 
-        let request = GeminiRequest {
-            contents: vec![GeminiContent {
-                parts: vec![GeminiPart { text: prompt }],
-            }],
-        };
+    async fn summarize(&self, texts: &[&str]) -> Result<Option<String>> {
+        summarize_texts(self, texts).await
+    }
 
-        let url = format!(
-            "https://generativelanguage.googleapis.com/v1beta/models/{}:generateContent?key={}",
-            self.model, self.api_key
-        );
+    fn supported_languages(&self) -> &[&str] {
+        &SUPPORTED_LANGUAGES
+    }
 
-        let response = self
-            .client
-            .post(&url)
-            .json(&request)
-            .send()
-            .await
-            .map_err(|e| AutosubError::Api(format!("Translation request failed: {}", e)))?;
+    fn name(&self) -> &'static str {
+        "gemini"
+    }
 
-        let status = response.status();
-        let body = response
-            .text()
-            .await
-            .map_err(|e| AutosubError::Api(format!("Failed to read response: {}", e)))?;
+    fn model(&self) -> &str {
+        &self.model
+    }
 
-        if !status.is_success() {
-            return Err(AutosubError::Api(format!(
-                "Translation API error ({}): {}",
-                status, body
-            )));
-        }
+    fn usage(&self) -> Vec<ModelUsage> {
+        self.usage.model_usage(&self.model)
+    }
+}
 
-        let gemini_response: GeminiResponse = serde_json::from_str(&body).map_err(|e| {
-            AutosubError::Api(format!("Failed to parse translation response: {}", e))
-        })?;
+/// A language model answering text prompts, used by the translators built on
+/// numbered-cue prompts (Gemini and OpenAI-compatible chat).
+#[async_trait]
+pub(crate) trait PromptModel: Send + Sync {
+    /// Send a prompt and return the reply text. With `cues`, the reply must be a
+    /// JSON array of `{"id", "text"}` objects.
+    async fn generate(&self, prompt: String, cues: bool) -> Result<String>;
+}
 
-        if let Some(error) = gemini_response.error {
-            return Err(AutosubError::Api(format!(
-                "Gemini error: {}",
-                error.message
-            )));
+#[async_trait]
+impl PromptModel for GeminiTranslator {
+    async fn generate(&self, prompt: String, cues: bool) -> Result<String> {
+        self.request(prompt, cues.then(cue_schema)).await
+    }
+}
+
+/// Build the prompt translating `cues`, given as `(id, text)` pairs.
+pub(crate) fn translation_prompt(
+    cues: &[(usize, &str)],
+    target_lang: &str,
+    context: &TranslationContext,
+    template: Option<&PromptTemplate>,
+    glossary: Option<&Glossary>,
+) -> String {
+    let lang_name = language_code_to_name(target_lang);
+    let numbered_texts = number_cues(cues);
+    let texts: Vec<&str> = cues.iter().map(|(_, text)| *text).collect();
+    let glossary = glossary
+        .map(|glossary| glossary.instructions(target_lang, &texts))
+        .unwrap_or_default();
+    let budgets = budget_instructions(cues, &context.budgets);
+
+    if let Some(template) = template {
+        let values = HashMap::from([
+            ("language", lang_name.to_string()),
+            ("texts", numbered_texts),
+            ("count", cues.len().to_string()),
+            ("glossary", glossary),
+            ("previous_context", context.before.join("\n")),
+            ("next_context", context.after.join("\n")),
+            ("summary", context.summary.clone().unwrap_or_default()),
+            ("budgets", budgets),
+        ]);
+        return template.render(&values);
+    }
+
+    let context_instructions = context_instructions(context);
+
+    format!(
+        r#"Translate each of the following numbered subtitle lines to {lang_name}. Preserve all formatting.
+Reply with a JSON array holding exactly one object per line, {{"id": <the line's number>, "text": "<translation>"}}.
+Every number must appear exactly once: never merge, split, skip or leave a line empty.
+{context_instructions}
+{budgets}{glossary}Lines to translate:
+{numbered_texts}"#
+    )
+}
+
+/// Translate `texts` with numbered-cue prompts, requesting cues missing from a
+/// reply again before giving up.
+pub(crate) async fn translate_cues(
+    model: &dyn PromptModel,
+    texts: &[&str],
+    target_lang: &str,
+    context: &TranslationContext,
+    template: Option<&PromptTemplate>,
+    glossary: Option<&Glossary>,
+) -> Result<Vec<String>> {
+    if texts.is_empty() {
+        return Ok(vec![]);
+    }
+
+    debug!("Translating {} text(s) to {}", texts.len(), target_lang);
+
+    // Blank lines need no translation, so an empty reply for them is never an error
+    let mut translations: Vec<Option<String>> = texts
+        .iter()
+        .map(|t| t.trim().is_empty().then(String::new))
+        .collect();
+
+    for attempt in 0..=MAX_REREQUESTS {
+        let pending: Vec<(usize, &str)> = translations
+            .iter()
+            .enumerate()
+            .filter(|(_, t)| t.is_none())
+            .map(|(i, _)| (i + 1, texts[i]))
+            .collect();
+        if pending.is_empty() {
+            break;
+        }
+        if attempt > 0 {
+            warn!(
+                "Translation response missing cue(s) {}, re-requesting them ({}/{})",
+                cue_ids(&pending),
+                attempt,
+                MAX_REREQUESTS
+            );
         }
 
-        let translated_text = gemini_response
-            .candidates
-            .and_then(|c| c.into_iter().next())
-            .and_then(|c| c.content)
-            .and_then(|c| c.parts)
-            .and_then(|p| p.into_iter().next())
-            .and_then(|p| p.text)
-            .unwrap_or_default();
+        let prompt = translation_prompt(&pending, target_lang, context, template, glossary);
+        let reply = model.generate(prompt, true).await?;
+        for (id, text) in parse_cue_response(&reply, &pending) {
+            translations[id - 1] = Some(text);
+        }
+    }
+
+    let missing: Vec<(usize, &str)> = translations
+        .iter()
+        .enumerate()
+        .filter(|(_, t)| t.is_none())
+        .map(|(i, _)| (i + 1, texts[i]))
+        .collect();
+    if !missing.is_empty() {
+        return Err(AutosubError::Api(format!(
+            "Translation response still missing cue(s) {} of {} after {} re-requests",
+            cue_ids(&missing),
+            texts.len(),
+            MAX_REREQUESTS
+        )));
+    }
+
+    Ok(translations.into_iter().flatten().collect())
+}
+
+/// Shorten `translations` to their `budgets`; lines the reply lacks keep their translation.
+pub(crate) async fn condense_cues(
+    model: &dyn PromptModel,
+    sources: &[&str],
+    translations: &[&str],
+    budgets: &[usize],
+    target_lang: &str,
+    glossary: Option<&Glossary>,
+) -> Result<Vec<String>> {
+    if translations.is_empty() {
+        return Ok(vec![]);
+    }
+
+    debug!(
+        "Condensing {} translation(s) to {}",
+        translations.len(),
+        target_lang
+    );
+    let cues: Vec<(usize, &str)> = translations
+        .iter()
+        .enumerate()
+        .map(|(i, t)| (i + 1, *t))
+        .collect();
+    let glossary = glossary
+        .map(|glossary| glossary.instructions(target_lang, sources))
+        .unwrap_or_default();
+    let prompt = condense_prompt(target_lang, sources, &cues, budgets, &glossary);
+    let reply = model.generate(prompt, true).await?;
+
+    // Lines the reply lacks keep their translation
+    let mut condensed = parse_cue_response(&reply, &cues);
+    Ok(cues
+        .iter()
+        .map(|(id, translation)| {
+            condensed
+                .remove(id)
+                .unwrap_or_else(|| translation.to_string())
+        })
+        .collect())
+}
 
-        if texts.len() == 1 {
-            Ok(vec![translated_text.trim().to_string()])
-        } else {
-            Ok(self.parse_batch_response(&translated_text, texts.len()))
+/// Summarize subtitles for use as translation context.
+pub(crate) async fn summarize_texts(
+    model: &dyn PromptModel,
+    texts: &[&str],
+) -> Result<Option<String>> {
+    if texts.is_empty() {
+        return Ok(None);
+    }
+
+    // Long documents are summarized from their opening, which introduces the characters
+    let mut excerpt = String::new();
+    for text in texts {
+        if excerpt.len() + text.len() > MAX_SUMMARY_INPUT_CHARS {
+            break;
         }
+        excerpt.push_str(text);
+        excerpt.push('\n');
     }
 
-    fn supported_languages(&self) -> &[&str] {
-        &SUPPORTED_LANGUAGES
+    debug!(
+        "Summarizing {} characters of subtitles for translation context",
+        excerpt.len()
+    );
+    let summary = model.generate(summary_prompt(&excerpt), false).await?;
+    let summary = summary.trim();
+    Ok((!summary.is_empty()).then(|| summary.to_string()))
+}
+
+/// Instructions presenting the context of a batch, empty without context.
+fn context_instructions(context: &TranslationContext) -> String {
+    if context.before.is_empty() && context.after.is_empty() && context.summary.is_none() {
+        return String::new();
     }
 
-    fn name(&self) -> &'static str {
-        "gemini"
+    let mut instructions = String::from(
+        "\nThe material below is context only. Use it to keep names, pronouns, gender agreement, \
+         register and running references consistent, but do NOT translate it or include it in your reply.\n",
+    );
+    if let Some(ref summary) = context.summary {
+        instructions.push_str(&format!("\nAbout this video:\n{}\n", summary));
+    }
+    if !context.before.is_empty() {
+        instructions.push_str(&format!(
+            "\nLines just before:\n{}\n",
+            bullet_lines(&context.before)
+        ));
+    }
+    if !context.after.is_empty() {
+        instructions.push_str(&format!(
+            "\nLines just after:\n{}\n",
+            bullet_lines(&context.after)
+        ));
+    }
+    instructions
+}
+
+/// Character limits of the cues that have one, empty when none do.
+fn budget_instructions(cues: &[(usize, &str)], budgets: &[usize]) -> String {
+    let limits: Vec<String> = cues
+        .iter()
+        .filter_map(|(id, _)| {
+            budgets
+                .get(id - 1)
+                .map(|budget| format!("- [{}]: at most {} characters", id, budget))
+        })
+        .collect();
+    if limits.is_empty() {
+        return String::new();
+    }
+    format!(
+        "Viewers must be able to read each line while it is on screen, so keep every translation within its \
+         character limit (line breaks do not count). Condense when needed: drop fillers and repetition and prefer \
+         shorter wording, but keep the meaning.\n{}\n",
+        limits.join("\n")
+    )
+}
+
+/// Prompt asking to shorten translations, given as `(id, translation)` pairs, to their budgets.
+fn condense_prompt(
+    target_lang: &str,
+    sources: &[&str],
+    cues: &[(usize, &str)],
+    budgets: &[usize],
+    glossary: &str,
+) -> String {
+    let lang_name = language_code_to_name(target_lang);
+    let lines = cues
+        .iter()
+        .zip(sources.iter().zip(budgets))
+        .map(|((id, translation), (source, budget))| {
+            format!(
+                "[{}] at most {} characters (now {})\nOriginal: {}\nTranslation: {}",
+                id,
+                budget,
+                budget_len(translation),
+                source,
+                translation
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n");
+
+    format!(
+        r#"These {lang_name} subtitle translations are too long to read in the time they are on screen.
+Shorten each translation to at most its number of characters (line breaks do not count), keeping its meaning, tone and names.
+Drop fillers and repetition and prefer shorter wording; do not add anything.
+Reply with a JSON array holding one object per line, {{"id": <the line's number>, "text": "<shortened translation>"}}.
+{glossary}
+{lines}"#
+    )
+}
+
+fn bullet_lines(lines: &[String]) -> String {
+    lines
+        .iter()
+        .map(|line| format!("- {}", line))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Prompt asking for a summary of subtitles to guide their translation.
+fn summary_prompt(subtitles: &str) -> String {
+    format!(
+        r#"The following are the subtitles of a video. Write a short summary (at most 150 words, in English) for a translator who will translate them piece by piece:
+- the main characters, with their gender and how they relate to each other
+- the setting and topic
+- the register and tone (formal or casual, humour, slang)
+Return ONLY the summary.
+
+Subtitles:
+{subtitles}"#
+    )
+}
+
+/// Number cues by their ID as `[1] first`, `[2] second`, one per line.
+fn number_cues(cues: &[(usize, &str)]) -> String {
+    cues.iter()
+        .map(|(id, text)| format!("[{}] {}", id, text))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Comma-separated IDs of `cues`, for messages.
+fn cue_ids(cues: &[(usize, &str)]) -> String {
+    cues.iter()
+        .map(|(id, _)| id.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// One translated cue in a structured response.
+#[derive(Deserialize, Debug)]
+struct CueTranslation {
+    id: usize,
+    text: String,
+}
+
+/// Response schema: an array of `{id, text}` objects.
+fn cue_schema() -> serde_json::Value {
+    serde_json::json!({
+        "type": "ARRAY",
+        "items": {
+            "type": "OBJECT",
+            "properties": {
+                "id": { "type": "INTEGER" },
+                "text": { "type": "STRING" }
+            },
+            "required": ["id", "text"]
+        }
+    })
+}
+
+/// Valid translations in a structured response to a request for `requested` cues.
+///
+/// IDs that were not requested are ignored. IDs that come back empty or more
+/// than once are treated as missing, so they are requested again rather than
+/// risk a blank or shifted cue.
+fn parse_cue_response(reply: &str, requested: &[(usize, &str)]) -> HashMap<usize, String> {
+    let json = reply
+        .trim()
+        .trim_start_matches("```json")
+        .trim_start_matches("```")
+        .trim_end_matches("```")
+        .trim();
+    let cues: Vec<CueTranslation> = match serde_json::from_str(json) {
+        Ok(cues) => cues,
+        Err(e) => {
+            warn!("Translation response is not a valid cue list: {}", e);
+            return HashMap::new();
+        }
+    };
+
+    let mut seen: HashMap<usize, usize> = HashMap::new();
+    for cue in &cues {
+        *seen.entry(cue.id).or_default() += 1;
+    }
+
+    let mut translations = HashMap::new();
+    for cue in cues {
+        if !requested.iter().any(|(id, _)| *id == cue.id) {
+            warn!("Translation response has unexpected cue {}", cue.id);
+            continue;
+        }
+        let text = cue.text.trim();
+        if seen[&cue.id] > 1 || text.is_empty() {
+            continue;
+        }
+        translations.insert(cue.id, text.to_string());
     }
+    translations
 }
 
 /// Convert language code to human-readable name for better prompting.
-fn language_code_to_name(code: &str) -> &'static str {
+pub(crate) fn language_code_to_name(code: &str) -> &'static str {
     let lowercase = code.to_lowercase();
     match lowercase.as_str() {
         "en" => "English",
@@ -283,7 +714,7 @@ fn language_code_to_name(code: &str) -> &'static str {
 }
 
 /// List of commonly supported language codes.
-const SUPPORTED_LANGUAGES: [&str; 38] = [
+pub(crate) const SUPPORTED_LANGUAGES: [&str; 38] = [
     "en", "es", "fr", "de", "it", "pt", "ru", "ja", "ko", "zh", "ar", "hi", "th", "vi", "id", "ms",
     "tl", "nl", "pl", "tr", "uk", "cs", "sv", "da", "fi", "no", "el", "he", "hu", "ro", "bg", "hr",
     "sk", "sl", "lt", "lv", "et", "bn",
@@ -318,7 +749,11 @@ mod tests {
     #[test]
     fn test_build_prompt_single() {
         let translator = GeminiTranslator::new("test-key".to_string());
-        let prompt = translator.build_prompt(&["Hello, world!"], "es");
+        let prompt = translator.build_prompt(
+            &[(1, "Hello, world!")],
+            "es",
+            &TranslationContext::default(),
+        );
         assert!(prompt.contains("Spanish"));
         assert!(prompt.contains("Hello, world!"));
     }
@@ -326,20 +761,161 @@ mod tests {
     #[test]
     fn test_build_prompt_batch() {
         let translator = GeminiTranslator::new("test-key".to_string());
-        let prompt = translator.build_prompt(&["Hello", "Goodbye"], "ja");
+        let prompt = translator.build_prompt(
+            &[(1, "Hello"), (2, "Goodbye")],
+            "ja",
+            &TranslationContext::default(),
+        );
         assert!(prompt.contains("Japanese"));
         assert!(prompt.contains("[1] Hello"));
         assert!(prompt.contains("[2] Goodbye"));
     }
 
     #[test]
-    fn test_parse_batch_response() {
+    fn test_build_prompt_with_context() {
+        let translator = GeminiTranslator::new("test-key".to_string());
+        let context = TranslationContext {
+            before: vec!["Where is Anna?".to_string()],
+            after: vec!["She left.".to_string()],
+            summary: Some("Two sisters run a bakery.".to_string()),
+            budgets: Vec::new(),
+        };
+        let prompt = translator.build_prompt(&[(1, "Hello"), (2, "Goodbye")], "es", &context);
+
+        assert!(prompt.contains("do NOT translate"));
+        assert!(prompt.contains("Two sisters run a bakery."));
+        assert!(prompt.contains("- Where is Anna?"));
+        assert!(prompt.contains("- She left."));
+        // Context lines are not numbered, so they cannot be mistaken for lines to translate
+        assert!(!prompt.contains("[3]"));
+        assert!(prompt.find("- Where is Anna?").unwrap() < prompt.find("[1] Hello").unwrap());
+    }
+
+    #[test]
+    fn test_build_prompt_from_template() {
+        let template = PromptTemplate::parse(
+            "Translate these {count} lines to {language}. Reply as [{\"id\": N, \"text\": \"...\"}].\n{glossary}{texts}",
+            crate::prompt::PromptKind::Translation,
+        )
+        .unwrap();
+        let translator =
+            GeminiTranslator::new("test-key".to_string()).with_prompt_template(template);
+
+        let prompt = translator.build_prompt(&[(1, "Hello")], "fr", &TranslationContext::default());
+        assert_eq!(
+            prompt,
+            "Translate these 1 lines to French. Reply as [{\"id\": N, \"text\": \"...\"}].\n[1] Hello"
+        );
+    }
+
+    #[test]
+    fn test_build_prompt_with_glossary() {
+        let glossary = Glossary::parse(
+            "do_not_translate = [\"Acme\"]\n[languages.es.terms]\nspaceship = \"nave espacial\"",
+        )
+        .unwrap();
+        let translator =
+            GeminiTranslator::new("test-key".to_string()).with_glossary(glossary.clone());
+
+        let prompt = translator.build_prompt(
+            &[(1, "Board the spaceship")],
+            "es",
+            &TranslationContext::default(),
+        );
+        assert!(prompt.contains("\"spaceship\" → \"nave espacial\""));
+        assert!(!prompt.contains("Acme"));
+        assert!(prompt.find("Glossary").unwrap() < prompt.find("[1] Board").unwrap());
+
+        let template = PromptTemplate::parse(
+            "To {language}, as [{\"id\": N, \"text\": \"...\"}].\n{glossary}{texts}",
+            crate::prompt::PromptKind::Translation,
+        )
+        .unwrap();
+        let translator = translator.with_prompt_template(template);
+        let prompt = translator.build_prompt(&[(1, "Acme")], "fr", &TranslationContext::default());
+        assert_eq!(
+            prompt,
+            "To French, as [{\"id\": N, \"text\": \"...\"}].\nGlossary (always use these renderings exactly):\n- \"Acme\": keep as written, do not translate\n[1] Acme"
+        );
+    }
+
+    #[test]
+    fn test_build_prompt_with_budgets() {
+        let translator = GeminiTranslator::new("test-key".to_string());
+        let context = TranslationContext {
+            budgets: vec![12, 30, 8],
+            ..Default::default()
+        };
+        // Re-requested cues only list their own limits
+        let prompt = translator.build_prompt(&[(1, "Hello"), (3, "Bye")], "es", &context);
+        assert!(prompt.contains("- [1]: at most 12 characters\n- [3]: at most 8 characters\n"));
+        assert!(!prompt.contains("30 characters"));
+        assert!(!prompt.contains("do NOT translate"));
+        assert!(prompt.find("at most 12").unwrap() < prompt.find("[1] Hello").unwrap());
+
+        let prompt = translator.build_prompt(&[(1, "Hello")], "es", &TranslationContext::default());
+        assert!(!prompt.contains("characters"));
+    }
+
+    #[test]
+    fn test_condense_prompt() {
+        let prompt = condense_prompt(
+            "de",
+            &["See you tomorrow"],
+            &[(1, "Wir sehen uns\nmorgen wieder")],
+            &[15],
+            "",
+        );
+        assert!(prompt.contains("German"));
+        assert!(prompt.contains("[1] at most 15 characters (now 26)"));
+        assert!(prompt
+            .contains("Original: See you tomorrow\nTranslation: Wir sehen uns\nmorgen wieder"));
+    }
+
+    #[test]
+    fn test_build_prompt_keeps_cue_ids() {
         let translator = GeminiTranslator::new("test-key".to_string());
-        let response = "[1] Hola\n[2] Adiós";
-        let results = translator.parse_batch_response(response, 2);
+        let prompt = translator.build_prompt(
+            &[(4, "Again"), (7, "Later")],
+            "es",
+            &TranslationContext::default(),
+        );
+        assert!(prompt.contains("[4] Again\n[7] Later"));
+        assert!(!prompt.contains("[1]"));
+    }
+
+    #[test]
+    fn test_parse_cue_response() {
+        let requested = [(1, "Hello"), (2, "Goodbye")];
+        let results = parse_cue_response(
+            r#"[{"id": 2, "text": "Adiós"}, {"id": 1, "text": "Hola"}]"#,
+            &requested,
+        );
         assert_eq!(results.len(), 2);
-        assert_eq!(results[0], "Hola");
-        assert_eq!(results[1], "Adiós");
+        assert_eq!(results[&1], "Hola");
+        assert_eq!(results[&2], "Adiós");
+
+        // Fenced JSON is accepted
+        let results = parse_cue_response(
+            "```json\n[{\"id\": 1, \"text\": \"Hola\"}]\n```",
+            &requested,
+        );
+        assert_eq!(results[&1], "Hola");
+    }
+
+    #[test]
+    fn test_parse_cue_response_rejects_bad_ids() {
+        let requested = [(1, "One"), (2, "Two"), (3, "Three")];
+        let results = parse_cue_response(
+            r#"[{"id": 1, "text": "Uno"}, {"id": 2, "text": "Dos"}, {"id": 2, "text": "Tres"},
+                {"id": 3, "text": "  "}, {"id": 9, "text": "Nueve"}]"#,
+            &requested,
+        );
+        // Duplicated, empty and unexpected IDs are never used
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[&1], "Uno");
+
+        assert!(parse_cue_response("[1] Uno\n[2] Dos", &requested).is_empty());
     }
 
     #[test]
@@ -0,0 +1,325 @@
+//! Translation glossaries.
+//!
+//! A glossary is a TOML file fixing how terms are translated per target
+//! language, plus terms that must be kept as written:
+//!
+//! ```toml
+//! # Kept as written in every language
+//! do_not_translate = ["Acme", "Kubernetes"]
+//!
+//! [languages.es]
+//! do_not_translate = ["Señor Gato"]
+//!
+//! [languages.es.terms]
+//! spaceship = "nave espacial"
+//! ```
+//!
+//! Terms found in a batch are given to the translator, and translations are
+//! checked afterwards for cues where a required term did not come out.
+
+use crate::error::{AutosubError, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Glossary entries for every target language.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Glossary {
+    /// Terms kept as written in every language.
+    pub do_not_translate: Vec<String>,
+    /// Entries per target language code.
+    pub languages: BTreeMap<String, LanguageGlossary>,
+}
+
+/// Glossary entries for one target language.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LanguageGlossary {
+    /// Source term to the translation it must always get.
+    pub terms: BTreeMap<String, String>,
+    /// Terms kept as written in this language.
+    pub do_not_translate: Vec<String>,
+}
+
+/// A source term and the text it must appear as in the translation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GlossaryEntry {
+    pub source: String,
+    pub target: String,
+}
+
+impl GlossaryEntry {
+    /// Whether the term is kept as written rather than translated.
+    pub fn is_kept(&self) -> bool {
+        self.source == self.target
+    }
+}
+
+/// A cue whose source has a glossary term that its translation lacks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GlossaryMiss {
+    /// Position of the cue among the translated texts.
+    pub index: usize,
+    pub source: String,
+    pub expected: String,
+}
+
+impl Glossary {
+    /// Read and validate a glossary file.
+    pub fn load(path: &Path) -> Result<Self> {
+        let source = std::fs::read_to_string(path).map_err(|e| {
+            AutosubError::Config(format!("Cannot read glossary {}: {}", path.display(), e))
+        })?;
+        Self::parse(&source).map_err(|e| match e {
+            AutosubError::Config(msg) => {
+                AutosubError::Config(format!("{}: {}", path.display(), msg))
+            }
+            other => other,
+        })
+    }
+
+    /// Parse and validate glossary TOML.
+    pub fn parse(source: &str) -> Result<Self> {
+        let glossary: Self = toml::from_str(source)
+            .map_err(|e| AutosubError::Config(format!("Invalid glossary: {}", e)))?;
+
+        let blank_kept = glossary
+            .do_not_translate
+            .iter()
+            .chain(
+                glossary
+                    .languages
+                    .values()
+                    .flat_map(|l| &l.do_not_translate),
+            )
+            .any(|term| term.trim().is_empty());
+        let blank_term = glossary
+            .languages
+            .values()
+            .flat_map(|l| &l.terms)
+            .any(|(source, target)| source.trim().is_empty() || target.trim().is_empty());
+        if blank_kept || blank_term {
+            return Err(AutosubError::Config(
+                "Glossary terms and their translations must not be blank".to_string(),
+            ));
+        }
+
+        Ok(glossary)
+    }
+
+    /// Number of terms across all languages.
+    pub fn len(&self) -> usize {
+        self.do_not_translate.len()
+            + self
+                .languages
+                .values()
+                .map(|l| l.terms.len() + l.do_not_translate.len())
+                .sum::<usize>()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Every entry that applies to `target_lang`; language codes match case-insensitively.
+    pub fn entries(&self, target_lang: &str) -> Vec<GlossaryEntry> {
+        let language = self
+            .languages
+            .iter()
+            .find(|(code, _)| code.eq_ignore_ascii_case(target_lang))
+            .map(|(_, language)| language);
+
+        let kept = self
+            .do_not_translate
+            .iter()
+            .chain(language.into_iter().flat_map(|l| &l.do_not_translate))
+            .map(|term| GlossaryEntry {
+                source: term.clone(),
+                target: term.clone(),
+            });
+        let terms = language
+            .into_iter()
+            .flat_map(|l| &l.terms)
+            .map(|(source, target)| GlossaryEntry {
+                source: source.clone(),
+                target: target.clone(),
+            });
+
+        let mut entries: Vec<GlossaryEntry> = Vec::new();
+        for entry in terms.chain(kept) {
+            if !entries.iter().any(|e| e.source == entry.source) {
+                entries.push(entry);
+            }
+        }
+        entries
+    }
+
+    /// Entries for `target_lang` whose source term occurs in any of `texts`.
+    pub fn relevant(&self, target_lang: &str, texts: &[&str]) -> Vec<GlossaryEntry> {
+        self.entries(target_lang)
+            .into_iter()
+            .filter(|entry| texts.iter().any(|text| contains_term(text, &entry.source)))
+            .collect()
+    }
+
+    /// Prompt instructions for the terms occurring in `texts`, empty when none do.
+    pub fn instructions(&self, target_lang: &str, texts: &[&str]) -> String {
+        let entries = self.relevant(target_lang, texts);
+        if entries.is_empty() {
+            return String::new();
+        }
+
+        let mut instructions = String::from("Glossary (always use these renderings exactly):\n");
+        for entry in entries {
+            if entry.is_kept() {
+                instructions.push_str(&format!(
+                    "- \"{}\": keep as written, do not translate\n",
+                    entry.source
+                ));
+            } else {
+                instructions.push_str(&format!("- \"{}\" → \"{}\"\n", entry.source, entry.target));
+            }
+        }
+        instructions
+    }
+
+    /// Cues whose source contains a term that their translation does not render as required.
+    pub fn check(
+        &self,
+        target_lang: &str,
+        sources: &[String],
+        translations: &[String],
+    ) -> Vec<GlossaryMiss> {
+        let entries = self.entries(target_lang);
+        let mut misses = Vec::new();
+        for (index, (source, translation)) in sources.iter().zip(translations).enumerate() {
+            for entry in &entries {
+                if contains_term(source, &entry.source)
+                    && !contains_term(translation, &entry.target)
+                {
+                    misses.push(GlossaryMiss {
+                        index,
+                        source: entry.source.clone(),
+                        expected: entry.target.clone(),
+                    });
+                }
+            }
+        }
+        misses
+    }
+}
+
+/// Case-insensitive search for `term` in `text`.
+///
+/// Latin letters and digits at either end of the term must not continue into
+/// a longer word ("cat" is not found in "category"); scripts written without
+/// spaces match anywhere.
+fn contains_term(text: &str, term: &str) -> bool {
+    let text = text.to_lowercase();
+    let term = term.trim().to_lowercase();
+    if term.is_empty() {
+        return false;
+    }
+
+    let is_word = |c: Option<char>| c.is_some_and(|c| c.is_ascii_alphanumeric());
+    let starts_word = is_word(term.chars().next());
+    let ends_word = is_word(term.chars().last());
+
+    text.match_indices(&term).any(|(start, _)| {
+        let before = text[..start].chars().last();
+        let after = text[start + term.len()..].chars().next();
+        let joined_before = starts_word && is_word(before);
+        let joined_after = ends_word && is_word(after);
+        !joined_before && !joined_after
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GLOSSARY: &str = r#"
+        do_not_translate = ["Acme"]
+
+        [languages.es]
+        do_not_translate = ["Naruto"]
+
+        [languages.es.terms]
+        cat = "gato"
+        spaceship = "nave espacial"
+    "#;
+
+    #[test]
+    fn test_entries_per_language() {
+        let glossary = Glossary::parse(GLOSSARY).unwrap();
+        assert_eq!(glossary.len(), 4);
+
+        let spanish = glossary.entries("ES");
+        assert_eq!(spanish.len(), 4);
+        assert!(spanish
+            .iter()
+            .any(|e| e.source == "spaceship" && e.target == "nave espacial"));
+        assert!(spanish.iter().any(|e| e.source == "Naruto" && e.is_kept()));
+
+        // Other languages only get the global do-not-translate list
+        assert_eq!(
+            glossary.entries("fr"),
+            [GlossaryEntry {
+                source: "Acme".into(),
+                target: "Acme".into()
+            }]
+        );
+    }
+
+    #[test]
+    fn test_instructions_list_only_relevant_terms() {
+        let glossary = Glossary::parse(GLOSSARY).unwrap();
+        let instructions = glossary.instructions("es", &["The Acme spaceship has landed"]);
+        assert!(instructions.contains("\"spaceship\" → \"nave espacial\""));
+        assert!(instructions.contains("\"Acme\": keep as written"));
+        assert!(!instructions.contains("Naruto"));
+        assert!(!instructions.contains("gato"));
+
+        assert_eq!(glossary.instructions("es", &["Nothing here"]), "");
+    }
+
+    #[test]
+    fn test_check_reports_missing_terms() {
+        let glossary = Glossary::parse(GLOSSARY).unwrap();
+        let sources = vec![
+            "My cat likes Acme food".to_string(),
+            "A category of spaceships".to_string(),
+            "Board the spaceship".to_string(),
+        ];
+        let translations = vec![
+            "A mi gato le gusta la comida Acme".to_string(),
+            "Una categoría de naves".to_string(),
+            "Suban a la nave".to_string(),
+        ];
+
+        let misses = glossary.check("es", &sources, &translations);
+        assert_eq!(
+            misses,
+            [GlossaryMiss {
+                index: 2,
+                source: "spaceship".to_string(),
+                expected: "nave espacial".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_contains_term() {
+        assert!(contains_term("Hello, Acme!", "acme"));
+        assert!(!contains_term("category", "cat"));
+        assert!(contains_term("田中さんは来ない", "田中"));
+    }
+
+    #[test]
+    fn test_invalid_glossary_is_rejected() {
+        assert!(Glossary::parse("[languages.es.terms]\ncat = \"\"").is_err());
+        assert!(Glossary::parse("unknown = 1").is_err());
+        assert!(Glossary::parse("do_not_translate = [\" \"]").is_err());
+    }
+}
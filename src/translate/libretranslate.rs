@@ -0,0 +1,156 @@
+//! LibreTranslate translation, for the public instance or a self-hosted server.
+
+use crate::error::{AutosubError, Result};
+use crate::translate::Translator;
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+/// Public LibreTranslate instance, which needs an API key.
+const API_BASE_URL: &str = "https://libretranslate.com";
+
+/// Translator using a LibreTranslate server.
+pub struct LibreTranslateTranslator {
+    client: Client,
+    api_key: Option<String>,
+    base_url: String,
+    source_language: Option<String>,
+}
+
+impl LibreTranslateTranslator {
+    /// Create a translator for the public instance.
+    pub fn new() -> Self {
+        Self {
+            client: crate::http::default_client(),
+            api_key: None,
+            base_url: API_BASE_URL.to_string(),
+            source_language: None,
+        }
+    }
+
+    /// Send requests through a preconfigured HTTP client (timeouts, proxy, CA).
+    pub fn with_http_client(mut self, client: Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// Send requests to a different server, e.g. a self-hosted one.
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Authenticate with an API key; self-hosted servers usually need none.
+    pub fn with_api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.api_key = Some(api_key.into());
+        self
+    }
+
+    /// Language of the texts; the server detects it when not set.
+    pub fn with_source_language(mut self, language: impl Into<String>) -> Self {
+        self.source_language = Some(language.into());
+        self
+    }
+}
+
+impl Default for LibreTranslateTranslator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Serialize)]
+struct LibreTranslateRequest<'a> {
+    q: &'a [&'a str],
+    source: &'a str,
+    target: &'a str,
+    format: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    api_key: Option<&'a str>,
+}
+
+#[derive(Deserialize)]
+struct LibreTranslateResponse {
+    #[serde(rename = "translatedText")]
+    translated_text: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct LibreTranslateError {
+    error: String,
+}
+
+#[async_trait]
+impl Translator for LibreTranslateTranslator {
+    async fn translate(&self, text: &str, target_lang: &str) -> Result<String> {
+        let results = self.translate_batch(&[text], target_lang).await?;
+        Ok(results.into_iter().next().unwrap_or_default())
+    }
+
+    async fn translate_batch(&self, texts: &[&str], target_lang: &str) -> Result<Vec<String>> {
+        if texts.is_empty() {
+            return Ok(vec![]);
+        }
+
+        debug!(
+            "Translating {} text(s) to {} with LibreTranslate",
+            texts.len(),
+            target_lang
+        );
+        let target = target_lang.to_lowercase();
+        let request = LibreTranslateRequest {
+            q: texts,
+            source: self.source_language.as_deref().unwrap_or("auto"),
+            target: &target,
+            format: "text",
+            api_key: self.api_key.as_deref(),
+        };
+
+        let response = self
+            .client
+            .post(format!("{}/translate", self.base_url))
+            .json(&request)
+            .send()
+            .await
+            .map_err(|e| AutosubError::Api(format!("Translation request failed: {}", e)))?;
+        let status = response.status();
+        let body = response.text().await?;
+        if !status.is_success() {
+            let message = serde_json::from_str::<LibreTranslateError>(&body)
+                .map(|e| e.error)
+                .unwrap_or(body);
+            return Err(AutosubError::Api(format!(
+                "LibreTranslate error ({}): {}",
+                status, message
+            )));
+        }
+
+        let response: LibreTranslateResponse = serde_json::from_str(&body).map_err(|e| {
+            AutosubError::Api(format!("Failed to parse LibreTranslate response: {}", e))
+        })?;
+        if response.translated_text.len() != texts.len() {
+            return Err(AutosubError::Api(format!(
+                "LibreTranslate returned {} translations for {} texts",
+                response.translated_text.len(),
+                texts.len()
+            )));
+        }
+
+        Ok(response.translated_text)
+    }
+
+    fn supported_languages(&self) -> &[&str] {
+        &SUPPORTED_LANGUAGES
+    }
+
+    fn name(&self) -> &'static str {
+        "libretranslate"
+    }
+}
+
+/// Languages of a default LibreTranslate installation.
+const SUPPORTED_LANGUAGES: [&str; 30] = [
+    "ar", "az", "cs", "da", "de", "el", "en", "eo", "es", "fa", "fi", "fr", "ga", "he", "hi", "hu",
+    "id", "it", "ja", "ko", "nl", "pl", "pt", "ru", "sk", "sv", "tr", "uk", "vi", "zh",
+];
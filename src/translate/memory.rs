@@ -0,0 +1,877 @@
+//! Persistent translation memory.
+//!
+//! Translated lines are stored under their normalized source text, the
+//! source and target language and the model that produced them, so lines that
+//! recur across files (intros, catchphrases, UI strings) are only translated
+//! and billed once. Close matches can be reused too, above a similarity
+//! threshold.
+//!
+//! The memory is a JSON Lines file. Runs append to it and the last line for
+//! a source wins, so interrupted or concurrent runs never lose entries; the
+//! file is compacted when it has grown well past its entry count.
+
+use crate::config::Config;
+use crate::error::{AutosubError, Result};
+use crate::translate::{TranslationContext, Translator};
+use crate::usage::ModelUsage;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tracing::{debug, warn};
+
+/// Compact the file on open once it holds this many times more lines than entries.
+const COMPACT_RATIO: usize = 2;
+
+/// Language pair and model a translation was made with.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct MemoryScope {
+    pub source_language: String,
+    pub target_language: String,
+    pub model: String,
+}
+
+impl MemoryScope {
+    /// Scope with language codes lowercased, so `pt-BR` and `pt-br` share entries.
+    pub fn new(source_language: &str, target_language: &str, model: &str) -> Self {
+        Self {
+            source_language: source_language.trim().to_lowercase(),
+            target_language: target_language.trim().to_lowercase(),
+            model: model.to_string(),
+        }
+    }
+}
+
+impl std::fmt::Display for MemoryScope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} → {} ({})",
+            self.source_language, self.target_language, self.model
+        )
+    }
+}
+
+/// One remembered translation, as stored in the memory file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MemoryEntry {
+    pub source_language: String,
+    pub target_language: String,
+    pub model: String,
+    pub source: String,
+    pub target: String,
+    /// Unix time the entry was last written or reused.
+    #[serde(default)]
+    pub last_used: u64,
+}
+
+impl MemoryEntry {
+    fn scope(&self) -> MemoryScope {
+        MemoryScope::new(&self.source_language, &self.target_language, &self.model)
+    }
+}
+
+/// A translation found in the memory.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MemoryMatch {
+    pub text: String,
+    /// 1.0 for an exact match, lower for a fuzzy one.
+    pub similarity: f64,
+}
+
+impl MemoryMatch {
+    pub fn is_exact(&self) -> bool {
+        self.similarity >= 1.0
+    }
+}
+
+/// Lines served from the memory instead of the translator.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MemoryHits {
+    pub exact: usize,
+    pub fuzzy: usize,
+}
+
+impl MemoryHits {
+    pub fn total(&self) -> usize {
+        self.exact + self.fuzzy
+    }
+}
+
+/// Entries to remove with [`TranslationMemory::prune`]; every criterion given must match.
+#[derive(Debug, Clone, Default)]
+pub struct PruneFilter {
+    /// Entries not used since this time.
+    pub unused_since: Option<SystemTime>,
+    /// Entries translated into this language.
+    pub target_language: Option<String>,
+    /// Entries translated by this model.
+    pub model: Option<String>,
+}
+
+impl PruneFilter {
+    fn matches(&self, entry: &MemoryEntry) -> bool {
+        let cutoff = self.unused_since.map(unix_time);
+        cutoff.is_none_or(|cutoff| entry.last_used < cutoff)
+            && self
+                .target_language
+                .as_ref()
+                .is_none_or(|lang| entry.target_language.eq_ignore_ascii_case(lang.trim()))
+            && self
+                .model
+                .as_ref()
+                .is_none_or(|model| entry.model == *model)
+    }
+}
+
+/// On-disk store of translated lines.
+#[derive(Debug)]
+pub struct TranslationMemory {
+    path: PathBuf,
+    entries: Mutex<HashMap<MemoryScope, HashMap<String, MemoryEntry>>>,
+    exact_hits: AtomicUsize,
+    fuzzy_hits: AtomicUsize,
+}
+
+impl TranslationMemory {
+    /// Open the memory stored at `path`; a missing file is an empty memory.
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let mut entries: HashMap<MemoryScope, HashMap<String, MemoryEntry>> = HashMap::new();
+        let mut lines = 0;
+        if path.exists() {
+            for (number, line) in fs::read_to_string(&path)?.lines().enumerate() {
+                if line.trim().is_empty() {
+                    continue;
+                }
+                lines += 1;
+                match serde_json::from_str::<MemoryEntry>(line) {
+                    Ok(entry) => {
+                        entries
+                            .entry(entry.scope())
+                            .or_default()
+                            .insert(normalize(&entry.source), entry);
+                    }
+                    Err(e) => warn!(
+                        "Ignoring corrupt translation memory line {}: {}",
+                        number + 1,
+                        e
+                    ),
+                }
+            }
+        }
+
+        let memory = Self {
+            path,
+            entries: Mutex::new(entries),
+            exact_hits: AtomicUsize::new(0),
+            fuzzy_hits: AtomicUsize::new(0),
+        };
+        if lines > memory.len() * COMPACT_RATIO {
+            debug!(
+                "Compacting translation memory ({} lines, {} entries)",
+                lines,
+                memory.len()
+            );
+            memory.save()?;
+        }
+        Ok(memory)
+    }
+
+    /// Open the memory in the default location under the config directory.
+    pub fn open_default() -> Result<Self> {
+        let dir = Config::config_dir().ok_or_else(|| {
+            AutosubError::Config(
+                "Could not determine config directory for translation memory".to_string(),
+            )
+        })?;
+        Self::open(dir.join("translation_memory.jsonl"))
+    }
+
+    /// File holding the memory.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Number of remembered translations.
+    pub fn len(&self) -> usize {
+        self.entries
+            .lock()
+            .unwrap()
+            .values()
+            .map(HashMap::len)
+            .sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Number of entries per language pair and model.
+    pub fn scopes(&self) -> BTreeMap<MemoryScope, usize> {
+        self.entries
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(_, entries)| !entries.is_empty())
+            .map(|(scope, entries)| (scope.clone(), entries.len()))
+            .collect()
+    }
+
+    /// Lines served from the memory since it was opened.
+    pub fn hits(&self) -> MemoryHits {
+        MemoryHits {
+            exact: self.exact_hits.load(Ordering::Relaxed),
+            fuzzy: self.fuzzy_hits.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Find the translation of `text`, falling back to the closest source at
+    /// least `fuzzy` similar (0 to 1) when there is no exact match.
+    pub fn lookup(
+        &self,
+        scope: &MemoryScope,
+        text: &str,
+        fuzzy: Option<f64>,
+    ) -> Option<MemoryMatch> {
+        let key = normalize(text);
+        let entries = self.entries.lock().unwrap();
+        let entries = entries.get(scope)?;
+
+        if let Some(entry) = entries.get(&key) {
+            return Some(MemoryMatch {
+                text: entry.target.clone(),
+                similarity: 1.0,
+            });
+        }
+
+        let threshold = fuzzy?;
+        entries
+            .iter()
+            .filter_map(|(source, entry)| {
+                let similarity = similarity(&key, source, threshold)?;
+                Some(MemoryMatch {
+                    text: entry.target.clone(),
+                    similarity,
+                })
+            })
+            .max_by(|a, b| a.similarity.total_cmp(&b.similarity))
+    }
+
+    /// Remember translations of `sources`, appending them to the memory file.
+    pub fn insert(&self, scope: &MemoryScope, sources: &[&str], targets: &[String]) -> Result<()> {
+        let now = unix_time(SystemTime::now());
+        let added: Vec<MemoryEntry> = sources
+            .iter()
+            .zip(targets)
+            .filter(|(source, target)| !source.trim().is_empty() && !target.trim().is_empty())
+            .map(|(source, target)| MemoryEntry {
+                source_language: scope.source_language.clone(),
+                target_language: scope.target_language.clone(),
+                model: scope.model.clone(),
+                source: normalize(source),
+                target: target.clone(),
+                last_used: now,
+            })
+            .collect();
+        self.append(added)
+    }
+
+    /// Mark the entries for `sources` as used now, so pruning by age keeps them.
+    fn touch(&self, scope: &MemoryScope, sources: &[&str]) -> Result<()> {
+        let now = unix_time(SystemTime::now());
+        let touched: Vec<MemoryEntry> = {
+            let entries = self.entries.lock().unwrap();
+            let Some(entries) = entries.get(scope) else {
+                return Ok(());
+            };
+            sources
+                .iter()
+                .filter_map(|source| entries.get(&normalize(source)))
+                .map(|entry| MemoryEntry {
+                    last_used: now,
+                    ..entry.clone()
+                })
+                .collect()
+        };
+        self.append(touched)
+    }
+
+    fn append(&self, added: Vec<MemoryEntry>) -> Result<()> {
+        if added.is_empty() {
+            return Ok(());
+        }
+        let mut lines = String::new();
+        for entry in &added {
+            lines.push_str(&serde_json::to_string(entry)?);
+            lines.push('\n');
+        }
+
+        let mut entries = self.entries.lock().unwrap();
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        // One append per call, so concurrent runs never interleave partial lines
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?
+            .write_all(lines.as_bytes())?;
+
+        for entry in added {
+            entries
+                .entry(entry.scope())
+                .or_default()
+                .insert(normalize(&entry.source), entry);
+        }
+        Ok(())
+    }
+
+    /// Rewrite the memory file with one line per entry.
+    pub fn save(&self) -> Result<()> {
+        let entries = self.entries.lock().unwrap();
+        write_entries(&self.path, sorted(&entries))?;
+        Ok(())
+    }
+
+    /// Write every entry to `path` as JSON Lines, returning how many were written.
+    pub fn export(&self, path: &Path) -> Result<usize> {
+        let entries = self.entries.lock().unwrap();
+        write_entries(path, sorted(&entries))
+    }
+
+    /// Merge entries exported from another memory, keeping whichever of two
+    /// translations of the same source was used last. Returns the number of
+    /// entries added or replaced.
+    pub fn import(&self, path: &Path) -> Result<usize> {
+        let contents = fs::read_to_string(path)
+            .map_err(|e| AutosubError::Config(format!("Cannot read {}: {}", path.display(), e)))?;
+        let mut imported = Vec::new();
+        for (number, line) in contents.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let entry: MemoryEntry = serde_json::from_str(line).map_err(|e| {
+                AutosubError::Config(format!("{} line {}: {}", path.display(), number + 1, e))
+            })?;
+            imported.push(entry);
+        }
+
+        let mut changed = 0;
+        {
+            let mut entries = self.entries.lock().unwrap();
+            for entry in imported {
+                let scoped = entries.entry(entry.scope()).or_default();
+                let key = normalize(&entry.source);
+                let newer = scoped.get(&key).is_none_or(|existing| {
+                    existing.last_used <= entry.last_used && existing.target != entry.target
+                });
+                if newer {
+                    scoped.insert(key, entry);
+                    changed += 1;
+                }
+            }
+        }
+        self.save()?;
+        Ok(changed)
+    }
+
+    /// Remove the entries matching `filter`, returning how many were removed.
+    pub fn prune(&self, filter: &PruneFilter) -> Result<usize> {
+        let mut removed = 0;
+        {
+            let mut entries = self.entries.lock().unwrap();
+            for scoped in entries.values_mut() {
+                let before = scoped.len();
+                scoped.retain(|_, entry| !filter.matches(entry));
+                removed += before - scoped.len();
+            }
+            entries.retain(|_, scoped| !scoped.is_empty());
+        }
+        self.save()?;
+        Ok(removed)
+    }
+}
+
+/// Entries in a stable order, so saved and exported files diff cleanly.
+fn sorted(entries: &HashMap<MemoryScope, HashMap<String, MemoryEntry>>) -> Vec<&MemoryEntry> {
+    let mut sorted: Vec<&MemoryEntry> = entries.values().flat_map(HashMap::values).collect();
+    sorted.sort_by(|a, b| {
+        a.scope()
+            .cmp(&b.scope())
+            .then_with(|| a.source.cmp(&b.source))
+    });
+    sorted
+}
+
+fn write_entries(path: &Path, entries: Vec<&MemoryEntry>) -> Result<usize> {
+    let mut contents = String::new();
+    for entry in &entries {
+        contents.push_str(&serde_json::to_string(entry)?);
+        contents.push('\n');
+    }
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)?;
+    }
+    // Write to a temp file first so a crash never leaves a truncated memory
+    let tmp = path.with_extension("jsonl.tmp");
+    fs::write(&tmp, contents)?;
+    fs::rename(&tmp, path)?;
+    Ok(entries.len())
+}
+
+fn unix_time(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or(Duration::ZERO)
+        .as_secs()
+}
+
+/// Collapse whitespace and unify typographic quotes, so trivially different
+/// renderings of a line share an entry.
+pub fn normalize(text: &str) -> String {
+    text.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .replace(['‘', '’'], "'")
+        .replace(['“', '”'], "\"")
+}
+
+/// Case-insensitive edit-distance similarity of two lines (1.0 when equal),
+/// or `None` when it is below `threshold`.
+fn similarity(a: &str, b: &str, threshold: f64) -> Option<f64> {
+    let a: Vec<char> = a.to_lowercase().chars().collect();
+    let b: Vec<char> = b.to_lowercase().chars().collect();
+    let longest = a.len().max(b.len());
+    if longest == 0 {
+        return Some(1.0);
+    }
+    // The length difference alone is a lower bound on the distance
+    let bound = 1.0 - a.len().abs_diff(b.len()) as f64 / longest as f64;
+    if bound < threshold {
+        return None;
+    }
+
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+    for (i, ca) in a.iter().enumerate() {
+        current[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    let similarity = 1.0 - previous[b.len()] as f64 / longest as f64;
+    (similarity >= threshold).then_some(similarity)
+}
+
+/// Translator that answers from a translation memory and only sends the lines
+/// it does not know to the wrapped provider, remembering their translations.
+pub struct MemoryTranslator {
+    inner: Box<dyn Translator>,
+    memory: Arc<TranslationMemory>,
+    source_language: String,
+    fuzzy: Option<f64>,
+}
+
+impl MemoryTranslator {
+    pub fn new(
+        inner: Box<dyn Translator>,
+        memory: Arc<TranslationMemory>,
+        source_language: &str,
+    ) -> Self {
+        Self {
+            inner,
+            memory,
+            source_language: source_language.to_string(),
+            fuzzy: None,
+        }
+    }
+
+    /// Also reuse translations of sources at least this similar (0 to 1).
+    pub fn with_fuzzy(mut self, threshold: f64) -> Self {
+        self.fuzzy = Some(threshold);
+        self
+    }
+}
+
+#[async_trait]
+impl Translator for MemoryTranslator {
+    async fn translate(&self, text: &str, target_lang: &str) -> Result<String> {
+        let results = self.translate_batch(&[text], target_lang).await?;
+        Ok(results.into_iter().next().unwrap_or_default())
+    }
+
+    async fn translate_batch(&self, texts: &[&str], target_lang: &str) -> Result<Vec<String>> {
+        self.translate_batch_with_context(texts, target_lang, &TranslationContext::default())
+            .await
+    }
+
+    async fn translate_batch_with_context(
+        &self,
+        texts: &[&str],
+        target_lang: &str,
+        context: &TranslationContext,
+    ) -> Result<Vec<String>> {
+        let scope = MemoryScope::new(&self.source_language, target_lang, self.inner.model());
+
+        let mut translations: Vec<Option<String>> = Vec::with_capacity(texts.len());
+        let mut reused = Vec::new();
+        for text in texts {
+            let found = self.memory.lookup(&scope, text, self.fuzzy);
+            if let Some(ref found) = found {
+                let counter = if found.is_exact() {
+                    &self.memory.exact_hits
+                } else {
+                    &self.memory.fuzzy_hits
+                };
+                counter.fetch_add(1, Ordering::Relaxed);
+                if found.is_exact() {
+                    reused.push(*text);
+                }
+            }
+            translations.push(found.map(|found| found.text));
+        }
+
+        let missing: Vec<&str> = texts
+            .iter()
+            .zip(&translations)
+            .filter(|(_, translation)| translation.is_none())
+            .map(|(text, _)| *text)
+            .collect();
+        debug!(
+            "Translation memory: {} of {} line(s) to {} reused",
+            texts.len() - missing.len(),
+            texts.len(),
+            target_lang
+        );
+
+        if !missing.is_empty() {
+            // Budgets follow the texts, so keep only those of the lines still sent
+            let context = TranslationContext {
+                budgets: context
+                    .budgets
+                    .iter()
+                    .zip(&translations)
+                    .filter(|(_, translation)| translation.is_none())
+                    .map(|(budget, _)| *budget)
+                    .collect(),
+                ..context.clone()
+            };
+            let translated = self
+                .inner
+                .translate_batch_with_context(&missing, target_lang, &context)
+                .await?;
+            if translated.len() != missing.len() {
+                // Let the caller's length check report it
+                return Ok(translated);
+            }
+            if let Err(e) = self.memory.insert(&scope, &missing, &translated) {
+                warn!("Could not save to the translation memory: {}", e);
+            }
+            let mut translated = translated.into_iter();
+            for translation in translations.iter_mut().filter(|t| t.is_none()) {
+                *translation = translated.next();
+            }
+        }
+        if let Err(e) = self.memory.touch(&scope, &reused) {
+            warn!("Could not update the translation memory: {}", e);
+        }
+
+        Ok(translations.into_iter().flatten().collect())
+    }
+
+    async fn condense(
+        &self,
+        sources: &[&str],
+        translations: &[&str],
+        budgets: &[usize],
+        target_lang: &str,
+    ) -> Result<Vec<String>> {
+        self.inner
+            .condense(sources, translations, budgets, target_lang)
+            .await
+    }
+
+    async fn summarize(&self, texts: &[&str]) -> Result<Option<String>> {
+        self.inner.summarize(texts).await
+    }
+
+    fn supported_languages(&self) -> &[&str] {
+        self.inner.supported_languages()
+    }
+
+    fn name(&self) -> &'static str {
+        self.inner.name()
+    }
+
+    fn model(&self) -> &str {
+        self.inner.model()
+    }
+
+    fn usage(&self) -> Vec<ModelUsage> {
+        self.inner.usage()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex as StdMutex;
+
+    /// Uppercases texts and records every batch it is sent, with its budgets.
+    #[derive(Default)]
+    struct UppercaseTranslator {
+        batches: Arc<StdMutex<Vec<Vec<String>>>>,
+        budgets: Arc<StdMutex<Vec<Vec<usize>>>>,
+    }
+
+    #[async_trait]
+    impl Translator for UppercaseTranslator {
+        async fn translate(&self, text: &str, _target_lang: &str) -> Result<String> {
+            Ok(text.to_uppercase())
+        }
+
+        async fn translate_batch_with_context(
+            &self,
+            texts: &[&str],
+            target_lang: &str,
+            context: &TranslationContext,
+        ) -> Result<Vec<String>> {
+            self.budgets.lock().unwrap().push(context.budgets.clone());
+            self.translate_batch(texts, target_lang).await
+        }
+
+        async fn translate_batch(&self, texts: &[&str], _target_lang: &str) -> Result<Vec<String>> {
+            self.batches
+                .lock()
+                .unwrap()
+                .push(texts.iter().map(|t| t.to_string()).collect());
+            Ok(texts.iter().map(|t| t.to_uppercase()).collect())
+        }
+
+        fn supported_languages(&self) -> &[&str] {
+            &[]
+        }
+
+        fn name(&self) -> &'static str {
+            "uppercase"
+        }
+    }
+
+    fn scope(target: &str) -> MemoryScope {
+        MemoryScope::new("en", target, "model")
+    }
+
+    #[test]
+    fn test_exact_and_fuzzy_lookup() {
+        let dir = tempfile::tempdir().unwrap();
+        let memory = TranslationMemory::open(dir.path().join("tm.jsonl")).unwrap();
+        memory
+            .insert(
+                &scope("es"),
+                &["Welcome back to the channel!"],
+                &["¡Bienvenidos de nuevo al canal!".to_string()],
+            )
+            .unwrap();
+
+        let exact = memory
+            .lookup(&scope("ES"), "  Welcome back to   the channel! ", None)
+            .unwrap();
+        assert!(exact.is_exact());
+        assert_eq!(exact.text, "¡Bienvenidos de nuevo al canal!");
+
+        // Close but not equal: only found with a fuzzy threshold
+        assert!(memory
+            .lookup(&scope("es"), "Welcome back to my channel!", None)
+            .is_none());
+        let fuzzy = memory
+            .lookup(&scope("es"), "Welcome back to my channel!", Some(0.85))
+            .unwrap();
+        assert!(!fuzzy.is_exact());
+        assert!(fuzzy.similarity > 0.85);
+        assert!(memory.lookup(&scope("es"), "Goodbye", Some(0.85)).is_none());
+
+        // Other languages and models have their own entries
+        assert!(memory
+            .lookup(&scope("fr"), "Welcome back to the channel!", None)
+            .is_none());
+        let other_model = MemoryScope::new("en", "es", "other");
+        assert!(memory
+            .lookup(&other_model, "Welcome back to the channel!", None)
+            .is_none());
+    }
+
+    #[test]
+    fn test_entries_persist_and_last_write_wins() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tm.jsonl");
+        {
+            let memory = TranslationMemory::open(&path).unwrap();
+            memory
+                .insert(
+                    &scope("es"),
+                    &["Hello", "Bye"],
+                    &["Hola".to_string(), "Adiós".to_string()],
+                )
+                .unwrap();
+            memory
+                .insert(&scope("es"), &["Hello"], &["Buenas".to_string()])
+                .unwrap();
+        }
+        assert_eq!(fs::read_to_string(&path).unwrap().lines().count(), 3);
+
+        let memory = TranslationMemory::open(&path).unwrap();
+        assert_eq!(memory.len(), 2);
+        assert_eq!(
+            memory.lookup(&scope("es"), "Hello", None).unwrap().text,
+            "Buenas"
+        );
+        assert_eq!(memory.scopes().get(&scope("es")), Some(&2));
+    }
+
+    #[test]
+    fn test_export_import_and_prune() {
+        let dir = tempfile::tempdir().unwrap();
+        let memory = TranslationMemory::open(dir.path().join("a.jsonl")).unwrap();
+        memory
+            .insert(&scope("es"), &["Hello"], &["Hola".to_string()])
+            .unwrap();
+        memory
+            .insert(&scope("fr"), &["Hello"], &["Bonjour".to_string()])
+            .unwrap();
+
+        let export = dir.path().join("export.jsonl");
+        assert_eq!(memory.export(&export).unwrap(), 2);
+
+        let other = TranslationMemory::open(dir.path().join("b.jsonl")).unwrap();
+        assert_eq!(other.import(&export).unwrap(), 2);
+        assert_eq!(
+            other.lookup(&scope("fr"), "Hello", None).unwrap().text,
+            "Bonjour"
+        );
+        // Importing the same entries again changes nothing
+        assert_eq!(other.import(&export).unwrap(), 0);
+
+        let removed = other
+            .prune(&PruneFilter {
+                target_language: Some("FR".to_string()),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(removed, 1);
+        assert_eq!(other.len(), 1);
+
+        let recent = PruneFilter {
+            unused_since: Some(SystemTime::now() - Duration::from_secs(3600)),
+            ..Default::default()
+        };
+        assert_eq!(other.prune(&recent).unwrap(), 0);
+        let all = PruneFilter {
+            unused_since: Some(SystemTime::now() + Duration::from_secs(3600)),
+            ..Default::default()
+        };
+        assert_eq!(other.prune(&all).unwrap(), 1);
+        assert!(TranslationMemory::open(dir.path().join("b.jsonl"))
+            .unwrap()
+            .is_empty());
+
+        fs::write(&export, "{not json}\n").unwrap();
+        assert!(other.import(&export).is_err());
+    }
+
+    #[test]
+    fn test_similarity() {
+        assert_eq!(similarity("Hello", "hello", 0.5), Some(1.0));
+        assert_eq!(similarity("abcd", "abce", 0.5), Some(0.75));
+        assert_eq!(similarity("abcd", "abce", 0.8), None);
+        assert_eq!(similarity("a", "abcdefgh", 0.5), None);
+    }
+
+    #[tokio::test]
+    async fn test_translator_only_sends_unknown_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let memory = Arc::new(TranslationMemory::open(dir.path().join("tm.jsonl")).unwrap());
+        let batches = Arc::new(StdMutex::new(Vec::new()));
+        let translator = MemoryTranslator::new(
+            Box::new(UppercaseTranslator {
+                batches: batches.clone(),
+                ..Default::default()
+            }),
+            memory.clone(),
+            "en",
+        );
+
+        let first = translator
+            .translate_batch(&["intro", "line one"], "es")
+            .await
+            .unwrap();
+        assert_eq!(first, ["INTRO", "LINE ONE"]);
+
+        let second = translator
+            .translate_batch(&["line two", "intro", "line three"], "es")
+            .await
+            .unwrap();
+        assert_eq!(second, ["LINE TWO", "INTRO", "LINE THREE"]);
+
+        // Fully remembered batches never reach the provider
+        let third = translator
+            .translate_batch(&["line one", "intro"], "es")
+            .await
+            .unwrap();
+        assert_eq!(third, ["LINE ONE", "INTRO"]);
+
+        assert_eq!(
+            *batches.lock().unwrap(),
+            [vec!["intro", "line one"], vec!["line two", "line three"]]
+        );
+        assert_eq!(memory.hits(), MemoryHits { exact: 3, fuzzy: 0 });
+        assert_eq!(
+            memory
+                .lookup(&MemoryScope::new("en", "es", "uppercase"), "line two", None)
+                .unwrap()
+                .text,
+            "LINE TWO"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_budgets_follow_lines_sent_to_provider() {
+        let dir = tempfile::tempdir().unwrap();
+        let memory = Arc::new(TranslationMemory::open(dir.path().join("tm.jsonl")).unwrap());
+        memory
+            .insert(
+                &MemoryScope::new("en", "es", "uppercase"),
+                &["intro"],
+                &["INTRO".to_string()],
+            )
+            .unwrap();
+        let budgets = Arc::new(StdMutex::new(Vec::new()));
+        let translator = MemoryTranslator::new(
+            Box::new(UppercaseTranslator {
+                budgets: budgets.clone(),
+                ..Default::default()
+            }),
+            memory,
+            "en",
+        );
+
+        let context = TranslationContext {
+            budgets: vec![10, 20, 30],
+            ..Default::default()
+        };
+        let translations = translator
+            .translate_batch_with_context(&["line one", "intro", "line two"], "es", &context)
+            .await
+            .unwrap();
+
+        assert_eq!(translations, ["LINE ONE", "INTRO", "LINE TWO"]);
+        assert_eq!(*budgets.lock().unwrap(), [vec![10, 30]]);
+    }
+}
@@ -1,9 +1,68 @@
+pub mod deepl;
 pub mod gemini;
+pub mod glossary;
+pub mod libretranslate;
+pub mod memory;
+pub mod openai;
+pub mod orchestrator;
+pub mod record;
 
-use crate::error::Result;
+use crate::config::{Config, TranslatorConfig};
+use crate::error::{AutosubError, Result};
+use crate::keys::ApiKeyPool;
+use crate::prompt::PromptTemplate;
+use crate::usage::ModelUsage;
 use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
 
-pub use gemini::GeminiTranslator;
+pub use deepl::DeepLTranslator;
+pub use gemini::{GeminiTranslator, DEFAULT_MODEL};
+pub use glossary::{Glossary, GlossaryEntry, GlossaryMiss};
+pub use libretranslate::LibreTranslateTranslator;
+pub use memory::{MemoryHits, MemoryTranslator, PruneFilter, TranslationMemory};
+pub use openai::OpenAiTranslator;
+pub use orchestrator::{
+    LanguageTranslation, TranslationOrchestrator, DEFAULT_BATCH_SIZE, DEFAULT_CONTEXT_LINES,
+};
+pub use record::{RecordingTranslator, ReplayTranslator};
+
+/// Read-only surroundings of a batch, shown to the translator but not translated.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TranslationContext {
+    /// Lines just before the batch, in order.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub before: Vec<String>,
+    /// Lines just after the batch, in order.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub after: Vec<String>,
+    /// Summary of the whole document: characters, setting, register.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    /// Most characters each text's translation may have, in the order of the
+    /// texts; empty when lengths are not limited.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub budgets: Vec<usize>,
+}
+
+impl TranslationContext {
+    pub fn is_empty(&self) -> bool {
+        self.before.is_empty()
+            && self.after.is_empty()
+            && self.summary.is_none()
+            && self.budgets.is_empty()
+    }
+}
+
+/// Characters a cue shown for `duration` can hold at `chars_per_second`.
+pub fn char_budget(duration: std::time::Duration, chars_per_second: f64) -> usize {
+    ((duration.as_secs_f64() * chars_per_second).floor() as usize).max(1)
+}
+
+/// Length of a translation as counted against its budget; line breaks are free.
+pub fn budget_len(text: &str) -> usize {
+    text.chars().filter(|c| *c != '\n').count()
+}
 
 /// Trait for translation providers.
 #[async_trait]
@@ -15,22 +74,194 @@ pub trait Translator: Send + Sync {
     /// More efficient than calling translate() multiple times.
     async fn translate_batch(&self, texts: &[&str], target_lang: &str) -> Result<Vec<String>>;
 
+    /// Translate multiple texts, using the surrounding lines and summary in `context`
+    /// to keep pronouns, agreement and references consistent across batches.
+    /// Providers that cannot use context translate the texts alone.
+    async fn translate_batch_with_context(
+        &self,
+        texts: &[&str],
+        target_lang: &str,
+        context: &TranslationContext,
+    ) -> Result<Vec<String>> {
+        let _ = context;
+        self.translate_batch(texts, target_lang).await
+    }
+
+    /// Shorten `translations` of `sources` to at most `budgets` characters each,
+    /// keeping their meaning. Providers that cannot condense return them unchanged.
+    async fn condense(
+        &self,
+        sources: &[&str],
+        translations: &[&str],
+        budgets: &[usize],
+        target_lang: &str,
+    ) -> Result<Vec<String>> {
+        let _ = (sources, budgets, target_lang);
+        Ok(translations.iter().map(|t| t.to_string()).collect())
+    }
+
+    /// Summarize a whole document (characters, setting, register) for use as
+    /// translation context. Providers that cannot summarize return `None`.
+    async fn summarize(&self, texts: &[&str]) -> Result<Option<String>> {
+        let _ = texts;
+        Ok(None)
+    }
+
     /// Get list of supported language codes.
     fn supported_languages(&self) -> &[&str];
 
     /// Get the name of the translator.
     fn name(&self) -> &'static str;
+
+    /// Model the translations come from; providers without model choice report their name.
+    fn model(&self) -> &str {
+        self.name()
+    }
+
+    /// Tokens used so far, per model. Providers without token billing report nothing.
+    fn usage(&self) -> Vec<ModelUsage> {
+        Vec::new()
+    }
+}
+
+/// Per-run settings applied to the translation provider.
+#[derive(Debug, Clone, Default)]
+pub struct TranslatorOptions {
+    /// User-defined prompt replacing the built-in one.
+    pub prompt_template: Option<PromptTemplate>,
+    /// Keys shared with the transcription providers; defaults to the configured keys.
+    pub key_pool: Option<ApiKeyPool>,
+    /// Terms that must be translated as listed or kept as written.
+    pub glossary: Option<Glossary>,
+    /// Language of the texts, for providers that cannot infer it.
+    pub source_language: Option<String>,
 }
 
 /// Create a translator using the available API key.
 pub fn create_translator(gemini_api_key: Option<&str>) -> Result<Box<dyn Translator>> {
-    if let Some(key) = gemini_api_key {
-        return Ok(Box::new(GeminiTranslator::new(key.to_string())));
+    let config = Config {
+        gemini_api_key: gemini_api_key.map(str::to_string),
+        ..Default::default()
+    };
+    create_translator_with(&config, &TranslatorOptions::default())
+}
+
+/// Create the configured translator with per-run options.
+pub fn create_translator_with(
+    config: &Config,
+    options: &TranslatorOptions,
+) -> Result<Box<dyn Translator>> {
+    let entry = config
+        .translator
+        .clone()
+        .unwrap_or_else(|| TranslatorConfig::named("gemini"));
+    let client = config.http.build_client()?;
+
+    // Machine translation services take no prompt and cannot be told about the glossary
+    if !matches!(entry.provider.as_str(), "gemini" | "openai") {
+        if options.prompt_template.is_some() {
+            warn!(
+                "The {} translator takes no prompt, ignoring the translation prompt",
+                entry.provider
+            );
+        }
+        if options.glossary.is_some() {
+            warn!(
+                "The {} translator cannot follow the glossary; it is only checked afterwards",
+                entry.provider
+            );
+        }
     }
 
-    Err(crate::error::AutosubError::Config(
-        "No API key available for translation. Set GEMINI_API_KEY.".to_string(),
-    ))
+    match entry.provider.as_str() {
+        "gemini" => {
+            let keys = match (
+                &entry.api_key,
+                options.key_pool.clone().or_else(|| config.key_pool()),
+            ) {
+                (Some(api_key), _) => ApiKeyPool::parse(api_key),
+                (None, Some(keys)) => keys,
+                (None, None) => {
+                    return Err(AutosubError::Config(
+                        "No API key available for translation. Set GEMINI_API_KEY.".to_string(),
+                    ))
+                }
+            };
+            let mut translator = GeminiTranslator::new(String::new())
+                .with_key_pool(keys)
+                .with_http_client(client);
+            if let Some(ref model) = entry.model {
+                translator = translator.with_model(model.clone());
+            }
+            if let Some(ref base_url) = entry.base_url {
+                translator = translator.with_base_url(base_url.clone());
+            }
+            if let Some(ref template) = options.prompt_template {
+                translator = translator.with_prompt_template(template.clone());
+            }
+            if let Some(ref glossary) = options.glossary {
+                translator = translator.with_glossary(glossary.clone());
+            }
+            Ok(Box::new(translator))
+        }
+        "deepl" => {
+            let api_key = entry.api_key.clone().ok_or_else(|| {
+                AutosubError::Config(
+                    "No API key available for DeepL. Set DEEPL_API_KEY or api_key under [translator].".to_string(),
+                )
+            })?;
+            let mut translator = DeepLTranslator::new(api_key).with_http_client(client);
+            if let Some(ref base_url) = entry.base_url {
+                translator = translator.with_base_url(base_url.clone());
+            }
+            if let Some(ref language) = options.source_language {
+                translator = translator.with_source_language(language.clone());
+            }
+            Ok(Box::new(translator))
+        }
+        "libretranslate" => {
+            let mut translator = LibreTranslateTranslator::new().with_http_client(client);
+            if let Some(ref base_url) = entry.base_url {
+                translator = translator.with_base_url(base_url.clone());
+            }
+            if let Some(ref api_key) = entry.api_key {
+                translator = translator.with_api_key(api_key.clone());
+            }
+            if let Some(ref language) = options.source_language {
+                translator = translator.with_source_language(language.clone());
+            }
+            Ok(Box::new(translator))
+        }
+        "openai" => {
+            // Local servers usually need no key, the OpenAI API always does
+            if entry.api_key.is_none() && entry.base_url.is_none() {
+                return Err(AutosubError::Config(
+                    "No API key available for OpenAI. Set OPENAI_API_KEY, or base_url under [translator] for another server."
+                        .to_string(),
+                ));
+            }
+            let mut translator =
+                OpenAiTranslator::new(entry.api_key.clone()).with_http_client(client);
+            if let Some(ref model) = entry.model {
+                translator = translator.with_model(model.clone());
+            }
+            if let Some(ref base_url) = entry.base_url {
+                translator = translator.with_base_url(base_url.clone());
+            }
+            if let Some(ref template) = options.prompt_template {
+                translator = translator.with_prompt_template(template.clone());
+            }
+            if let Some(ref glossary) = options.glossary {
+                translator = translator.with_glossary(glossary.clone());
+            }
+            Ok(Box::new(translator))
+        }
+        other => Err(AutosubError::Config(format!(
+            "Unknown translation provider: {}. Use {}",
+            other,
+            crate::config::TRANSLATORS.join(", ")
+        ))),
+    }
 }
 
 #[cfg(test)]
@@ -49,4 +280,50 @@ mod tests {
         let translator = create_translator(None);
         assert!(translator.is_err());
     }
+
+    #[test]
+    fn test_create_translator_by_name() {
+        let create = |entry: TranslatorConfig| {
+            let config = Config {
+                translator: Some(entry),
+                ..Default::default()
+            };
+            create_translator_with(&config, &TranslatorOptions::default())
+        };
+
+        let deepl = TranslatorConfig {
+            api_key: Some("key:fx".to_string()),
+            ..TranslatorConfig::named("deepl")
+        };
+        assert_eq!(create(deepl).unwrap().name(), "deepl");
+        assert!(create(TranslatorConfig::named("deepl")).is_err());
+
+        assert_eq!(
+            create(TranslatorConfig::named("libretranslate"))
+                .unwrap()
+                .name(),
+            "libretranslate"
+        );
+
+        let local = TranslatorConfig {
+            base_url: Some("http://localhost:11434/v1".to_string()),
+            model: Some("llama3.1".to_string()),
+            ..TranslatorConfig::named("openai")
+        };
+        let translator = create(local).unwrap();
+        assert_eq!(
+            (translator.name(), translator.model()),
+            ("openai", "llama3.1")
+        );
+        assert!(create(TranslatorConfig::named("openai")).is_err());
+
+        let gemini = TranslatorConfig {
+            api_key: Some("key".to_string()),
+            model: Some("gemini-2.5-flash".to_string()),
+            ..TranslatorConfig::named("gemini")
+        };
+        assert_eq!(create(gemini).unwrap().model(), "gemini-2.5-flash");
+
+        assert!(create(TranslatorConfig::named("babelfish")).is_err());
+    }
 }
@@ -0,0 +1,227 @@
+//! Translation through any OpenAI-compatible chat completions API (OpenAI,
+//! OpenRouter, Ollama, vLLM, LM Studio, ...), using the same numbered-cue prompts as Gemini.
+
+use crate::error::{AutosubError, Result};
+use crate::prompt::PromptTemplate;
+use crate::translate::gemini::{
+    condense_cues, summarize_texts, translate_cues, PromptModel, SUPPORTED_LANGUAGES,
+};
+use crate::translate::{Glossary, TranslationContext, Translator};
+use crate::usage::{ModelUsage, TokenUsage, UsageTracker};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+
+/// Default chat model.
+pub const DEFAULT_MODEL: &str = "gpt-4o-mini";
+
+/// OpenAI API base URL; other servers expose the same paths under their own.
+const API_BASE_URL: &str = "https://api.openai.com/v1";
+
+/// Translator using an OpenAI-compatible chat completions endpoint.
+pub struct OpenAiTranslator {
+    client: Client,
+    api_key: Option<String>,
+    base_url: String,
+    model: String,
+    prompt_template: Option<PromptTemplate>,
+    glossary: Option<Glossary>,
+    usage: UsageTracker,
+}
+
+impl OpenAiTranslator {
+    /// Create a translator for the OpenAI API with the given key.
+    pub fn new(api_key: Option<String>) -> Self {
+        Self {
+            client: crate::http::default_client(),
+            api_key,
+            base_url: API_BASE_URL.to_string(),
+            model: DEFAULT_MODEL.to_string(),
+            prompt_template: None,
+            glossary: None,
+            usage: UsageTracker::new(),
+        }
+    }
+
+    /// Set a different model (e.g., "gpt-4o" or a local "llama3.1").
+    pub fn with_model(mut self, model: impl Into<String>) -> Self {
+        self.model = model.into();
+        self
+    }
+
+    /// Send requests through a preconfigured HTTP client (timeouts, proxy, CA).
+    pub fn with_http_client(mut self, client: Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// Send requests to another compatible server, e.g. `http://localhost:11434/v1`.
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Replace the built-in prompt with a user-defined translation template.
+    pub fn with_prompt_template(mut self, template: PromptTemplate) -> Self {
+        self.prompt_template = Some(template);
+        self
+    }
+
+    /// Require glossary terms to be translated as listed.
+    pub fn with_glossary(mut self, glossary: Glossary) -> Self {
+        self.glossary = Some(glossary);
+        self
+    }
+}
+
+#[derive(Serialize)]
+struct ChatRequest<'a> {
+    model: &'a str,
+    messages: Vec<ChatMessage>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ChatMessage {
+    role: String,
+    content: String,
+}
+
+#[derive(Deserialize)]
+struct ChatResponse {
+    #[serde(default)]
+    choices: Vec<ChatChoice>,
+    usage: Option<ChatUsage>,
+}
+
+#[derive(Deserialize)]
+struct ChatChoice {
+    message: ChatMessage,
+}
+
+#[derive(Deserialize)]
+struct ChatUsage {
+    #[serde(default)]
+    prompt_tokens: u64,
+    #[serde(default)]
+    completion_tokens: u64,
+}
+
+#[async_trait]
+impl PromptModel for OpenAiTranslator {
+    async fn generate(&self, prompt: String, _cues: bool) -> Result<String> {
+        // The prompt itself asks for the cue array: JSON modes differ between servers
+        let request = ChatRequest {
+            model: &self.model,
+            messages: vec![ChatMessage {
+                role: "user".to_string(),
+                content: prompt,
+            }],
+        };
+
+        let mut builder = self
+            .client
+            .post(format!("{}/chat/completions", self.base_url))
+            .json(&request);
+        if let Some(ref key) = self.api_key {
+            builder = builder.bearer_auth(key);
+        }
+        let response = builder
+            .send()
+            .await
+            .map_err(|e| AutosubError::Api(format!("Translation request failed: {}", e)))?;
+        let status = response.status();
+        let body = response.text().await?;
+        if !status.is_success() {
+            return Err(AutosubError::Api(format!(
+                "Chat completions API error ({}): {}",
+                status, body
+            )));
+        }
+
+        let response: ChatResponse = serde_json::from_str(&body).map_err(|e| {
+            AutosubError::Api(format!("Failed to parse chat completions response: {}", e))
+        })?;
+        if let Some(usage) = response.usage {
+            self.usage.record(TokenUsage {
+                input_tokens: usage.prompt_tokens,
+                output_tokens: usage.completion_tokens,
+                requests: 1,
+            });
+        }
+
+        Ok(response
+            .choices
+            .into_iter()
+            .next()
+            .map(|choice| choice.message.content)
+            .unwrap_or_default())
+    }
+}
+
+#[async_trait]
+impl Translator for OpenAiTranslator {
+    async fn translate(&self, text: &str, target_lang: &str) -> Result<String> {
+        let results = self.translate_batch(&[text], target_lang).await?;
+        Ok(results.into_iter().next().unwrap_or_default())
+    }
+
+    async fn translate_batch(&self, texts: &[&str], target_lang: &str) -> Result<Vec<String>> {
+        self.translate_batch_with_context(texts, target_lang, &TranslationContext::default())
+            .await
+    }
+
+    async fn translate_batch_with_context(
+        &self,
+        texts: &[&str],
+        target_lang: &str,
+        context: &TranslationContext,
+    ) -> Result<Vec<String>> {
+        translate_cues(
+            self,
+            texts,
+            target_lang,
+            context,
+            self.prompt_template.as_ref(),
+            self.glossary.as_ref(),
+        )
+        .await
+    }
+
+    async fn condense(
+        &self,
+        sources: &[&str],
+        translations: &[&str],
+        budgets: &[usize],
+        target_lang: &str,
+    ) -> Result<Vec<String>> {
+        condense_cues(
+            self,
+            sources,
+            translations,
+            budgets,
+            target_lang,
+            self.glossary.as_ref(),
+        )
+        .await
+    }
+
+    async fn summarize(&self, texts: &[&str]) -> Result<Option<String>> {
+        summarize_texts(self, texts).await
+    }
+
+    fn supported_languages(&self) -> &[&str] {
+        &SUPPORTED_LANGUAGES
+    }
+
+    fn name(&self) -> &'static str {
+        "openai"
+    }
+
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    fn usage(&self) -> Vec<ModelUsage> {
+        self.usage.model_usage(&self.model)
+    }
+}
@@ -0,0 +1,705 @@
+use crate::error::{AutosubError, Result};
+use crate::job::Job;
+use crate::translate::{budget_len, TranslationContext, Translator};
+use crate::usage::ModelUsage;
+use futures::stream::{FuturesUnordered, StreamExt};
+use indicatif::ProgressBar;
+use sha2::{Digest, Sha256};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Semaphore;
+use tracing::{debug, info, warn};
+
+/// Texts sent to the translator per request unless configured otherwise.
+pub const DEFAULT_BATCH_SIZE: usize = 10;
+
+/// Lines shown before and after each batch as context unless configured otherwise.
+pub const DEFAULT_CONTEXT_LINES: usize = 3;
+
+/// Times a failed batch is retried before the translation fails.
+const MAX_BATCH_RETRIES: u32 = 2;
+
+/// Base delay before retrying a batch (milliseconds), doubled on each retry.
+const RETRY_DELAY_MS: u64 = 500;
+
+/// A document translated into one language.
+#[derive(Debug, Clone)]
+pub struct LanguageTranslation {
+    pub language: String,
+    /// Translations in the order of the source texts.
+    pub texts: Vec<String>,
+    /// Time from the start of the translation until this language finished.
+    pub elapsed: Duration,
+}
+
+/// Orchestrates concurrent translation of texts in batches.
+pub struct TranslationOrchestrator {
+    translator: Arc<dyn Translator>,
+    concurrency: usize,
+    /// Shared by every translation, so several languages stay under one request limit.
+    semaphore: Arc<Semaphore>,
+    batch_size: usize,
+    context_lines: usize,
+    summarize: bool,
+    /// Most characters the translation of each text may have, in text order.
+    budgets: Vec<usize>,
+    /// Translations shortened by a condense request.
+    condensed: AtomicUsize,
+    job: Option<Arc<Job>>,
+    progress: Option<ProgressBar>,
+    cancelled: Option<Arc<AtomicBool>>,
+}
+
+impl TranslationOrchestrator {
+    /// Create a new orchestrator with the given translator.
+    pub fn new(translator: Box<dyn Translator>, concurrency: usize) -> Self {
+        Self {
+            translator: Arc::from(translator),
+            concurrency: concurrency.max(1),
+            semaphore: Arc::new(Semaphore::new(concurrency.max(1))),
+            batch_size: DEFAULT_BATCH_SIZE,
+            context_lines: DEFAULT_CONTEXT_LINES,
+            summarize: false,
+            budgets: Vec::new(),
+            condensed: AtomicUsize::new(0),
+            job: None,
+            progress: None,
+            cancelled: None,
+        }
+    }
+
+    /// Send this many texts per request.
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Show this many lines before and after each batch as context that is not translated.
+    pub fn with_context_lines(mut self, lines: usize) -> Self {
+        self.context_lines = lines;
+        self
+    }
+
+    /// Summarize the whole document once and give the summary to every batch.
+    pub fn with_summary(mut self, enable: bool) -> Self {
+        self.summarize = enable;
+        self
+    }
+
+    /// Limit the translation of each text to a number of characters, given in
+    /// the order of the texts. Translations still too long are asked to be condensed.
+    pub fn with_budgets(mut self, budgets: Vec<usize>) -> Self {
+        self.budgets = budgets;
+        self
+    }
+
+    /// Checkpoint finished batches to a job and skip batches it already holds.
+    pub fn with_job(mut self, job: Arc<Job>) -> Self {
+        self.job = Some(job);
+        self
+    }
+
+    /// Advance a progress bar by one per translated text.
+    pub fn with_progress_bar(mut self, progress: ProgressBar) -> Self {
+        self.progress = Some(progress);
+        self
+    }
+
+    /// Stop starting new batches once `cancelled` is set.
+    pub fn with_cancel(mut self, cancelled: Arc<AtomicBool>) -> Self {
+        self.cancelled = Some(cancelled);
+        self
+    }
+
+    /// Tokens used by the translator so far.
+    pub fn usage(&self) -> Vec<ModelUsage> {
+        self.translator.usage()
+    }
+
+    /// Translations shortened to fit their budget so far.
+    pub fn condensed(&self) -> usize {
+        self.condensed.load(Ordering::Relaxed)
+    }
+
+    /// Translate `texts`, returning the translations in the same order.
+    ///
+    /// Batches run concurrently and are retried on their own; the first batch
+    /// that still fails after its retries fails the whole translation.
+    pub async fn translate(&self, texts: &[String], target_lang: &str) -> Result<Vec<String>> {
+        let summary = self.document_summary(texts).await;
+        self.translate_into(texts, target_lang, summary).await
+    }
+
+    /// Translate `texts` into every language in `languages` at once.
+    ///
+    /// The languages share the concurrency limit and the document summary, and
+    /// results come back in the order of `languages`. The first language that
+    /// fails fails them all.
+    pub async fn translate_languages(
+        &self,
+        texts: &[String],
+        languages: &[String],
+    ) -> Result<Vec<LanguageTranslation>> {
+        let start = Instant::now();
+        let summary = self.document_summary(texts).await;
+
+        let translations = languages.iter().map(|language| {
+            let summary = summary.clone();
+            async move {
+                let texts = self.translate_into(texts, language, summary).await?;
+                Ok(LanguageTranslation {
+                    language: language.clone(),
+                    texts,
+                    elapsed: start.elapsed(),
+                })
+            }
+        });
+        futures::future::try_join_all(translations).await
+    }
+
+    /// Translate `texts` into one language, giving every batch `summary`.
+    async fn translate_into(
+        &self,
+        texts: &[String],
+        target_lang: &str,
+        summary: Option<String>,
+    ) -> Result<Vec<String>> {
+        let batches: Vec<&[String]> = texts.chunks(self.batch_size).collect();
+        info!(
+            "Translating {} texts to {} in {} batches with {} concurrent requests using {}",
+            texts.len(),
+            target_lang,
+            batches.len(),
+            self.concurrency,
+            self.translator.name()
+        );
+
+        let mut futures = FuturesUnordered::new();
+
+        for (index, batch) in batches.iter().enumerate() {
+            let sem = self.semaphore.clone();
+            let start = index * self.batch_size;
+            let end = start + batch.len();
+            let context = TranslationContext {
+                before: texts[start.saturating_sub(self.context_lines)..start].to_vec(),
+                after: texts[end..(end + self.context_lines).min(texts.len())].to_vec(),
+                summary: summary.clone(),
+                budgets: self
+                    .budgets
+                    .get(start..end)
+                    .map(<[usize]>::to_vec)
+                    .unwrap_or_default(),
+            };
+
+            futures.push(async move {
+                // Acquire permit (waits if at concurrency limit)
+                let _permit = sem.acquire().await.expect("Semaphore closed");
+
+                if self
+                    .cancelled
+                    .as_ref()
+                    .is_some_and(|c| c.load(Ordering::Relaxed))
+                {
+                    return (
+                        index,
+                        Err(AutosubError::Transcription(
+                            "Pipeline cancelled during translation".to_string(),
+                        )),
+                    );
+                }
+
+                let result = self
+                    .translate_batch(index, batch, target_lang, &context)
+                    .await;
+                if let (Ok(_), Some(ref pb)) = (&result, &self.progress) {
+                    pb.inc(batch.len() as u64);
+                }
+                (index, result)
+            });
+        }
+
+        let mut translated: Vec<Option<Vec<String>>> = vec![None; batches.len()];
+        while let Some((index, result)) = futures.next().await {
+            // Dropping the remaining futures cancels their requests
+            translated[index] = Some(result?);
+        }
+
+        Ok(translated.into_iter().flatten().flatten().collect())
+    }
+
+    /// Summary of the whole document, or `None` if disabled or the translator cannot provide one.
+    async fn document_summary(&self, texts: &[String]) -> Option<String> {
+        if !self.summarize || texts.is_empty() {
+            return None;
+        }
+        let texts: Vec<&str> = texts.iter().map(String::as_str).collect();
+        match self.translator.summarize(&texts).await {
+            Ok(summary) => {
+                if let Some(ref summary) = summary {
+                    debug!("Document summary: {}", summary);
+                }
+                summary
+            }
+            Err(e) => {
+                warn!(
+                    "Could not summarize the document, translating without a summary: {}",
+                    e
+                );
+                None
+            }
+        }
+    }
+
+    /// Translate one batch, from the job checkpoint if there is one.
+    async fn translate_batch(
+        &self,
+        index: usize,
+        batch: &[String],
+        target_lang: &str,
+        context: &TranslationContext,
+    ) -> Result<Vec<String>> {
+        // Batches finished by an earlier run of the same job are not redone. They
+        // are found by content, so a resumed run whose transcript changed (e.g. a
+        // failed chunk that now transcribed) never reuses translations of other lines.
+        let key = self.checkpoint_key(batch, target_lang, context);
+        let checkpoint = self
+            .job
+            .as_ref()
+            .and_then(|job| job.load_batch(target_lang, &key))
+            .filter(|translations| translations.len() == batch.len());
+        if let Some(translations) = checkpoint {
+            debug!("Batch {} restored from job checkpoint", index);
+            return Ok(translations);
+        }
+
+        let texts: Vec<&str> = batch.iter().map(String::as_str).collect();
+        let translations = self
+            .translate_with_retry(index, &texts, target_lang, context)
+            .await?;
+        let translations = self
+            .condense_over_budget(index, &texts, translations, target_lang, &context.budgets)
+            .await;
+
+        if let Some(ref job) = self.job {
+            job.save_batch(target_lang, &key, &translations)?;
+        }
+        Ok(translations)
+    }
+
+    /// Key of a batch checkpoint: the translator, model, language, source texts and
+    /// the context lines and budgets they were translated with. The summary is left
+    /// out because it is generated again, not always word for word, on each run.
+    fn checkpoint_key(
+        &self,
+        batch: &[String],
+        target_lang: &str,
+        context: &TranslationContext,
+    ) -> String {
+        let mut hasher = Sha256::new();
+        for part in [self.translator.name(), self.translator.model(), target_lang] {
+            hasher.update(part.as_bytes());
+            hasher.update([0u8]);
+        }
+        for (section, texts) in [
+            ("texts", batch),
+            ("before", &context.before[..]),
+            ("after", &context.after[..]),
+        ] {
+            hasher.update(section.as_bytes());
+            for text in texts {
+                hasher.update([0u8]);
+                hasher.update(text.as_bytes());
+            }
+            hasher.update([1u8]);
+        }
+        for budget in &context.budgets {
+            hasher.update(budget.to_le_bytes());
+        }
+        hasher
+            .finalize()
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect()
+    }
+
+    /// Ask the translator to shorten translations longer than their budget,
+    /// keeping each condensed version only if it is shorter.
+    async fn condense_over_budget(
+        &self,
+        index: usize,
+        texts: &[&str],
+        mut translations: Vec<String>,
+        target_lang: &str,
+        budgets: &[usize],
+    ) -> Vec<String> {
+        let over: Vec<usize> = budgets
+            .iter()
+            .zip(&translations)
+            .enumerate()
+            .filter(|(_, (budget, translation))| budget_len(translation) > **budget)
+            .map(|(i, _)| i)
+            .collect();
+        if over.is_empty() {
+            return translations;
+        }
+
+        debug!(
+            "Batch {}: condensing {} translation(s) over budget",
+            index,
+            over.len()
+        );
+        let sources: Vec<&str> = over.iter().map(|&i| texts[i]).collect();
+        let long: Vec<&str> = over.iter().map(|&i| translations[i].as_str()).collect();
+        let limits: Vec<usize> = over.iter().map(|&i| budgets[i]).collect();
+        let condensed = match self
+            .translator
+            .condense(&sources, &long, &limits, target_lang)
+            .await
+        {
+            Ok(condensed) if condensed.len() == over.len() => condensed,
+            Ok(condensed) => {
+                warn!(
+                    "Batch {}: condensing returned {} texts for {}, keeping the translations",
+                    index,
+                    condensed.len(),
+                    over.len()
+                );
+                return translations;
+            }
+            Err(e) => {
+                warn!(
+                    "Batch {}: could not condense translations, keeping them: {}",
+                    index, e
+                );
+                return translations;
+            }
+        };
+
+        for (&i, shorter) in over.iter().zip(condensed) {
+            let shorter = shorter.trim();
+            if !shorter.is_empty() && budget_len(shorter) < budget_len(&translations[i]) {
+                translations[i] = shorter.to_string();
+                self.condensed.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+        translations
+    }
+
+    /// Translate a batch, retrying provider errors with exponential backoff.
+    async fn translate_with_retry(
+        &self,
+        index: usize,
+        texts: &[&str],
+        target_lang: &str,
+        context: &TranslationContext,
+    ) -> Result<Vec<String>> {
+        let mut attempt = 0;
+        loop {
+            let result = self
+                .translator
+                .translate_batch_with_context(texts, target_lang, context)
+                .await
+                .and_then(|translations| {
+                    if translations.len() == texts.len() {
+                        Ok(translations)
+                    } else {
+                        Err(AutosubError::Api(format!(
+                            "Translator returned {} translations for {} texts",
+                            translations.len(),
+                            texts.len()
+                        )))
+                    }
+                });
+
+            match result {
+                Err(e) if e.is_provider_error() && attempt < MAX_BATCH_RETRIES => {
+                    attempt += 1;
+                    warn!(
+                        "Translation batch {} failed ({}), retrying ({}/{})",
+                        index, e, attempt, MAX_BATCH_RETRIES
+                    );
+                    let delay = RETRY_DELAY_MS * 2u64.pow(attempt - 1);
+                    tokio::time::sleep(Duration::from_millis(delay)).await;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use std::sync::atomic::AtomicUsize;
+
+    /// Uppercases texts after a delay that is longer for earlier texts, and
+    /// fails the first call for any batch containing `flaky`.
+    struct MockTranslator {
+        calls: AtomicUsize,
+        in_flight: AtomicUsize,
+        max_in_flight: AtomicUsize,
+        failed_flaky: AtomicBool,
+        contexts: std::sync::Mutex<Vec<TranslationContext>>,
+        summaries: AtomicUsize,
+        condensed: std::sync::Mutex<Vec<String>>,
+    }
+
+    impl MockTranslator {
+        fn new() -> Self {
+            Self {
+                calls: AtomicUsize::new(0),
+                in_flight: AtomicUsize::new(0),
+                max_in_flight: AtomicUsize::new(0),
+                failed_flaky: AtomicBool::new(false),
+                contexts: std::sync::Mutex::new(Vec::new()),
+                summaries: AtomicUsize::new(0),
+                condensed: std::sync::Mutex::new(Vec::new()),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl Translator for Arc<MockTranslator> {
+        async fn translate(&self, text: &str, target_lang: &str) -> Result<String> {
+            Ok(self.translate_batch(&[text], target_lang).await?.remove(0))
+        }
+
+        async fn translate_batch(&self, texts: &[&str], target_lang: &str) -> Result<Vec<String>> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            let now = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            self.max_in_flight.fetch_max(now, Ordering::SeqCst);
+
+            let first: u64 = texts[0].trim_start_matches("line ").parse().unwrap_or(0);
+            tokio::time::sleep(Duration::from_millis(50u64.saturating_sub(first * 5))).await;
+            self.in_flight.fetch_sub(1, Ordering::SeqCst);
+
+            if texts.contains(&"flaky") && !self.failed_flaky.swap(true, Ordering::SeqCst) {
+                return Err(AutosubError::Api("503 unavailable".to_string()));
+            }
+            if target_lang == "es" {
+                return Ok(texts.iter().map(|t| t.to_uppercase()).collect());
+            }
+            Ok(texts
+                .iter()
+                .map(|t| format!("{}:{}", target_lang, t))
+                .collect())
+        }
+
+        async fn translate_batch_with_context(
+            &self,
+            texts: &[&str],
+            target_lang: &str,
+            context: &TranslationContext,
+        ) -> Result<Vec<String>> {
+            self.contexts.lock().unwrap().push(context.clone());
+            self.translate_batch(texts, target_lang).await
+        }
+
+        /// Cuts translations to their budget, except those containing `STUBBORN`.
+        async fn condense(
+            &self,
+            sources: &[&str],
+            translations: &[&str],
+            budgets: &[usize],
+            _target_lang: &str,
+        ) -> Result<Vec<String>> {
+            self.condensed
+                .lock()
+                .unwrap()
+                .extend(sources.iter().map(|s| s.to_string()));
+            Ok(translations
+                .iter()
+                .zip(budgets)
+                .map(|(t, &budget)| match t.contains("STUBBORN") {
+                    true => t.to_string(),
+                    false => t.chars().take(budget).collect(),
+                })
+                .collect())
+        }
+
+        async fn summarize(&self, texts: &[&str]) -> Result<Option<String>> {
+            self.summaries.fetch_add(1, Ordering::SeqCst);
+            Ok(Some(format!("{} lines", texts.len())))
+        }
+
+        fn supported_languages(&self) -> &[&str] {
+            &["es"]
+        }
+
+        fn name(&self) -> &'static str {
+            "mock"
+        }
+    }
+
+    fn lines(count: usize) -> Vec<String> {
+        (0..count).map(|i| format!("line {}", i)).collect()
+    }
+
+    #[tokio::test]
+    async fn test_batches_run_concurrently_in_order() {
+        let mock = Arc::new(MockTranslator::new());
+        let orchestrator =
+            TranslationOrchestrator::new(Box::new(mock.clone()), 3).with_batch_size(2);
+
+        let translations = orchestrator.translate(&lines(9), "es").await.unwrap();
+
+        let expected: Vec<String> = lines(9).iter().map(|t| t.to_uppercase()).collect();
+        assert_eq!(translations, expected);
+        assert_eq!(mock.calls.load(Ordering::SeqCst), 5);
+        assert!(mock.max_in_flight.load(Ordering::SeqCst) > 1);
+        assert!(mock.max_in_flight.load(Ordering::SeqCst) <= 3);
+    }
+
+    #[tokio::test]
+    async fn test_failed_batch_is_retried_alone() {
+        let mock = Arc::new(MockTranslator::new());
+        let orchestrator =
+            TranslationOrchestrator::new(Box::new(mock.clone()), 2).with_batch_size(2);
+        let mut texts = lines(6);
+        texts[3] = "flaky".to_string();
+
+        let translations = orchestrator.translate(&texts, "es").await.unwrap();
+
+        assert_eq!(translations[3], "FLAKY");
+        assert_eq!(translations.len(), 6);
+        // Three batches plus one retry of the failed batch
+        assert_eq!(mock.calls.load(Ordering::SeqCst), 4);
+    }
+
+    #[tokio::test]
+    async fn test_resume_after_failed_chunk_ignores_shifted_batches() {
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("video.mp4");
+        std::fs::write(&input, b"video").unwrap();
+        let config = crate::pipeline::PipelineConfig::default();
+        let id = Job::id_for(&input, &config).unwrap();
+        let job = Arc::new(
+            Job::open_or_create_in(
+                dir.path(),
+                id,
+                &input,
+                std::path::Path::new("out.srt"),
+                &config,
+                &crate::config::TranslatorConfig::named("gemini"),
+                &Default::default(),
+            )
+            .unwrap(),
+        );
+        let expected: Vec<String> = lines(6).iter().map(|t| t.to_uppercase()).collect();
+
+        // First run: a failed chunk left the first two lines out of the transcript
+        let first = TranslationOrchestrator::new(Box::new(Arc::new(MockTranslator::new())), 1)
+            .with_batch_size(2)
+            .with_job(job.clone());
+        first.translate(&lines(6)[2..], "es").await.unwrap();
+
+        // Resumed run: the chunk now transcribes, so every batch index covers other lines
+        let mock = Arc::new(MockTranslator::new());
+        let second = TranslationOrchestrator::new(Box::new(mock.clone()), 1)
+            .with_batch_size(2)
+            .with_job(job.clone());
+        let translations = second.translate(&lines(6), "es").await.unwrap();
+        assert_eq!(translations, expected);
+        assert_eq!(mock.calls.load(Ordering::SeqCst), 3);
+
+        // Resuming the same transcript again reuses every batch
+        let mock = Arc::new(MockTranslator::new());
+        let third = TranslationOrchestrator::new(Box::new(mock.clone()), 1)
+            .with_batch_size(2)
+            .with_job(job);
+        let translations = third.translate(&lines(6), "es").await.unwrap();
+        assert_eq!(translations, expected);
+        assert_eq!(mock.calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn test_batches_get_surrounding_context() {
+        let mock = Arc::new(MockTranslator::new());
+        let orchestrator = TranslationOrchestrator::new(Box::new(mock.clone()), 1)
+            .with_batch_size(3)
+            .with_context_lines(2)
+            .with_summary(true);
+
+        orchestrator.translate(&lines(7), "es").await.unwrap();
+
+        let contexts = mock.contexts.lock().unwrap();
+        assert_eq!(contexts.len(), 3);
+        assert!(contexts[0].before.is_empty());
+        assert_eq!(contexts[0].after, ["line 3", "line 4"]);
+        assert_eq!(contexts[1].before, ["line 1", "line 2"]);
+        assert_eq!(contexts[1].after, ["line 6"]);
+        assert_eq!(contexts[2].before, ["line 4", "line 5"]);
+        assert!(contexts[2].after.is_empty());
+        // One summary of the whole document is shared by every batch
+        assert!(contexts
+            .iter()
+            .all(|c| c.summary.as_deref() == Some("7 lines")));
+    }
+
+    #[tokio::test]
+    async fn test_languages_share_limit_and_summary() {
+        let mock = Arc::new(MockTranslator::new());
+        let orchestrator = TranslationOrchestrator::new(Box::new(mock.clone()), 3)
+            .with_batch_size(2)
+            .with_summary(true);
+        let languages = ["es".to_string(), "fr".to_string(), "de".to_string()];
+
+        let results = orchestrator
+            .translate_languages(&lines(6), &languages)
+            .await
+            .unwrap();
+
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].language, "es");
+        assert_eq!(results[0].texts[5], "LINE 5");
+        assert_eq!(results[1].texts[0], "fr:line 0");
+        assert_eq!(
+            results[2].texts,
+            lines(6)
+                .iter()
+                .map(|t| format!("de:{}", t))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(mock.calls.load(Ordering::SeqCst), 9);
+        assert!(mock.max_in_flight.load(Ordering::SeqCst) <= 3);
+        assert_eq!(mock.summaries.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_translations_over_budget_are_condensed() {
+        let mock = Arc::new(MockTranslator::new());
+        let orchestrator = TranslationOrchestrator::new(Box::new(mock.clone()), 2)
+            .with_batch_size(2)
+            .with_budgets(vec![10, 8, 5]);
+        let texts = ["line 0", "a much longer line", "stubborn line"].map(String::from);
+
+        let translations = orchestrator.translate(&texts, "es").await.unwrap();
+
+        assert_eq!(translations, ["LINE 0", "A MUCH L", "STUBBORN LINE"]);
+        assert_eq!(orchestrator.condensed(), 1);
+        assert_eq!(
+            *mock.condensed.lock().unwrap(),
+            ["a much longer line", "stubborn line"]
+        );
+
+        // Each batch is told the budgets of its own texts
+        let mut budgets: Vec<Vec<usize>> = mock
+            .contexts
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|c| c.budgets.clone())
+            .collect();
+        budgets.sort();
+        assert_eq!(budgets, [vec![5], vec![10, 8]]);
+    }
+
+    #[tokio::test]
+    async fn test_empty_input() {
+        let orchestrator =
+            TranslationOrchestrator::new(Box::new(Arc::new(MockTranslator::new())), 2);
+        assert!(orchestrator.translate(&[], "es").await.unwrap().is_empty());
+    }
+}
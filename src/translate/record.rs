@@ -0,0 +1,380 @@
+//! Recording and replay wrappers for translation providers.
+//!
+//! Records are keyed by a hash of the target language, the batch texts and
+//! their context. Document summaries are recorded by a hash of the texts,
+//! and condense requests by a hash of everything they send.
+
+use crate::error::Result;
+use crate::record::{hash_parts, RecordStore};
+use crate::translate::{TranslationContext, Translator};
+use crate::usage::ModelUsage;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+/// Directory under the recording root holding translation records.
+const KIND: &str = "translation";
+
+/// Directory under the recording root holding document summaries.
+const SUMMARY_KIND: &str = "summary";
+
+/// Directory under the recording root holding condense requests.
+const CONDENSE_KIND: &str = "condense";
+
+/// A recorded translation call.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranslationRecord {
+    pub request: TranslationRequest,
+    pub response: Vec<String>,
+}
+
+/// What was sent to the provider.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranslationRequest {
+    pub provider: String,
+    pub target_language: String,
+    pub texts: Vec<String>,
+    #[serde(default, skip_serializing_if = "TranslationContext::is_empty")]
+    pub context: TranslationContext,
+}
+
+/// A recorded document summary.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SummaryRecord {
+    pub provider: String,
+    pub summary: Option<String>,
+}
+
+/// A recorded request to shorten translations.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CondenseRecord {
+    pub provider: String,
+    pub target_language: String,
+    pub sources: Vec<String>,
+    pub translations: Vec<String>,
+    pub budgets: Vec<usize>,
+    pub response: Vec<String>,
+}
+
+/// Key a batch is recorded and replayed under.
+fn batch_key(texts: &[&str], target_lang: &str, context: &TranslationContext) -> String {
+    let key = std::iter::once(target_lang.as_bytes()).chain(texts.iter().map(|t| t.as_bytes()));
+    // Batches without context keep the keys of recordings made before context existed
+    if context.is_empty() {
+        return hash_parts(key);
+    }
+    let context = serde_json::to_vec(context).unwrap_or_default();
+    hash_parts(key.chain(std::iter::once(context.as_slice())))
+}
+
+/// Key a condense request is recorded and replayed under.
+fn condense_key(
+    sources: &[&str],
+    translations: &[&str],
+    budgets: &[usize],
+    target_lang: &str,
+) -> String {
+    let budgets = budgets
+        .iter()
+        .map(|b| b.to_string())
+        .collect::<Vec<_>>()
+        .join(",");
+    hash_parts(
+        std::iter::once(target_lang.as_bytes())
+            .chain(sources.iter().map(|t| t.as_bytes()))
+            .chain(translations.iter().map(|t| t.as_bytes()))
+            .chain(std::iter::once(budgets.as_bytes())),
+    )
+}
+
+/// Key a document summary is recorded and replayed under.
+fn summary_key(texts: &[&str]) -> String {
+    hash_parts(texts.iter().map(|t| t.as_bytes()))
+}
+
+/// Translator that saves every successful call of the wrapped provider.
+pub struct RecordingTranslator {
+    inner: Box<dyn Translator>,
+    store: RecordStore,
+}
+
+impl RecordingTranslator {
+    pub fn new(inner: Box<dyn Translator>, store: RecordStore) -> Self {
+        Self { inner, store }
+    }
+}
+
+#[async_trait]
+impl Translator for RecordingTranslator {
+    async fn translate(&self, text: &str, target_lang: &str) -> Result<String> {
+        let results = self.translate_batch(&[text], target_lang).await?;
+        Ok(results.into_iter().next().unwrap_or_default())
+    }
+
+    async fn translate_batch(&self, texts: &[&str], target_lang: &str) -> Result<Vec<String>> {
+        self.translate_batch_with_context(texts, target_lang, &TranslationContext::default())
+            .await
+    }
+
+    async fn translate_batch_with_context(
+        &self,
+        texts: &[&str],
+        target_lang: &str,
+        context: &TranslationContext,
+    ) -> Result<Vec<String>> {
+        let translations = self
+            .inner
+            .translate_batch_with_context(texts, target_lang, context)
+            .await?;
+
+        let key = batch_key(texts, target_lang, context);
+        let record = TranslationRecord {
+            request: TranslationRequest {
+                provider: self.inner.name().to_string(),
+                target_language: target_lang.to_string(),
+                texts: texts.iter().map(|t| t.to_string()).collect(),
+                context: context.clone(),
+            },
+            response: translations.clone(),
+        };
+        self.store.save(KIND, &key, &record)?;
+        debug!("Recorded translation of {} text(s) as {}", texts.len(), key);
+
+        Ok(translations)
+    }
+
+    async fn condense(
+        &self,
+        sources: &[&str],
+        translations: &[&str],
+        budgets: &[usize],
+        target_lang: &str,
+    ) -> Result<Vec<String>> {
+        let condensed = self
+            .inner
+            .condense(sources, translations, budgets, target_lang)
+            .await?;
+
+        let key = condense_key(sources, translations, budgets, target_lang);
+        let record = CondenseRecord {
+            provider: self.inner.name().to_string(),
+            target_language: target_lang.to_string(),
+            sources: sources.iter().map(|t| t.to_string()).collect(),
+            translations: translations.iter().map(|t| t.to_string()).collect(),
+            budgets: budgets.to_vec(),
+            response: condensed.clone(),
+        };
+        self.store.save(CONDENSE_KIND, &key, &record)?;
+        debug!(
+            "Recorded condensing of {} translation(s) as {}",
+            translations.len(),
+            key
+        );
+
+        Ok(condensed)
+    }
+
+    async fn summarize(&self, texts: &[&str]) -> Result<Option<String>> {
+        let summary = self.inner.summarize(texts).await?;
+
+        let key = summary_key(texts);
+        let record = SummaryRecord {
+            provider: self.inner.name().to_string(),
+            summary: summary.clone(),
+        };
+        self.store.save(SUMMARY_KIND, &key, &record)?;
+        debug!("Recorded document summary as {}", key);
+
+        Ok(summary)
+    }
+
+    fn supported_languages(&self) -> &[&str] {
+        self.inner.supported_languages()
+    }
+
+    fn name(&self) -> &'static str {
+        self.inner.name()
+    }
+
+    fn model(&self) -> &str {
+        self.inner.model()
+    }
+
+    fn usage(&self) -> Vec<ModelUsage> {
+        self.inner.usage()
+    }
+}
+
+/// Translator that serves recorded responses without calling any provider.
+pub struct ReplayTranslator {
+    store: RecordStore,
+}
+
+impl ReplayTranslator {
+    pub fn new(store: RecordStore) -> Self {
+        Self { store }
+    }
+}
+
+#[async_trait]
+impl Translator for ReplayTranslator {
+    async fn translate(&self, text: &str, target_lang: &str) -> Result<String> {
+        let results = self.translate_batch(&[text], target_lang).await?;
+        Ok(results.into_iter().next().unwrap_or_default())
+    }
+
+    async fn translate_batch(&self, texts: &[&str], target_lang: &str) -> Result<Vec<String>> {
+        self.translate_batch_with_context(texts, target_lang, &TranslationContext::default())
+            .await
+    }
+
+    async fn translate_batch_with_context(
+        &self,
+        texts: &[&str],
+        target_lang: &str,
+        context: &TranslationContext,
+    ) -> Result<Vec<String>> {
+        let key = batch_key(texts, target_lang, context);
+        let record: TranslationRecord = self.store.load(KIND, &key)?;
+        debug!(
+            "Replaying translation of {} text(s) from {}",
+            texts.len(),
+            key
+        );
+        Ok(record.response)
+    }
+
+    async fn condense(
+        &self,
+        sources: &[&str],
+        translations: &[&str],
+        budgets: &[usize],
+        target_lang: &str,
+    ) -> Result<Vec<String>> {
+        let key = condense_key(sources, translations, budgets, target_lang);
+        let record: CondenseRecord = self.store.load(CONDENSE_KIND, &key)?;
+        debug!(
+            "Replaying condensing of {} translation(s) from {}",
+            translations.len(),
+            key
+        );
+        Ok(record.response)
+    }
+
+    async fn summarize(&self, texts: &[&str]) -> Result<Option<String>> {
+        let key = summary_key(texts);
+        let record: SummaryRecord = self.store.load(SUMMARY_KIND, &key)?;
+        debug!("Replaying document summary from {}", key);
+        Ok(record.summary)
+    }
+
+    fn supported_languages(&self) -> &[&str] {
+        &[]
+    }
+
+    fn name(&self) -> &'static str {
+        "replay"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct UppercaseTranslator;
+
+    #[async_trait]
+    impl Translator for UppercaseTranslator {
+        async fn translate(&self, text: &str, _target_lang: &str) -> Result<String> {
+            Ok(text.to_uppercase())
+        }
+
+        async fn translate_batch(&self, texts: &[&str], _target_lang: &str) -> Result<Vec<String>> {
+            Ok(texts.iter().map(|t| t.to_uppercase()).collect())
+        }
+
+        fn supported_languages(&self) -> &[&str] {
+            &["en"]
+        }
+
+        async fn condense(
+            &self,
+            _sources: &[&str],
+            translations: &[&str],
+            budgets: &[usize],
+            _target_lang: &str,
+        ) -> Result<Vec<String>> {
+            Ok(translations
+                .iter()
+                .zip(budgets)
+                .map(|(t, &b)| t.chars().take(b).collect())
+                .collect())
+        }
+
+        fn name(&self) -> &'static str {
+            "uppercase"
+        }
+    }
+
+    #[tokio::test]
+    async fn test_replay_serves_recorded_batch() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = RecordStore::new(dir.path());
+
+        let recorder = RecordingTranslator::new(Box::new(UppercaseTranslator), store.clone());
+        recorder
+            .translate_batch(&["hello", "bye"], "en")
+            .await
+            .unwrap();
+
+        let replayer = ReplayTranslator::new(store);
+        assert_eq!(
+            replayer
+                .translate_batch(&["hello", "bye"], "en")
+                .await
+                .unwrap(),
+            vec!["HELLO", "BYE"]
+        );
+        assert!(replayer
+            .translate_batch(&["hello", "bye"], "fr")
+            .await
+            .is_err());
+        assert!(replayer.translate_batch(&["hello"], "en").await.is_err());
+
+        // The same texts with different context were never recorded
+        let context = TranslationContext {
+            before: vec!["earlier".to_string()],
+            ..Default::default()
+        };
+        assert!(replayer
+            .translate_batch_with_context(&["hello", "bye"], "en", &context)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_replay_serves_recorded_condensing() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = RecordStore::new(dir.path());
+
+        let recorder = RecordingTranslator::new(Box::new(UppercaseTranslator), store.clone());
+        let condensed = recorder
+            .condense(&["hello there"], &["HELLO THERE"], &[5], "en")
+            .await
+            .unwrap();
+        assert_eq!(condensed, ["HELLO"]);
+
+        let replayer = ReplayTranslator::new(store);
+        assert_eq!(
+            replayer
+                .condense(&["hello there"], &["HELLO THERE"], &[5], "en")
+                .await
+                .unwrap(),
+            ["HELLO"]
+        );
+        assert!(replayer
+            .condense(&["hello there"], &["HELLO THERE"], &[6], "en")
+            .await
+            .is_err());
+    }
+}
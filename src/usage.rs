@@ -0,0 +1,323 @@
+//! Token usage accounting and cost estimation.
+//!
+//! Providers record the token counts reported with each response. The
+//! pipeline collects the totals per model and prices them with a table of
+//! per-million-token rates, which can be overridden in config.toml.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+/// Gemini bills audio input at a fixed 32 tokens per second.
+pub const AUDIO_TOKENS_PER_SECOND: u64 = 32;
+
+/// Rough transcript output for typical speech, including timestamps.
+const TRANSCRIPT_TOKENS_PER_SECOND: u64 = 5;
+
+/// Fixed prompt overhead per transcription request.
+const TRANSCRIBE_PROMPT_TOKENS: u64 = 150;
+
+/// Token counts for one model.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenUsage {
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub requests: u64,
+}
+
+impl TokenUsage {
+    pub fn total_tokens(&self) -> u64 {
+        self.input_tokens + self.output_tokens
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.requests == 0 && self.total_tokens() == 0
+    }
+
+    /// Add another usage to this one.
+    pub fn add(&mut self, other: TokenUsage) {
+        self.input_tokens += other.input_tokens;
+        self.output_tokens += other.output_tokens;
+        self.requests += other.requests;
+    }
+}
+
+/// Token usage attributed to a model.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModelUsage {
+    pub model: String,
+    #[serde(flatten)]
+    pub usage: TokenUsage,
+}
+
+/// Merge usage entries for the same model.
+pub fn merge_usage(entries: impl IntoIterator<Item = ModelUsage>) -> Vec<ModelUsage> {
+    let mut merged: Vec<ModelUsage> = Vec::new();
+    for entry in entries {
+        match merged.iter_mut().find(|m| m.model == entry.model) {
+            Some(existing) => existing.usage.add(entry.usage),
+            None => merged.push(entry),
+        }
+    }
+    merged
+}
+
+/// Thread-safe running total of token usage.
+#[derive(Debug, Default)]
+pub struct UsageTracker {
+    input_tokens: AtomicU64,
+    output_tokens: AtomicU64,
+    requests: AtomicU64,
+}
+
+impl UsageTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record the usage of one response.
+    pub fn record(&self, usage: TokenUsage) {
+        self.input_tokens
+            .fetch_add(usage.input_tokens, Ordering::Relaxed);
+        self.output_tokens
+            .fetch_add(usage.output_tokens, Ordering::Relaxed);
+        self.requests.fetch_add(usage.requests, Ordering::Relaxed);
+    }
+
+    /// Current totals.
+    pub fn snapshot(&self) -> TokenUsage {
+        TokenUsage {
+            input_tokens: self.input_tokens.load(Ordering::Relaxed),
+            output_tokens: self.output_tokens.load(Ordering::Relaxed),
+            requests: self.requests.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Current totals attributed to `model`, or nothing if unused.
+    pub fn model_usage(&self, model: &str) -> Vec<ModelUsage> {
+        let usage = self.snapshot();
+        if usage.is_empty() {
+            return Vec::new();
+        }
+        vec![ModelUsage {
+            model: model.to_string(),
+            usage,
+        }]
+    }
+}
+
+/// `usageMetadata` block of a Gemini response.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct GeminiUsageMetadata {
+    #[serde(default)]
+    prompt_token_count: u64,
+    #[serde(default)]
+    candidates_token_count: u64,
+    #[serde(default)]
+    thoughts_token_count: u64,
+}
+
+impl From<&GeminiUsageMetadata> for TokenUsage {
+    fn from(meta: &GeminiUsageMetadata) -> Self {
+        // Thinking tokens are billed as output
+        TokenUsage {
+            input_tokens: meta.prompt_token_count,
+            output_tokens: meta.candidates_token_count + meta.thoughts_token_count,
+            requests: 1,
+        }
+    }
+}
+
+/// Price of a model in US dollars per million tokens.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ModelPrice {
+    pub input_per_million: f64,
+    pub output_per_million: f64,
+}
+
+impl ModelPrice {
+    /// Cost of the given usage in US dollars.
+    pub fn cost(&self, usage: &TokenUsage) -> f64 {
+        (usage.input_tokens as f64 * self.input_per_million
+            + usage.output_tokens as f64 * self.output_per_million)
+            / 1_000_000.0
+    }
+}
+
+/// Built-in prices. Transcription models are priced at their audio input rate.
+pub fn default_prices() -> HashMap<String, ModelPrice> {
+    [
+        ("gemini-2.5-flash", 1.00, 2.50),
+        ("gemini-2.5-pro", 1.25, 10.00),
+        ("gemini-2.0-flash", 0.10, 0.40),
+    ]
+    .into_iter()
+    .map(|(model, input, output)| {
+        (
+            model.to_string(),
+            ModelPrice {
+                input_per_million: input,
+                output_per_million: output,
+            },
+        )
+    })
+    .collect()
+}
+
+/// Price lookup combining the built-in table with configured overrides.
+#[derive(Debug, Clone)]
+pub struct PriceTable {
+    prices: HashMap<String, ModelPrice>,
+}
+
+impl PriceTable {
+    /// Built-in prices with `overrides` taking precedence.
+    pub fn new(overrides: &HashMap<String, ModelPrice>) -> Self {
+        let mut prices = default_prices();
+        prices.extend(overrides.iter().map(|(k, v)| (k.clone(), *v)));
+        Self { prices }
+    }
+
+    pub fn price(&self, model: &str) -> Option<&ModelPrice> {
+        self.prices.get(model)
+    }
+
+    /// Total cost of the usage, or `None` if any model has no known price.
+    pub fn cost(&self, usage: &[ModelUsage]) -> Option<f64> {
+        usage.iter().try_fold(0.0, |total, entry| {
+            self.price(&entry.model)
+                .map(|price| total + price.cost(&entry.usage))
+        })
+    }
+}
+
+impl Default for PriceTable {
+    fn default() -> Self {
+        Self::new(&HashMap::new())
+    }
+}
+
+/// Predict token usage for an audio file before anything is sent.
+///
+/// Audio input is billed at a fixed rate per second; output and translation
+/// volumes are estimates for typical speech.
+pub fn estimate_usage(
+    audio_duration: Duration,
+    chunk_count: usize,
+    transcribe_model: &str,
+    translate_model: Option<&str>,
+) -> Vec<ModelUsage> {
+    let seconds = audio_duration.as_secs_f64().ceil() as u64;
+    let requests = chunk_count.max(1) as u64;
+    let transcript_tokens = seconds * TRANSCRIPT_TOKENS_PER_SECOND;
+
+    let mut usage = vec![ModelUsage {
+        model: transcribe_model.to_string(),
+        usage: TokenUsage {
+            input_tokens: seconds * AUDIO_TOKENS_PER_SECOND + requests * TRANSCRIBE_PROMPT_TOKENS,
+            output_tokens: transcript_tokens,
+            requests,
+        },
+    }];
+
+    if let Some(model) = translate_model {
+        // Batches of ten segments of a few seconds each
+        let batches = (seconds / 40).max(1);
+        usage.push(ModelUsage {
+            model: model.to_string(),
+            usage: TokenUsage {
+                input_tokens: transcript_tokens + batches * 60,
+                output_tokens: transcript_tokens,
+                requests: batches,
+            },
+        });
+    }
+
+    usage
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tracker_and_metadata() {
+        let meta: GeminiUsageMetadata = serde_json::from_str(
+            r#"{"promptTokenCount": 1000, "candidatesTokenCount": 200, "thoughtsTokenCount": 50, "totalTokenCount": 1250}"#,
+        )
+        .unwrap();
+        let tracker = UsageTracker::new();
+        tracker.record((&meta).into());
+        tracker.record((&meta).into());
+
+        let usage = tracker.snapshot();
+        assert_eq!(usage.input_tokens, 2000);
+        assert_eq!(usage.output_tokens, 500);
+        assert_eq!(usage.requests, 2);
+        assert!(UsageTracker::new().model_usage("m").is_empty());
+    }
+
+    #[test]
+    fn test_price_table_cost() {
+        let usage = vec![ModelUsage {
+            model: "gemini-2.0-flash".to_string(),
+            usage: TokenUsage {
+                input_tokens: 1_000_000,
+                output_tokens: 500_000,
+                requests: 10,
+            },
+        }];
+        let table = PriceTable::default();
+        let cost = table.cost(&usage).unwrap();
+        assert!((cost - 0.30).abs() < 1e-9);
+
+        let mut overrides = HashMap::new();
+        overrides.insert(
+            "gemini-2.0-flash".to_string(),
+            ModelPrice {
+                input_per_million: 1.0,
+                output_per_million: 2.0,
+            },
+        );
+        let cost = PriceTable::new(&overrides).cost(&usage).unwrap();
+        assert!((cost - 2.0).abs() < 1e-9);
+
+        let unknown = vec![ModelUsage {
+            model: "mystery".to_string(),
+            usage: TokenUsage::default(),
+        }];
+        assert!(table.cost(&unknown).is_none());
+    }
+
+    #[test]
+    fn test_estimate_usage() {
+        let usage = estimate_usage(
+            Duration::from_secs(600),
+            2,
+            "gemini-2.5-flash",
+            Some("gemini-2.0-flash"),
+        );
+        assert_eq!(usage.len(), 2);
+        assert_eq!(usage[0].usage.input_tokens, 600 * 32 + 2 * 150);
+        assert_eq!(usage[0].usage.requests, 2);
+        assert!(usage[1].usage.output_tokens > 0);
+    }
+
+    #[test]
+    fn test_merge_usage() {
+        let entry = |model: &str, tokens| ModelUsage {
+            model: model.to_string(),
+            usage: TokenUsage {
+                input_tokens: tokens,
+                output_tokens: 0,
+                requests: 1,
+            },
+        };
+        let merged = merge_usage(vec![entry("a", 1), entry("b", 2), entry("a", 3)]);
+        assert_eq!(merged.len(), 2);
+        assert_eq!(merged[0].usage.input_tokens, 4);
+        assert_eq!(merged[0].usage.requests, 2);
+    }
+}
@@ -5,14 +5,17 @@
 
 use autosub::audio::{AudioMetadata, ChunkConfig, SpeechRegion};
 use autosub::config::{Config, OutputFormat};
-use autosub::pipeline::PipelineConfig;
+use autosub::error::AutosubError;
+use autosub::pipeline::{translate_subtitles, PipelineConfig};
 use autosub::subtitle::{
-    convert_to_subtitles, convert_with_defaults, create_formatter, json::JsonFormatter,
-    quick_convert, srt::SrtFormatter, vtt::VttFormatter, PostProcessConfig, SubtitleEntry,
-    SubtitleFormatter,
+    convert_to_subtitles, convert_with_defaults, create_formatter, create_formatter_with_options,
+    json::JsonFormatter, quick_convert, read_subtitles, srt::SrtFormatter, vtt::VttFormatter,
+    FormatOptions, OriginalStyle, PostProcessConfig, SubtitleEntry, SubtitleFormatter,
 };
-use autosub::transcribe::{Transcript, TranscriptSegment};
+use autosub::transcribe::{SegmentKind, Transcript, TranscriptSegment};
 
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
 use std::time::Duration;
 
 // ============================================================================
@@ -31,8 +34,10 @@ mod config_tests {
 
     #[test]
     fn test_config_gemini_validation() {
-        let mut config = Config::default();
-        config.gemini_api_key = None;
+        let mut config = Config {
+            gemini_api_key: None,
+            ..Default::default()
+        };
 
         let result = config.validate();
         assert!(result.is_err());
@@ -41,6 +46,32 @@ mod config_tests {
         assert!(config.validate().is_ok());
     }
 
+    #[test]
+    fn test_prompt_templates_from_files() {
+        use autosub::prompt::PromptTemplates;
+
+        let dir = tempfile::tempdir().unwrap();
+        let transcription = dir.path().join("transcribe.txt");
+        let translation = dir.path().join("translate.txt");
+        std::fs::write(
+            &transcription,
+            "Audio in {language}, from {offset}.\nWrite [MM:SS] text lines.",
+        )
+        .unwrap();
+        std::fs::write(&translation, "Translate to {language}:\n{texts}").unwrap();
+
+        let prompts = PromptTemplates::load(Some(&transcription), None).unwrap();
+        assert!(prompts.transcription.is_some());
+        assert!(prompts.translation.is_none());
+
+        // The translation template does not ask for the JSON reply format
+        let err = PromptTemplates::load(Some(&transcription), Some(&translation)).unwrap_err();
+        assert!(err.to_string().contains("\"id\""));
+        assert!(err.to_string().contains("translate.txt"));
+
+        assert!(PromptTemplates::load(Some(&dir.path().join("missing.txt")), None).is_err());
+    }
+
     #[test]
     fn test_output_format_extensions() {
         assert_eq!(OutputFormat::Srt.extension(), "srt");
@@ -64,6 +95,9 @@ mod subtitle_formatter_tests {
                 end: Duration::from_millis(4000),
                 text: "Hello, welcome to this video.".to_string(),
                 speaker: None,
+                confidence: None,
+                kind: SegmentKind::Speech,
+                original_text: None,
             },
             SubtitleEntry {
                 index: 2,
@@ -71,13 +105,16 @@ mod subtitle_formatter_tests {
                 end: Duration::from_millis(7000),
                 text: "Today we're going to learn.".to_string(),
                 speaker: None,
+                confidence: None,
+                kind: SegmentKind::Speech,
+                original_text: None,
             },
         ]
     }
 
     #[test]
     fn test_srt_formatter_integration() {
-        let formatter = SrtFormatter;
+        let formatter = SrtFormatter::default();
         let entries = sample_entries();
         let output = formatter.format(&entries);
 
@@ -90,7 +127,7 @@ mod subtitle_formatter_tests {
 
     #[test]
     fn test_vtt_formatter_integration() {
-        let formatter = VttFormatter;
+        let formatter = VttFormatter::default();
         let entries = sample_entries();
         let output = formatter.format(&entries);
 
@@ -106,6 +143,7 @@ mod subtitle_formatter_tests {
             source_file: Some("test.mp4".to_string()),
             language: Some("en".to_string()),
             provider: Some("gemini".to_string()),
+            ..Default::default()
         };
         let entries = sample_entries();
         let output = formatter.format(&entries);
@@ -137,9 +175,12 @@ mod subtitle_formatter_tests {
             end: Duration::from_secs(5),
             text: "This is line one.\nThis is line two.".to_string(),
             speaker: None,
+            confidence: None,
+            kind: SegmentKind::Speech,
+            original_text: None,
         }];
 
-        let formatter = SrtFormatter;
+        let formatter = SrtFormatter::default();
         let output = formatter.format(&entries);
 
         assert!(output.contains("This is line one.\nThis is line two."));
@@ -160,16 +201,20 @@ mod conversion_tests {
                 end: Duration::from_millis(2500),
                 text: "First segment here.".to_string(),
                 speaker: None,
+                kind: SegmentKind::Speech,
                 confidence: Some(0.95),
                 words: None,
+                original_text: None,
             },
             TranscriptSegment {
                 start: Duration::from_millis(3000),
                 end: Duration::from_millis(5500),
                 text: "Second segment here.".to_string(),
                 speaker: Some("Speaker A".to_string()),
+                kind: SegmentKind::Speech,
                 confidence: Some(0.90),
                 words: None,
+                original_text: None,
             },
         ]
     }
@@ -201,8 +246,10 @@ mod conversion_tests {
             end: Duration::from_millis(200), // Very short - should extend
             text: "Short.".to_string(),
             speaker: None,
+            kind: SegmentKind::Speech,
             confidence: None,
             words: None,
+            original_text: None,
         }];
 
         let entries = convert_with_defaults(segments);
@@ -218,8 +265,10 @@ mod conversion_tests {
             end: Duration::from_secs(3),
             text: "So, um, you know, this is like important.".to_string(),
             speaker: None,
+            kind: SegmentKind::Speech,
             confidence: None,
             words: None,
+            original_text: None,
         }];
 
         let config = PostProcessConfig {
@@ -242,16 +291,20 @@ mod conversion_tests {
                 end: Duration::from_millis(1000),
                 text: "First.".to_string(),
                 speaker: None,
+                kind: SegmentKind::Speech,
                 confidence: None,
                 words: None,
+                original_text: None,
             },
             TranscriptSegment {
                 start: Duration::from_millis(1050), // Only 50ms gap
                 end: Duration::from_millis(2000),
                 text: "Second.".to_string(),
                 speaker: None,
+                kind: SegmentKind::Speech,
                 confidence: None,
                 words: None,
+                original_text: None,
             },
         ];
 
@@ -384,16 +437,39 @@ mod pipeline_config_tests {
         let config = PipelineConfig {
             format: OutputFormat::Vtt,
             language: "ja".to_string(),
-            translate_to: Some("en".to_string()),
+            translate_to: vec!["en".to_string()],
             concurrency: 8,
+            translation_batch_size: 25,
+            translation_context: 0,
+            translation_summary: true,
+            max_cps: Some(17.0),
+            diarize: true,
+            sdh: true,
+            speaker_format: Default::default(),
+            validate: true,
+            use_cache: false,
+            resumable: false,
+            incremental: false,
+            low_confidence: 0.5,
+            retranscribe_below: None,
+            provider_mode: Default::default(),
+            transcription_prompt: None,
+            translation_prompt: None,
+            glossary: None,
+            translation_memory: true,
+            memory_fuzzy: None,
+            dual_subtitles: None,
             post_process: Some(PostProcessConfig::default()),
             show_progress: true,
         };
 
         assert_eq!(config.format, OutputFormat::Vtt);
         assert_eq!(config.language, "ja");
-        assert_eq!(config.translate_to, Some("en".to_string()));
+        assert_eq!(config.translate_to, ["en"]);
         assert_eq!(config.concurrency, 8);
+        assert_eq!(config.translation_batch_size, 25);
+        assert!(config.diarize);
+        assert!(config.sdh);
     }
 }
 
@@ -413,16 +489,20 @@ mod e2e_formatting_tests {
                 end: Duration::from_millis(3000),
                 text: "Welcome to the tutorial.".to_string(),
                 speaker: None,
+                kind: SegmentKind::Speech,
                 confidence: Some(0.99),
                 words: None,
+                original_text: None,
             },
             TranscriptSegment {
                 start: Duration::from_millis(3500),
                 end: Duration::from_millis(6000),
                 text: "Let's get started.".to_string(),
                 speaker: None,
+                kind: SegmentKind::Speech,
                 confidence: Some(0.98),
                 words: None,
+                original_text: None,
             },
         ];
 
@@ -449,8 +529,10 @@ mod e2e_formatting_tests {
             end: Duration::from_secs(5),
             text: "Hello World".to_string(),
             speaker: None,
+            kind: SegmentKind::Speech,
             confidence: None,
             words: None,
+            original_text: None,
         }];
 
         let entries = quick_convert(segments);
@@ -462,6 +544,132 @@ mod e2e_formatting_tests {
         assert!(vtt_output.contains("Hello World"));
     }
 
+    #[test]
+    fn test_dual_subtitle_workflow() {
+        let segments = vec![TranscriptSegment {
+            start: Duration::from_secs(0),
+            end: Duration::from_secs(3),
+            text: "Bienvenidos al tutorial.".to_string(),
+            speaker: None,
+            kind: SegmentKind::Speech,
+            confidence: None,
+            words: None,
+            original_text: Some("Welcome to the tutorial.".to_string()),
+        }];
+        let entries = quick_convert(segments);
+
+        let options = FormatOptions::default().with_dual(OriginalStyle::Italic);
+        let srt_output =
+            create_formatter_with_options(OutputFormat::Srt, &options).format(&entries);
+        assert!(srt_output.contains("Bienvenidos al tutorial.\n<i>Welcome to the tutorial.</i>\n"));
+
+        let vtt_output =
+            create_formatter_with_options(OutputFormat::Vtt, &options).format(&entries);
+        assert!(vtt_output.contains("Bienvenidos al tutorial.\n<i>Welcome to the tutorial.</i>\n"));
+
+        let json_output =
+            create_formatter_with_options(OutputFormat::Json, &options).format(&entries);
+        let json: serde_json::Value = serde_json::from_str(&json_output).unwrap();
+        assert_eq!(json["subtitles"][0]["text"], "Bienvenidos al tutorial.");
+        assert_eq!(
+            json["subtitles"][0]["original_text"],
+            "Welcome to the tutorial."
+        );
+
+        // Without dual mode the text output shows the translation only
+        assert!(!create_formatter(OutputFormat::Srt)
+            .format(&entries)
+            .contains("Welcome"));
+    }
+
+    #[test]
+    fn test_existing_subtitle_file_workflow() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("movie.srt");
+        std::fs::write(
+            &path,
+            "\u{feff}12\r\n00:00:01,000 --> 00:00:03,500\r\nWelcome back.\r\n\r\n\
+             13\r\n00:00:04,000 --> 00:00:06,000\r\nShall we begin?\r\n",
+        )
+        .unwrap();
+
+        let (mut entries, format) = read_subtitles(&path).unwrap();
+        assert_eq!(format, OutputFormat::Srt);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].index, 12);
+        assert_eq!(entries[1].start, Duration::from_secs(4));
+
+        // Translated text replaces the original; timings and numbering stay
+        entries[0].original_text = Some(std::mem::replace(
+            &mut entries[0].text,
+            "Bienvenidos.".to_string(),
+        ));
+        let options = FormatOptions::default().with_dual(OriginalStyle::Plain);
+        let vtt_output =
+            create_formatter_with_options(OutputFormat::Vtt, &options).format(&entries);
+        assert!(vtt_output.contains("00:00:01.000 --> 00:00:03.500\nBienvenidos.\nWelcome back.\n"));
+        let srt_output = create_formatter(OutputFormat::Srt).format(&entries);
+        assert!(srt_output.starts_with("12\n00:00:01,000 --> 00:00:03,500\nBienvenidos.\n"));
+        assert!(srt_output.contains("13\n00:00:04,000 --> 00:00:06,000\nShall we begin?\n"));
+
+        let empty = dir.path().join("empty.vtt");
+        std::fs::write(&empty, "WEBVTT\n\n").unwrap();
+        let message = read_subtitles(&empty).unwrap_err().to_string();
+        assert_eq!(
+            message,
+            format!("Invalid subtitle file: {}: no cues found", empty.display())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_translate_subtitles_rejects_bad_input() {
+        let dir = tempfile::tempdir().unwrap();
+        let output = dir.path().join("movie.es.srt");
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let pipeline_config = PipelineConfig {
+            translate_to: vec!["es".to_string()],
+            show_progress: false,
+            ..Default::default()
+        };
+
+        let missing = dir.path().join("missing.srt");
+        let result = translate_subtitles(
+            &missing,
+            &output,
+            &Config::default(),
+            pipeline_config.clone(),
+            cancelled.clone(),
+        )
+        .await;
+        assert!(matches!(result, Err(AutosubError::FileNotFound(_))));
+
+        let unsupported = dir.path().join("movie.ass");
+        std::fs::write(&unsupported, "[Script Info]\n").unwrap();
+        let result = translate_subtitles(
+            &unsupported,
+            &output,
+            &Config::default(),
+            pipeline_config,
+            cancelled.clone(),
+        )
+        .await;
+        assert!(matches!(result, Err(AutosubError::SubtitleParse(_))));
+
+        // Nothing to translate into
+        let input = dir.path().join("movie.srt");
+        std::fs::write(&input, "1\n00:00:01,000 --> 00:00:02,000\nHi\n").unwrap();
+        let result = translate_subtitles(
+            &input,
+            &output,
+            &Config::default(),
+            PipelineConfig::default(),
+            cancelled,
+        )
+        .await;
+        assert!(matches!(result, Err(AutosubError::Config(_))));
+        assert!(!output.exists());
+    }
+
     #[test]
     fn test_workflow_with_speaker_diarization() {
         let segments = vec![
@@ -470,21 +678,25 @@ mod e2e_formatting_tests {
                 end: Duration::from_secs(3),
                 text: "How are you?".to_string(),
                 speaker: Some("Alice".to_string()),
+                kind: SegmentKind::Speech,
                 confidence: None,
                 words: None,
+                original_text: None,
             },
             TranscriptSegment {
                 start: Duration::from_secs(4),
                 end: Duration::from_secs(7),
                 text: "I'm doing great!".to_string(),
                 speaker: Some("Bob".to_string()),
+                kind: SegmentKind::Speech,
                 confidence: None,
                 words: None,
+                original_text: None,
             },
         ];
 
         let entries = quick_convert(segments);
-        let formatter = SrtFormatter;
+        let formatter = SrtFormatter::default();
         let output = formatter.format(&entries);
 
         assert!(output.contains("[Alice] How are you?"));
@@ -506,7 +718,7 @@ mod edge_case_tests {
 
         assert!(entries.is_empty());
 
-        let formatter = SrtFormatter;
+        let formatter = SrtFormatter::default();
         let output = formatter.format(&entries);
         assert!(output.is_empty());
     }
@@ -518,8 +730,10 @@ mod edge_case_tests {
             end: Duration::from_millis(100), // 100ms
             text: "Hi".to_string(),
             speaker: None,
+            kind: SegmentKind::Speech,
             confidence: None,
             words: None,
+            original_text: None,
         }];
 
         let entries = convert_with_defaults(segments);
@@ -536,16 +750,20 @@ mod edge_case_tests {
                 end: Duration::from_secs(5),
                 text: "First".to_string(),
                 speaker: None,
+                kind: SegmentKind::Speech,
                 confidence: None,
                 words: None,
+                original_text: None,
             },
             TranscriptSegment {
                 start: Duration::from_secs(4), // Overlaps!
                 end: Duration::from_secs(8),
                 text: "Second".to_string(),
                 speaker: None,
+                kind: SegmentKind::Speech,
                 confidence: None,
                 words: None,
+                original_text: None,
             },
         ];
 
@@ -562,8 +780,10 @@ mod edge_case_tests {
             end: Duration::from_secs(3),
             text: "  trimmed text  ".to_string(),
             speaker: None,
+            kind: SegmentKind::Speech,
             confidence: None,
             words: None,
+            original_text: None,
         }];
 
         let entries = quick_convert(segments);
@@ -580,21 +800,25 @@ mod edge_case_tests {
                 end: Duration::from_secs(3),
                 text: "日本語テスト".to_string(),
                 speaker: None,
+                kind: SegmentKind::Speech,
                 confidence: None,
                 words: None,
+                original_text: None,
             },
             TranscriptSegment {
                 start: Duration::from_secs(4),
                 end: Duration::from_secs(7),
                 text: "🎬 Emoji support".to_string(),
                 speaker: None,
+                kind: SegmentKind::Speech,
                 confidence: None,
                 words: None,
+                original_text: None,
             },
         ];
 
         let entries = quick_convert(segments);
-        let formatter = SrtFormatter;
+        let formatter = SrtFormatter::default();
         let output = formatter.format(&entries);
 
         assert!(output.contains("日本語テスト"));
@@ -610,8 +834,10 @@ mod edge_case_tests {
             end: Duration::from_secs(10),
             text: long_text.to_string(),
             speaker: None,
+            kind: SegmentKind::Speech,
             confidence: None,
             words: None,
+            original_text: None,
         }];
 
         let config = PostProcessConfig {
@@ -640,8 +866,10 @@ mod transcript_tests {
             end: Duration::from_secs(5),
             text: "Test segment.".to_string(),
             speaker: None,
+            kind: SegmentKind::Speech,
             confidence: Some(0.95),
             words: None,
+            original_text: None,
         };
 
         let transcript = Transcript::single(segment);
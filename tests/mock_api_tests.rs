@@ -73,9 +73,6 @@ mod orchestrator_tests {
     async fn test_orchestrator_creation() {
         let client: Box<dyn Transcriber> = Box::new(GeminiClient::new("test-api-key".to_string()));
         let _orchestrator = TranscriptionOrchestrator::new(client, 4);
-
-        // Just verify it compiles and creates successfully
-        assert!(true);
     }
 
     #[tokio::test]
@@ -95,8 +92,6 @@ mod orchestrator_tests {
     async fn test_orchestrator_with_progress_disabled() {
         let client: Box<dyn Transcriber> = Box::new(GeminiClient::new("test-api-key".to_string()));
         let _orchestrator = TranscriptionOrchestrator::new(client, 4).with_progress(false);
-
-        assert!(true);
     }
 }
 
@@ -105,7 +100,7 @@ mod orchestrator_tests {
 // ============================================================================
 
 mod response_parsing_tests {
-    use autosub::transcribe::TranscriptSegment;
+    use autosub::transcribe::{SegmentKind, TranscriptSegment};
     use std::time::Duration;
 
     #[test]
@@ -115,8 +110,10 @@ mod response_parsing_tests {
             end: Duration::from_secs(5),
             text: "Hello world".to_string(),
             speaker: Some("Speaker 1".to_string()),
+            kind: SegmentKind::Speech,
             confidence: Some(0.95),
             words: None,
+            original_text: None,
         };
 
         assert_eq!(segment.text, "Hello world");
@@ -131,8 +128,10 @@ mod response_parsing_tests {
             end: Duration::from_secs(3),
             text: "Simple text".to_string(),
             speaker: None,
+            kind: SegmentKind::Speech,
             confidence: None,
             words: None,
+            original_text: None,
         };
 
         assert_eq!(segment.text, "Simple text");
@@ -152,8 +151,10 @@ mod factory_tests {
 
     #[test]
     fn test_create_gemini_transcriber() {
-        let mut config = Config::default();
-        config.gemini_api_key = Some("test-key".to_string());
+        let config = Config {
+            gemini_api_key: Some("test-key".to_string()),
+            ..Default::default()
+        };
 
         let transcriber = create_transcriber(&config).unwrap();
         assert_eq!(transcriber.name(), "Google Gemini");
@@ -161,20 +162,570 @@ mod factory_tests {
 
     #[test]
     fn test_create_transcriber_missing_gemini_key() {
-        let mut config = Config::default();
-        config.gemini_api_key = None;
+        let config = Config {
+            gemini_api_key: None,
+            ..Default::default()
+        };
 
         let result = create_transcriber(&config);
         assert!(result.is_err());
     }
 }
 
+// ============================================================================
+// Provider Fallback Tests
+// ============================================================================
+
+mod fallback_tests {
+    use super::*;
+    use autosub::transcribe::FallbackTranscriber;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    async fn test_fallback_to_second_provider() {
+        let primary = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(403).set_body_string("quota exceeded"))
+            .expect(1)
+            .mount(&primary)
+            .await;
+
+        let secondary = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1beta/models/gemini-2.0-flash:generateContent"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                r#"{"candidates":[{"content":{"parts":[{"text":"[00:00] Hello from backup"}]},"finishReason":"STOP"}]}"#,
+            ))
+            .expect(1)
+            .mount(&secondary)
+            .await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let audio = dir.path().join("chunk_0000.wav");
+        std::fs::write(&audio, b"RIFF0000WAVE").unwrap();
+        let chunk = AudioChunk {
+            region: SpeechRegion {
+                start: Duration::from_secs(0),
+                end: Duration::from_secs(5),
+            },
+            path: audio,
+            index: 0,
+        };
+
+        let chain = FallbackTranscriber::new(vec![
+            Box::new(GeminiClient::new("key-a".to_string()).with_base_url(primary.uri())),
+            Box::new(
+                GeminiClient::new("key-b".to_string())
+                    .with_model("gemini-2.0-flash")
+                    .with_base_url(secondary.uri()),
+            ),
+        ])
+        .unwrap();
+
+        let transcript = chain.transcribe(&chunk).await.unwrap();
+        assert_eq!(transcript.segments[0].text, "Hello from backup");
+        assert_eq!(
+            transcript.provider.as_deref(),
+            Some("gemini/gemini-2.0-flash")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_bad_request_fails_without_fallback() {
+        let primary = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(400).set_body_string("API key not valid"))
+            .expect(1)
+            .mount(&primary)
+            .await;
+
+        let secondary = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(0)
+            .mount(&secondary)
+            .await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let audio = dir.path().join("chunk_0000.wav");
+        std::fs::write(&audio, b"RIFF0000WAVE").unwrap();
+        let chunk = AudioChunk {
+            region: SpeechRegion {
+                start: Duration::from_secs(0),
+                end: Duration::from_secs(5),
+            },
+            path: audio,
+            index: 0,
+        };
+
+        let chain = FallbackTranscriber::new(vec![
+            Box::new(GeminiClient::new("bad-key".to_string()).with_base_url(primary.uri())),
+            Box::new(GeminiClient::new("key-b".to_string()).with_base_url(secondary.uri())),
+        ])
+        .unwrap();
+
+        let err = chain.transcribe(&chunk).await.unwrap_err();
+        assert!(err.to_string().contains("API key not valid"));
+    }
+}
+
+// ============================================================================
+// Shared HTTP Client Tests
+// ============================================================================
+
+mod http_tests {
+    use super::*;
+    use autosub::http::{HttpConfig, DEFAULT_USER_AGENT};
+    use wiremock::matchers::{header, method};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn chunk(dir: &std::path::Path) -> AudioChunk {
+        let audio = dir.join("chunk_0000.wav");
+        std::fs::write(&audio, b"RIFF0000WAVE").unwrap();
+        AudioChunk {
+            region: SpeechRegion {
+                start: Duration::from_secs(0),
+                end: Duration::from_secs(5),
+            },
+            path: audio,
+            index: 0,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_requests_send_user_agent() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(header("user-agent", DEFAULT_USER_AGENT))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                r#"{"candidates":[{"content":{"parts":[{"text":"[00:00] Hi"}]},"finishReason":"STOP"}]}"#,
+            ))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let client = GeminiClient::new("key".to_string())
+            .with_http_client(HttpConfig::default().build_client().unwrap())
+            .with_base_url(server.uri());
+        let transcript = client.transcribe(&chunk(dir.path())).await.unwrap();
+        assert_eq!(transcript.segments[0].text, "Hi");
+    }
+
+    #[tokio::test]
+    async fn test_hung_request_times_out() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_delay(Duration::from_secs(30)))
+            .mount(&server)
+            .await;
+
+        let http = HttpConfig {
+            request_timeout_secs: 1,
+            ..Default::default()
+        };
+        let dir = tempfile::tempdir().unwrap();
+        let client = GeminiClient::new("key".to_string())
+            .with_http_client(http.build_client().unwrap())
+            .with_base_url(server.uri());
+
+        let started = std::time::Instant::now();
+        assert!(client.transcribe(&chunk(dir.path())).await.is_err());
+        assert!(started.elapsed() < Duration::from_secs(20));
+    }
+}
+
+// ============================================================================
+// Record and Replay Tests
+// ============================================================================
+
+mod record_replay_tests {
+    use super::*;
+    use autosub::record::RecordStore;
+    use autosub::transcribe::{RecordingTranscriber, ReplayTranscriber};
+    use wiremock::matchers::method;
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    async fn test_replay_matches_recorded_gemini_response() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                r#"{"candidates":[{"content":{"parts":[{"text":"[00:01] Recorded once"}]},"finishReason":"STOP"}]}"#,
+            ))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let audio = dir.path().join("chunk_0000.wav");
+        std::fs::write(&audio, b"RIFF0000WAVE").unwrap();
+        let chunk = AudioChunk {
+            region: SpeechRegion {
+                start: Duration::from_secs(30),
+                end: Duration::from_secs(35),
+            },
+            path: audio,
+            index: 0,
+        };
+        let store = RecordStore::new(dir.path().join("recording"));
+
+        let recorder = RecordingTranscriber::new(
+            Box::new(GeminiClient::new("key".to_string()).with_base_url(server.uri())),
+            store.clone(),
+        );
+        let live = recorder.transcribe(&chunk).await.unwrap();
+
+        // The mock only answers once, so this must come from the recording
+        let replayed = ReplayTranscriber::new(store)
+            .transcribe(&chunk)
+            .await
+            .unwrap();
+        assert_eq!(replayed.segments[0].text, live.segments[0].text);
+        assert_eq!(replayed.segments[0].start, Duration::from_secs(31));
+        assert_eq!(replayed.provider, live.provider);
+    }
+}
+
+// ============================================================================
+// API Key Rotation Tests
+// ============================================================================
+
+mod key_rotation_tests {
+    use super::*;
+    use autosub::keys::ApiKeyPool;
+    use wiremock::matchers::{method, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    async fn test_quota_error_moves_to_next_key() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(query_param("key", "key-a-1111"))
+            .respond_with(
+                ResponseTemplate::new(429)
+                    .set_body_string(r#"{"error":{"code":429,"status":"RESOURCE_EXHAUSTED"}}"#),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(query_param("key", "key-b-2222"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                r#"{"candidates":[{"content":{"parts":[{"text":"[00:00] Hello"}]},"finishReason":"STOP"}],"usageMetadata":{"promptTokenCount":100,"candidatesTokenCount":10}}"#,
+            ))
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let audio = dir.path().join("chunk_0000.wav");
+        std::fs::write(&audio, b"RIFF0000WAVE").unwrap();
+        let chunk = AudioChunk {
+            region: SpeechRegion {
+                start: Duration::from_secs(0),
+                end: Duration::from_secs(5),
+            },
+            path: audio,
+            index: 0,
+        };
+
+        let pool = ApiKeyPool::parse("key-a-1111,key-b-2222");
+        let client = GeminiClient::new(String::new())
+            .with_key_pool(pool.clone())
+            .with_base_url(server.uri());
+
+        // The exhausted key rests, so the second request goes straight to the other one
+        for _ in 0..2 {
+            let transcript = client.transcribe(&chunk).await.unwrap();
+            assert_eq!(transcript.segments[0].text, "Hello");
+        }
+
+        let usage = pool.usage();
+        assert_eq!(usage[0].key, "...1111");
+        assert_eq!(usage[0].quota_errors, 1);
+        assert_eq!(usage[0].usage.requests, 1);
+        assert_eq!(usage[1].usage.requests, 2);
+        assert_eq!(usage[1].usage.input_tokens, 200);
+    }
+}
+
+// ============================================================================
+// Structured Translation Tests
+// ============================================================================
+
+mod translation_tests {
+    use autosub::translate::{GeminiTranslator, TranslationOrchestrator, Translator};
+    use wiremock::matchers::{body_string_contains, method};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn reply(json: &str) -> ResponseTemplate {
+        let body = serde_json::json!({
+            "candidates": [{"content": {"parts": [{"text": json}]}}]
+        });
+        ResponseTemplate::new(200).set_body_json(body)
+    }
+
+    #[tokio::test]
+    async fn test_missing_cue_is_requested_again() {
+        let server = MockServer::start().await;
+        // The first reply drops cue 2
+        Mock::given(method("POST"))
+            .and(body_string_contains("[1] Hello"))
+            .and(body_string_contains("responseSchema"))
+            .respond_with(reply(
+                r#"[{"id": 1, "text": "Hola"}, {"id": 3, "text": "Gracias"}]"#,
+            ))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(body_string_contains("[2] Goodbye"))
+            .respond_with(reply(r#"[{"id": 2, "text": "Adiós"}]"#))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let translator = GeminiTranslator::new("key".to_string()).with_base_url(server.uri());
+        let translations = translator
+            .translate_batch(&["Hello", "Goodbye", "Thanks"], "es")
+            .await
+            .unwrap();
+
+        assert_eq!(translations, ["Hola", "Adiós", "Gracias"]);
+    }
+
+    #[tokio::test]
+    async fn test_cue_never_returned_fails() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(reply(r#"[{"id": 1, "text": "Hola"}]"#))
+            .expect(3)
+            .mount(&server)
+            .await;
+
+        let translator = GeminiTranslator::new("key".to_string()).with_base_url(server.uri());
+        let err = translator
+            .translate_batch(&["Hello", "Goodbye"], "es")
+            .await
+            .unwrap_err();
+
+        assert!(err.to_string().contains("missing cue(s) 2"));
+    }
+
+    #[tokio::test]
+    async fn test_translation_over_budget_is_condensed() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(body_string_contains("Lines to translate"))
+            .and(body_string_contains("[2]: at most 10 characters"))
+            .respond_with(reply(r#"[{"id": 1, "text": "Hola"}, {"id": 2, "text": "Nos vemos mañana por la tarde"}]"#))
+            .expect(1)
+            .mount(&server)
+            .await;
+        // Only the cue over its budget is sent back to be shortened
+        Mock::given(method("POST"))
+            .and(body_string_contains("too long to read"))
+            .and(body_string_contains("[1] at most 10 characters (now 29)"))
+            .respond_with(reply(r#"[{"id": 1, "text": "Nos vemos"}]"#))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let translator = GeminiTranslator::new("key".to_string()).with_base_url(server.uri());
+        let orchestrator =
+            TranslationOrchestrator::new(Box::new(translator), 1).with_budgets(vec![10, 10]);
+        let texts = ["Hi".to_string(), "See you tomorrow afternoon".to_string()];
+        let translations = orchestrator.translate(&texts, "es").await.unwrap();
+
+        assert_eq!(translations, ["Hola", "Nos vemos"]);
+        assert_eq!(orchestrator.condensed(), 1);
+    }
+}
+
+// ============================================================================
+// Translation Backend Tests
+// ============================================================================
+
+mod translation_backend_tests {
+    use autosub::config::{Config, TranslatorConfig};
+    use autosub::translate::{
+        create_translator_with, DeepLTranslator, LibreTranslateTranslator, OpenAiTranslator,
+        TranslationContext, Translator, TranslatorOptions,
+    };
+    use wiremock::matchers::{body_partial_json, body_string_contains, header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn chat_reply(content: &str) -> ResponseTemplate {
+        ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "choices": [{"message": {"role": "assistant", "content": content}}],
+            "usage": {"prompt_tokens": 120, "completion_tokens": 30}
+        }))
+    }
+
+    #[tokio::test]
+    async fn test_deepl_translates_with_context() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v2/translate"))
+            .and(header("Authorization", "DeepL-Auth-Key secret:fx"))
+            .and(body_partial_json(serde_json::json!({
+                "text": ["Hello", "Goodbye"],
+                "target_lang": "DE",
+                "source_lang": "EN",
+                "context": "Where is Anna?"
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "translations": [
+                    {"detected_source_language": "EN", "text": "Hallo"},
+                    {"detected_source_language": "EN", "text": "Auf Wiedersehen"}
+                ]
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let translator = DeepLTranslator::new("secret:fx".to_string())
+            .with_base_url(server.uri())
+            .with_source_language("en");
+        let context = TranslationContext {
+            before: vec!["Where is Anna?".to_string()],
+            ..Default::default()
+        };
+        let translations = translator
+            .translate_batch_with_context(&["Hello", "Goodbye"], "de", &context)
+            .await
+            .unwrap();
+
+        assert_eq!(translations, ["Hallo", "Auf Wiedersehen"]);
+        assert!(translator.usage().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_deepl_quota_error() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(456).set_body_string("Quota exceeded"))
+            .mount(&server)
+            .await;
+
+        let translator = DeepLTranslator::new("secret".to_string()).with_base_url(server.uri());
+        let err = translator.translate("Hello", "de").await.unwrap_err();
+        assert!(err.to_string().contains("Quota exceeded"));
+    }
+
+    #[tokio::test]
+    async fn test_libretranslate_batch() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/translate"))
+            .and(body_partial_json(serde_json::json!({
+                "q": ["Hello", "Goodbye"],
+                "source": "auto",
+                "target": "es",
+                "format": "text"
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "translatedText": ["Hola", "Adiós"]
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let translator = LibreTranslateTranslator::new().with_base_url(server.uri());
+        let translations = translator
+            .translate_batch(&["Hello", "Goodbye"], "es")
+            .await
+            .unwrap();
+        assert_eq!(translations, ["Hola", "Adiós"]);
+    }
+
+    #[tokio::test]
+    async fn test_libretranslate_error_message() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(
+                ResponseTemplate::new(400)
+                    .set_body_json(serde_json::json!({"error": "xx is not supported"})),
+            )
+            .mount(&server)
+            .await;
+
+        let translator = LibreTranslateTranslator::new()
+            .with_base_url(server.uri())
+            .with_api_key("key");
+        let err = translator.translate("Hello", "xx").await.unwrap_err();
+        assert!(err.to_string().contains("xx is not supported"));
+    }
+
+    #[tokio::test]
+    async fn test_openai_chat_translates_numbered_cues() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/chat/completions"))
+            .and(header("Authorization", "Bearer sk-test"))
+            .and(body_partial_json(
+                serde_json::json!({"model": "gpt-4o-mini"}),
+            ))
+            .and(body_string_contains("[1] Hello"))
+            .respond_with(chat_reply(
+                r#"[{"id": 1, "text": "Bonjour"}, {"id": 2, "text": "Au revoir"}]"#,
+            ))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let translator = OpenAiTranslator::new(Some("sk-test".to_string()))
+            .with_base_url(format!("{}/v1", server.uri()));
+        let translations = translator
+            .translate_batch(&["Hello", "Goodbye"], "fr")
+            .await
+            .unwrap();
+
+        assert_eq!(translations, ["Bonjour", "Au revoir"]);
+        let usage = translator.usage();
+        assert_eq!(usage[0].model, "gpt-4o-mini");
+        assert_eq!(
+            (usage[0].usage.input_tokens, usage[0].usage.output_tokens),
+            (120, 30)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_translator_selected_by_name_uses_base_url() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/chat/completions"))
+            .and(body_partial_json(serde_json::json!({"model": "llama3.1"})))
+            .respond_with(chat_reply(
+                "```json\n[{\"id\": 1, \"text\": \"Hallo\"}]\n```",
+            ))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let config = Config {
+            translator: Some(TranslatorConfig {
+                model: Some("llama3.1".to_string()),
+                base_url: Some(format!("{}/v1", server.uri())),
+                ..TranslatorConfig::named("openai")
+            }),
+            ..Default::default()
+        };
+        let translator = create_translator_with(&config, &TranslatorOptions::default()).unwrap();
+
+        assert_eq!(translator.name(), "openai");
+        assert_eq!(translator.translate("Hello", "de").await.unwrap(), "Hallo");
+    }
+}
+
 // ============================================================================
 // Transcription Result Tests
 // ============================================================================
 
 mod result_tests {
-    use autosub::transcribe::{TranscriptSegment, TranscriptionResult};
+    use autosub::transcribe::{SegmentKind, TranscriptSegment, TranscriptionResult};
     use std::time::Duration;
 
     #[test]
@@ -197,16 +748,20 @@ mod result_tests {
                 end: Duration::from_secs(5),
                 text: "First".to_string(),
                 speaker: None,
+                kind: SegmentKind::Speech,
                 confidence: None,
                 words: None,
+                original_text: None,
             },
             TranscriptSegment {
                 start: Duration::from_secs(5),
                 end: Duration::from_secs(10),
                 text: "Second".to_string(),
                 speaker: None,
+                kind: SegmentKind::Speech,
                 confidence: None,
                 words: None,
+                original_text: None,
             },
         ];
 